@@ -0,0 +1,39 @@
+use crate::{state::AppState, verification::verify_bearer_token};
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use axum_macros::debug_handler;
+use github_notification::{
+    env::{get_digest_trigger_token, get_notifier_names},
+    notifier::{create_notifiers, create_team_digest, failure_message, notify_all},
+};
+
+// deliver the digest of the team by every configured notifier, e.g. from a scheduler
+// POST /digest with "Authorization: Bearer <DIGEST_TRIGGER_TOKEN>"
+// the failures are reported for each notifier, after all of them have been tried
+#[debug_handler]
+pub async fn deliver_digest_handler(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    // the route doesn't exist without a token, and a wrong token looks the same
+    match get_digest_trigger_token() {
+        Some(token) if verify_bearer_token(&headers, &token) => {}
+        _ => return StatusCode::NOT_FOUND.into_response(),
+    }
+
+    let (digest, format) =
+        create_team_digest("https://api.github.com", state.template.clone()).await;
+    let notifiers = create_notifiers(&get_notifier_names(), &format);
+    if notifiers.is_empty() {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "No Notifier is configured",
+        )
+            .into_response();
+    }
+    let reports = notify_all(&notifiers, &digest).await;
+    match failure_message(&reports) {
+        Some(message) => (StatusCode::INTERNAL_SERVER_ERROR, message).into_response(),
+        None => "ok".into_response(),
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{
+    state::AppState,
+    verification::{get_retry_num, verify_slack_request},
+};
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+};
+use axum_macros::debug_handler;
+use github_notification::slack::events::{SlackEventsPayload, SLACK_RETRY_NUM_HEADER};
+
+// receive events from Slack Events API
+// Slack expects a response within 3 seconds, so events are handled in the background
+#[debug_handler]
+pub async fn slack_events_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: String,
+) -> impl IntoResponse {
+    if let Err(e) = verify_slack_request(&headers, &body) {
+        return e;
+    }
+
+    let payload = match serde_json::from_str::<SlackEventsPayload>(&body) {
+        Ok(payload) => payload,
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, format!("Invalid payload: {e}"));
+        }
+    };
+
+    match payload {
+        SlackEventsPayload::UrlVerification { challenge } => (StatusCode::OK, challenge),
+        SlackEventsPayload::EventCallback(callback) => {
+            let retry_num = get_retry_num(&headers, SLACK_RETRY_NUM_HEADER);
+            let now = chrono::Utc::now().timestamp();
+            let is_duplicate = state.event_deduplicator.lock().unwrap().is_duplicate(
+                &callback.event_id,
+                retry_num,
+                now,
+            );
+            if !is_duplicate {
+                let dispatcher = state.event_dispatcher.clone();
+                tokio::spawn(async move {
+                    dispatcher.dispatch(callback).await;
+                });
+            }
+            (StatusCode::OK, "ok".to_string())
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use crate::state::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use axum_macros::debug_handler;
+use github_notification::{
+    env::{
+        get_feed_tokens, get_github_personal_access_token, get_locale_settings, get_slack_user_map,
+        get_state_dir,
+    },
+    feed::{
+        atom::{create_atom_feed, ATOM_CONTENT_TYPE},
+        etag_matches, feed_etag, get_feed_issues,
+        ical::{create_icalendar, ICALENDAR_CONTENT_TYPE},
+        verify_feed_token,
+    },
+    github::sort_issues,
+    slack::user_map::{resolve_slack_user_id, SLACK_USER_MAP_FILE_NAME},
+    snooze::{SnoozeScope, SnoozedIssues, SNOOZED_ISSUES_FILE_NAME},
+    store::{load_json, state_file_path},
+};
+use std::collections::HashMap;
+
+// feed readers poll often, and GitHub is asked again only after this
+const FEED_CACHE_CONTROL: &str = "private, max-age=300";
+
+// serve the issues assigned to a GitHub user, e.g. GET /feeds/octocat.atom?token=...
+// the feed is the same as the digest, without the snoozed issues and grouped by priority
+// GET /feeds/octocat.ics?token=... is the calendar of their due dates
+#[debug_handler]
+pub async fn feed_handler(
+    State(state): State<AppState>,
+    Path(file_name): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    // an unknown user and a wrong token look the same, so that users can't be probed
+    let (login, extension) = match file_name.rsplit_once('.') {
+        Some((login, extension @ ("atom" | "ics")))
+            if verify_feed_token(
+                &get_feed_tokens(),
+                login,
+                query.get("token").map(String::as_str),
+            ) =>
+        {
+            (login, extension)
+        }
+        _ => return StatusCode::NOT_FOUND.into_response(),
+    };
+
+    let now = chrono::Utc::now();
+    let (issues, github_etag) = match get_feed_issues(
+        "https://api.github.com",
+        &get_github_personal_access_token(),
+        login,
+        &state.feed_cache,
+    )
+    .await
+    {
+        Ok(result) => result,
+        Err(e) => return (StatusCode::BAD_GATEWAY, e.message).into_response(),
+    };
+    // the issues the Slack user of the login snoozed are left out
+    // nothing is snoozed for a login without a Slack user
+    let state_dir = get_state_dir();
+    let slack_user_id = resolve_slack_user_id(
+        login,
+        &get_slack_user_map(),
+        &state_file_path(&state_dir, SLACK_USER_MAP_FILE_NAME),
+    );
+    let issues = match slack_user_id {
+        Some(slack_user_id) => {
+            let snoozed_issues: SnoozedIssues =
+                load_json(&state_file_path(&state_dir, SNOOZED_ISSUES_FILE_NAME));
+            snoozed_issues.filter_issues(
+                &SnoozeScope::User(slack_user_id),
+                Ok(issues),
+                now.timestamp(),
+            )
+        }
+        None => Ok(issues),
+    };
+    let sorted_issues = match sort_issues(issues) {
+        Ok(sorted_issues) => sorted_issues,
+        Err(e) => return (StatusCode::BAD_GATEWAY, e.message).into_response(),
+    };
+    let locale = get_locale_settings().default;
+
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(FEED_CACHE_CONTROL),
+    );
+    if let Some(github_etag) = github_etag {
+        let shown_issue_ids: Vec<i64> = sorted_issues
+            .groups()
+            .iter()
+            .flat_map(|(_, issues)| issues.iter().map(|issue| issue.id))
+            .collect();
+        let etag = feed_etag(
+            &github_etag,
+            &format!("{extension}:{}", locale.code()),
+            now.date_naive(),
+            &shown_issue_ids,
+        );
+        let not_modified = headers
+            .get(header::IF_NONE_MATCH)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|if_none_match| etag_matches(if_none_match, &etag));
+        if let Ok(value) = HeaderValue::from_str(&etag) {
+            response_headers.insert(header::ETAG, value);
+        }
+        if not_modified {
+            return (StatusCode::NOT_MODIFIED, response_headers).into_response();
+        }
+    }
+    let (content_type, feed) = match extension {
+        "ics" => (
+            ICALENDAR_CONTENT_TYPE,
+            create_icalendar(&sorted_issues, locale, now),
+        ),
+        _ => (
+            ATOM_CONTENT_TYPE,
+            create_atom_feed(login, &sorted_issues, locale, state.template.style(), now),
+        ),
+    };
+    response_headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    (StatusCode::OK, response_headers, feed).into_response()
+}
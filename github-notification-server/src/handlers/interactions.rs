@@ -0,0 +1,90 @@
+use crate::{state::AppState, verification::verify_slack_request};
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_macros::debug_handler;
+use github_notification::slack::{
+    interactions::{handle_block_actions, parse_interaction_payload, SlackInteractionPayload},
+    modal::{
+        open_create_issue_modal, parse_create_issue_submission, submit_create_issue,
+        CREATE_ISSUE_MODAL_CALLBACK_ID, CREATE_ISSUE_SHORTCUT_CALLBACK_ID,
+    },
+};
+
+// receive interactions (button clicks, menu selections) from Slack
+// the original message is updated through response_url after the action is done
+#[debug_handler]
+pub async fn slack_interactions_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    if let Err(e) = verify_slack_request(&headers, &body) {
+        return e.into_response();
+    }
+
+    let payload = match parse_interaction_payload(&body) {
+        Ok(payload) => payload,
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, e).into_response();
+        }
+    };
+    let context = match state.slack_context {
+        Some(context) => context,
+        None => {
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Slack app is not configured".to_string(),
+            )
+                .into_response();
+        }
+    };
+
+    match payload {
+        SlackInteractionPayload::BlockActions(payload) => {
+            tokio::spawn(async move {
+                handle_block_actions(&context, payload).await;
+            });
+        }
+        SlackInteractionPayload::MessageAction(payload) => {
+            if payload.callback_id == CREATE_ISSUE_SHORTCUT_CALLBACK_ID {
+                // the modal is opened first thing, trigger_id expires 3 seconds after the shortcut
+                tokio::spawn(async move {
+                    let _ = open_create_issue_modal(
+                        &context,
+                        &payload.trigger_id,
+                        &payload.channel.id,
+                        &payload.user.id,
+                        Some(&payload.message.text),
+                    )
+                    .await;
+                });
+            }
+        }
+        SlackInteractionPayload::ViewSubmission(payload) => {
+            if payload.view.callback_id == CREATE_ISSUE_MODAL_CALLBACK_ID {
+                let submission = match parse_create_issue_submission(&payload.view) {
+                    Ok(submission) => submission,
+                    // show the errors in the modal
+                    Err(errors) => {
+                        let body = serde_json::json!({
+                            "response_action": "errors",
+                            "errors": errors,
+                        });
+                        return Json(body).into_response();
+                    }
+                };
+                tokio::spawn(async move {
+                    submit_create_issue(&context, &payload.user, &submission).await;
+                });
+            }
+        }
+        SlackInteractionPayload::Unknown => {
+            println!("Unknown Slack Interaction");
+        }
+    }
+    (StatusCode::OK, String::new()).into_response()
+}
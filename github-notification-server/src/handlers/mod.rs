@@ -1,2 +1,7 @@
+pub mod digest;
+pub mod events;
+pub mod feed;
 pub mod health_check;
+pub mod interactions;
 pub mod notification;
+pub mod schema;
@@ -1,21 +1,41 @@
-use axum::{http::StatusCode, response::IntoResponse};
+use crate::state::AppState;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
 use axum_macros::debug_handler;
 use github_notification::{
-    env::{get_github_personal_access_token, get_slack_webhook_url_from_env},
-    github::{get_my_issues, sort_issues},
+    env::{
+        get_email_user_recipients, get_github_personal_access_token, get_locale_settings,
+        get_notifier_names, get_slack_bot_token, get_slack_user_github_tokens, get_slack_user_map,
+        get_state_dir,
+    },
+    i18n::text,
+    notifier::{create_personal_email_notifier, Notifier},
     slack::{
-        notification::notify_by_slack,
-        payload::create_payload_for_slack,
-        slash::{validate_slash_command_payload, SlashCommandPayload},
+        locale::resolve_user_locale,
+        modal::open_create_issue_modal,
+        personal::{
+            create_personal_digest, create_personal_digest_response, onboarding_text,
+            resolve_digest_user,
+        },
+        slash::{
+            post_command_response, post_command_text, validate_slash_command_payload,
+            SlackCommandRequest, SlashCommandPayload,
+        },
+        user_map::{handle_link_command, SLACK_USER_MAP_FILE_NAME},
     },
+    store::state_file_path,
 };
 
 // receive request from slack
 // respond with text
 #[debug_handler]
 pub async fn create_notification_handler(
+    State(state): State<AppState>,
     form: axum::extract::Form<SlashCommandPayload>,
-) -> impl IntoResponse {
+) -> Response {
     // check token, command, text
     let req = match validate_slash_command_payload(&form) {
         Ok(req) => {
@@ -23,31 +43,117 @@ pub async fn create_notification_handler(
             req
         }
         Err(e) => {
-            return (StatusCode::BAD_REQUEST, e);
+            return (StatusCode::BAD_REQUEST, e).into_response();
         }
     };
     // branch by command
-    // if command is "help", print help message
-    if req.text.as_str() == "help" {
-        let message =
-            "Please provide command like \"health-check\", \"create-notification\"".to_string();
-        return (StatusCode::OK, message);
-    }
     // if command is "health-check", print ok
     if req.text.as_str() == "health-check" {
         let message = "Health Check OK".to_string();
-        return (StatusCode::OK, message);
+        return (StatusCode::OK, message).into_response();
+    }
+    // if command is "new", open the modal to create an issue
+    // the modal is opened first thing, trigger_id expires 3 seconds after the command
+    if req.text.as_str() == "new" {
+        let context = match state.slack_context {
+            Some(context) => context,
+            None => {
+                let locale =
+                    get_locale_settings().user_locale(&form.user_id, None, Some(&form.channel_id));
+                let message = text(locale, "command.not_configured");
+                return (StatusCode::OK, message).into_response();
+            }
+        };
+        tokio::spawn(async move {
+            if let Err(e) = open_create_issue_modal(
+                &context,
+                &form.trigger_id,
+                &form.channel_id,
+                &form.user_id,
+                None,
+            )
+            .await
+            {
+                post_command_text(&form.response_url, &e.message).await;
+            }
+        });
+        return (StatusCode::OK, String::new()).into_response();
     }
 
-    let token = get_github_personal_access_token();
-    let webhook_url = get_slack_webhook_url_from_env();
+    // Slack waits only 3 seconds for the response, so the command is acknowledged at once
+    // and the answer is posted to the response_url of the command
+    let form = form.0;
+    tokio::spawn(async move {
+        respond_to_command(state, form, req).await;
+    });
+    (StatusCode::OK, String::new()).into_response()
+}
 
-    let github_api_addr = "https://api.github.com".to_string();
-    let my_issues = get_my_issues(github_api_addr, token).await;
-    let sorted_issues = sort_issues(my_issues);
-    let payload = create_payload_for_slack(sorted_issues);
+// answer "help", "link" and the digest, which only the user who runs the command sees
+async fn respond_to_command(state: AppState, form: SlashCommandPayload, req: SlackCommandRequest) {
+    // the responses and the digest are for the user who runs the command
+    let locale = resolve_user_locale(
+        "https://slack.com/api",
+        get_slack_bot_token().as_deref(),
+        &get_locale_settings(),
+        &form.user_id,
+        Some(&form.channel_id),
+    )
+    .await;
+    // if command is "help", print help message
+    if req.text.as_str() == "help" {
+        post_command_text(&form.response_url, &text(locale, "command.help")).await;
+        return;
+    }
+
+    // if command is "link", link the user to a GitHub user
+    if req.text.as_str() == "link" {
+        let user_map_path = state_file_path(&get_state_dir(), SLACK_USER_MAP_FILE_NAME);
+        let message = handle_link_command(&user_map_path, &form.user_id, &req.args, locale);
+        post_command_text(&form.response_url, &message).await;
+        return;
+    }
+
+    // the digest shows the issues of the user who runs the command
+    let user_map_path = state_file_path(&get_state_dir(), SLACK_USER_MAP_FILE_NAME);
+    let digest_user = match resolve_digest_user(
+        &form.user_id,
+        &get_slack_user_github_tokens(),
+        &get_slack_user_map(),
+        &user_map_path,
+    ) {
+        Some(digest_user) => digest_user,
+        None => {
+            post_command_text(&form.response_url, &onboarding_text(locale)).await;
+            return;
+        }
+    };
 
-    // notify by slack
-    notify_by_slack(webhook_url, payload).await;
-    (StatusCode::OK, "ok".to_string())
+    let (digest, format) = create_personal_digest(
+        "https://api.github.com",
+        &get_github_personal_access_token(),
+        &digest_user,
+        &form.user_id,
+        &get_state_dir(),
+        locale,
+        state.template.clone(),
+    )
+    .await;
+    // the digest is also emailed to the address of the user, and only to it
+    if let Some(address) = get_email_user_recipients().remove(&form.user_id) {
+        if let Some(notifier) = create_personal_email_notifier(&get_notifier_names(), &format) {
+            let digest = digest.clone().with_recipients(vec![address]);
+            tokio::spawn(async move {
+                if let Err(e) = notifier.notify(&digest).await {
+                    println!("{}", e.message);
+                }
+            });
+        }
+    }
+    // only the user sees the digest, it is never sent to the shared notifiers
+    post_command_response(
+        &form.response_url,
+        &create_personal_digest_response(&digest, &format),
+    )
+    .await;
 }
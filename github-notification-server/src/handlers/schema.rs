@@ -0,0 +1,11 @@
+use axum::http::header;
+use axum::response::IntoResponse;
+use github_notification::webhook::payload::schema_json;
+
+// the JSON schema of the webhook payload, for the consumers to validate against
+pub async fn webhook_schema_handler() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "application/schema+json")],
+        schema_json(),
+    )
+}
@@ -1,2 +1,5 @@
 pub mod handlers;
 pub mod logger;
+pub mod schedule;
+pub mod state;
+pub mod verification;
@@ -1,9 +1,25 @@
 use axum::{
-    http::{Request, StatusCode},
+    http::{Request, StatusCode, Uri},
     middleware::Next,
     response::Response,
 };
 
+// the secret token of a feed is in its URL, and must not be in the log
+fn redact_uri(uri: &Uri) -> String {
+    let query = match uri.query() {
+        Some(query) => query,
+        None => return uri.to_string(),
+    };
+    let query: Vec<String> = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some(("token", _)) => "token=REDACTED".to_string(),
+            _ => pair.to_string(),
+        })
+        .collect();
+    format!("{}?{}", uri.path(), query.join("&"))
+}
+
 pub async fn access_log_on_request<B: std::fmt::Debug>(
     req: Request<B>,
     next: Next<B>,
@@ -11,7 +27,7 @@ pub async fn access_log_on_request<B: std::fmt::Debug>(
     println!(
         "{} {} {:?} {:?}",
         req.method(),
-        req.uri(),
+        redact_uri(req.uri()),
         req.headers(),
         req.body()
     );
@@ -2,10 +2,24 @@ use axum::{
     routing::{get, post},
     Router,
 };
-use github_notification::sentry::initialize_sentry;
+use github_notification::{
+    env::{
+        get_digest_style, get_github_personal_access_token, get_locale_settings,
+        get_slack_bot_token, get_slack_digest_template_path, get_slack_user_github_tokens,
+        get_slack_user_map, get_state_dir,
+    },
+    sentry::initialize_sentry,
+    slack::{context::SlackAppContext, template::load_digest_template},
+};
 use github_notification_server::{
-    handlers::{health_check::health_check_handler, notification::create_notification_handler},
+    handlers::{
+        digest::deliver_digest_handler, events::slack_events_handler, feed::feed_handler,
+        health_check::health_check_handler, interactions::slack_interactions_handler,
+        notification::create_notification_handler, schema::webhook_schema_handler,
+    },
     logger::access_log_on_request,
+    schedule::spawn_home_refresh,
+    state::AppState,
 };
 use std::net::SocketAddr;
 use tower::ServiceBuilder;
@@ -15,10 +29,42 @@ use tower_http::trace::TraceLayer;
 async fn main() {
     let _guard = initialize_sentry();
     tracing_subscriber::fmt::init();
+    // the server doesn't start with a broken template
+    let template = match load_digest_template(get_slack_digest_template_path().as_deref()) {
+        Ok(template) => template.with_style(get_digest_style()),
+        Err(err) => {
+            println!("{err}");
+            std::process::exit(1);
+        }
+    };
+    // App Home tabs can be published and events handled only with a bot token
+    let context = get_slack_bot_token().map(|bot_token| {
+        let context = SlackAppContext::new(
+            bot_token,
+            get_github_personal_access_token(),
+            get_state_dir(),
+        )
+        .with_locales(get_locale_settings())
+        .with_static_users(get_slack_user_map())
+        .with_user_tokens(get_slack_user_github_tokens())
+        .with_template(template.clone());
+        spawn_home_refresh(context.clone());
+        context
+    });
+    let state = AppState::new(context, template);
     // build our application with a route
     let app = Router::new()
         .route("/", get(health_check_handler))
         .route("/create-notification", post(create_notification_handler))
+        .route("/digest", post(deliver_digest_handler))
+        .route("/slack/events", post(slack_events_handler))
+        .route("/slack/interactions", post(slack_interactions_handler))
+        .route("/feeds/:file_name", get(feed_handler))
+        .route(
+            "/schema/webhook-payload.v1.json",
+            get(webhook_schema_handler),
+        )
+        .with_state(state)
         .layer(ServiceBuilder::new().layer(TraceLayer::new_for_http()))
         .layer(ServiceBuilder::new().layer(axum::middleware::from_fn(access_log_on_request)));
 
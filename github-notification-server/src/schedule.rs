@@ -0,0 +1,20 @@
+use github_notification::{
+    env::get_slack_home_refresh_interval_secs,
+    slack::{context::SlackAppContext, home::refresh_all_homes},
+};
+use std::time::Duration;
+
+// refresh the App Home tab of every known user periodically
+pub fn spawn_home_refresh(context: SlackAppContext) {
+    let interval_secs = get_slack_home_refresh_interval_secs();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            let failed_user_ids = refresh_all_homes(&context).await;
+            if !failed_user_ids.is_empty() {
+                println!("Refresh Home Error: {:?}", failed_user_ids);
+            }
+        }
+    });
+}
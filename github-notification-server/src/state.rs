@@ -0,0 +1,34 @@
+use github_notification::feed::FeedCache;
+use github_notification::slack::{
+    context::SlackAppContext,
+    events::{default_event_dispatcher, SlackEventDeduplicator, SlackEventDispatcher},
+    template::DigestTemplate,
+};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct AppState {
+    pub slack_context: Option<SlackAppContext>,
+    // for the digest of the slash command, which works without the Slack app
+    pub template: DigestTemplate,
+    pub event_dispatcher: Arc<SlackEventDispatcher>,
+    pub event_deduplicator: Arc<Mutex<SlackEventDeduplicator>>,
+    pub feed_cache: Arc<Mutex<FeedCache>>,
+}
+
+impl AppState {
+    // without a Slack app context, events and interactions are received but not handled
+    pub fn new(context: Option<SlackAppContext>, template: DigestTemplate) -> Self {
+        let event_dispatcher = match context.clone() {
+            Some(context) => default_event_dispatcher(context),
+            None => SlackEventDispatcher::new(),
+        };
+        Self {
+            slack_context: context,
+            template,
+            event_dispatcher: Arc::new(event_dispatcher),
+            event_deduplicator: Arc::new(Mutex::new(SlackEventDeduplicator::default())),
+            feed_cache: Arc::new(Mutex::new(FeedCache::default())),
+        }
+    }
+}
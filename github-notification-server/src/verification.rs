@@ -0,0 +1,48 @@
+use axum::http::{header::AUTHORIZATION, HeaderMap, StatusCode};
+use github_notification::{
+    env::{get_slack_signing_secret, SLACK_SIGNING_SECRET_KEY},
+    slack::signature::{
+        verify_slack_signature, SLACK_REQUEST_TIMESTAMP_HEADER, SLACK_SIGNATURE_HEADER,
+    },
+};
+
+fn get_header<'a>(headers: &'a HeaderMap, key: &str) -> Option<&'a str> {
+    headers.get(key).and_then(|value| value.to_str().ok())
+}
+
+// verify that the request was sent by Slack
+// without the signing secret nothing can be verified, which is a misconfiguration of the server
+pub fn verify_slack_request(headers: &HeaderMap, body: &str) -> Result<(), (StatusCode, String)> {
+    let signing_secret = get_slack_signing_secret().ok_or_else(|| {
+        let message = format!("{SLACK_SIGNING_SECRET_KEY} is not set");
+        println!("{message}");
+        (StatusCode::INTERNAL_SERVER_ERROR, message)
+    })?;
+    let timestamp = get_header(headers, SLACK_REQUEST_TIMESTAMP_HEADER).unwrap_or_default();
+    let signature = get_header(headers, SLACK_SIGNATURE_HEADER).unwrap_or_default();
+    let now = chrono::Utc::now().timestamp();
+    verify_slack_signature(&signing_secret, timestamp, body, signature, now)
+        .map_err(|e| (StatusCode::UNAUTHORIZED, e))
+}
+
+// verify the "Authorization: Bearer <token>" of the request
+// compared in constant time, so that the token can't be guessed byte by byte
+pub fn verify_bearer_token(headers: &HeaderMap, expected: &str) -> bool {
+    match get_header(headers, AUTHORIZATION.as_str())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    {
+        Some(token) => {
+            expected.len() == token.len()
+                && expected
+                    .bytes()
+                    .zip(token.bytes())
+                    .fold(0, |acc, (a, b)| acc | (a ^ b))
+                    == 0
+        }
+        None => false,
+    }
+}
+
+pub fn get_retry_num(headers: &HeaderMap, key: &str) -> Option<u32> {
+    get_header(headers, key).and_then(|value| value.parse().ok())
+}
@@ -0,0 +1,445 @@
+use super::state::DashboardState;
+use crate::digest::Digest;
+use crate::errors::{GitHubApiError, NotifyError};
+use crate::github::{create_gist, create_issue, pin_issue, update_gist, update_issue_body};
+use crate::i18n::{text, text_with, Locale};
+use crate::models::{IssueRef, NewIssue};
+use crate::notifier::{Notifier, DASHBOARD_NOTIFIER};
+use crate::render::markdown::{escape_markdown, MarkdownRenderer};
+use crate::render::render;
+use crate::slack::style::DigestStyle;
+use crate::store::{load_json, save_json};
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+pub const DASHBOARD_GIST_FILE_NAME: &str = "tasks.md";
+// GitHub rejects a longer issue body
+const MAX_ISSUE_BODY_LENGTH: usize = 65536;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DashboardTarget {
+    // an issue in the repository, created on the first run unless the number is given
+    Issue {
+        owner: String,
+        repo: String,
+        number: Option<i64>,
+    },
+    // a secret gist, created on the first run unless the id is given
+    Gist {
+        id: Option<String>,
+    },
+}
+
+impl DashboardTarget {
+    // "owner/repo", "owner/repo#12", "gist" or "gist:{id}"
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value == "gist" {
+            return Some(Self::Gist { id: None });
+        }
+        if let Some(id) = value.strip_prefix("gist:") {
+            return (!id.is_empty()).then(|| Self::Gist {
+                id: Some(id.to_string()),
+            });
+        }
+        if let Some(issue_ref) = IssueRef::parse(value) {
+            return Some(Self::Issue {
+                owner: issue_ref.owner,
+                repo: issue_ref.repo,
+                number: Some(issue_ref.number),
+            });
+        }
+        match value.split_once('/') {
+            Some((owner, repo)) if !owner.is_empty() && !repo.is_empty() && !repo.contains('/') => {
+                Some(Self::Issue {
+                    owner: owner.to_string(),
+                    repo: repo.to_string(),
+                    number: None,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+// keep whole lines, so that no link is cut in the middle
+fn truncate_lines(text: &str, max_len: usize) -> String {
+    if text.len() <= max_len {
+        return text.to_string();
+    }
+    let ellipsis = "…\n";
+    let mut truncated = String::new();
+    for line in text.lines() {
+        if truncated.len() + line.len() + 1 + ellipsis.len() > max_len {
+            break;
+        }
+        truncated.push_str(line);
+        truncated.push('\n');
+    }
+    truncated + ellipsis
+}
+
+// the Markdown digest with the time it was generated, e.g. "_Updated at 2023-01-10 00:00 UTC_"
+pub fn create_dashboard_body(digest: &Digest, locale: Locale, style: &DigestStyle) -> String {
+    let time = digest.generated_at.format("%Y-%m-%d %H:%M UTC").to_string();
+    let footer = format!(
+        "_{}_\n",
+        escape_markdown(&text_with(locale, "dashboard.updated", &[("time", &time)]))
+    );
+    let body = render(&MarkdownRenderer, digest, locale, style);
+    format!(
+        "{}\n{footer}",
+        truncate_lines(&body, MAX_ISSUE_BODY_LENGTH - footer.len() - 1)
+    )
+}
+
+pub struct DashboardNotifier {
+    pub github_api_addr: String,
+    pub token: String,
+    pub target: DashboardTarget,
+    // pin the issue when it is created
+    pub pin: bool,
+    pub state_path: PathBuf,
+    pub locale: Locale,
+    pub style: DigestStyle,
+}
+
+impl DashboardNotifier {
+    fn save_state(&self, state: &DashboardState) {
+        if let Err(err) = save_json(&self.state_path, state) {
+            println!("{err}");
+        }
+    }
+
+    async fn publish_issue(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: Option<i64>,
+        body: &str,
+    ) -> Result<(), NotifyError> {
+        let mut state: DashboardState = load_json(&self.state_path);
+        let issue_ref = match number {
+            Some(number) => Some(IssueRef {
+                owner: owner.to_string(),
+                repo: repo.to_string(),
+                number,
+            }),
+            None => state.issue_in(owner, repo),
+        };
+        if let Some(issue_ref) = issue_ref {
+            match update_issue_body(&self.github_api_addr, &self.token, &issue_ref, body).await? {
+                Some(_) => return Ok(()),
+                // a given issue is never replaced
+                None if number.is_some() => {
+                    return Err(GitHubApiError {
+                        message: format!("Dashboard Error: {issue_ref} is not found"),
+                    }
+                    .into())
+                }
+                // the issue was deleted or transferred, so a new one is created
+                None => {}
+            }
+        }
+
+        let new_issue = NewIssue {
+            title: text(self.locale, "dashboard.title"),
+            body: Some(body.to_string()),
+            ..Default::default()
+        };
+        let issue =
+            create_issue(&self.github_api_addr, &self.token, owner, repo, &new_issue).await?;
+        state.issue = Some(
+            IssueRef {
+                owner: owner.to_string(),
+                repo: repo.to_string(),
+                number: issue.number,
+            }
+            .to_string(),
+        );
+        // saved before pinning, so that a failed pin doesn't create another issue next time
+        self.save_state(&state);
+        // only a new issue is pinned, so an issue unpinned by hand stays unpinned
+        if self.pin {
+            let node_id = issue.node_id.ok_or_else(|| GitHubApiError {
+                message: "Pin Issue Error: no node id".to_string(),
+            })?;
+            pin_issue(&self.github_api_addr, &self.token, &node_id).await?;
+        }
+        Ok(())
+    }
+
+    async fn publish_gist(&self, id: Option<&str>, body: &str) -> Result<(), NotifyError> {
+        let mut state: DashboardState = load_json(&self.state_path);
+        let gist_id = id.map(str::to_string).or_else(|| state.gist_id.clone());
+        if let Some(gist_id) = gist_id {
+            match update_gist(
+                &self.github_api_addr,
+                &self.token,
+                &gist_id,
+                DASHBOARD_GIST_FILE_NAME,
+                body,
+            )
+            .await?
+            {
+                Some(_) => return Ok(()),
+                None if id.is_some() => {
+                    return Err(GitHubApiError {
+                        message: format!("Dashboard Error: gist {gist_id} is not found"),
+                    }
+                    .into())
+                }
+                None => {}
+            }
+        }
+
+        let gist = create_gist(
+            &self.github_api_addr,
+            &self.token,
+            &text(self.locale, "dashboard.title"),
+            DASHBOARD_GIST_FILE_NAME,
+            body,
+        )
+        .await?;
+        println!("Dashboard Gist: {}", gist.html_url);
+        state.gist_id = Some(gist.id);
+        self.save_state(&state);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for DashboardNotifier {
+    fn name(&self) -> &str {
+        DASHBOARD_NOTIFIER
+    }
+
+    async fn notify(&self, digest: &Digest) -> Result<(), NotifyError> {
+        let body = create_dashboard_body(digest, self.locale, &self.style);
+        match &self.target {
+            DashboardTarget::Issue {
+                owner,
+                repo,
+                number,
+            } => self.publish_issue(owner, repo, *number, &body).await,
+            DashboardTarget::Gist { id } => self.publish_gist(id.as_deref(), &body).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::digest;
+    use httpmock::prelude::*;
+
+    fn state_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir()
+            .join("github_notification_dashboard_test")
+            .join(name);
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn notifier(
+        github_api_addr: String,
+        target: DashboardTarget,
+        state_path: PathBuf,
+    ) -> DashboardNotifier {
+        DashboardNotifier {
+            github_api_addr,
+            token: "token".to_string(),
+            target,
+            pin: false,
+            state_path,
+            locale: Locale::En,
+            style: DigestStyle::default(),
+        }
+    }
+
+    fn issue_json(number: i64) -> serde_json::Value {
+        serde_json::json!({
+            "number": number,
+            "title": "Task board",
+            "html_url": format!("https://github.com/owner/repo/issues/{number}"),
+            "state": "open",
+            "labels": [],
+            "node_id": format!("I_{number}"),
+        })
+    }
+
+    #[test]
+    fn test_parse_dashboard_target() {
+        assert_eq!(
+            DashboardTarget::parse("owner/repo"),
+            Some(DashboardTarget::Issue {
+                owner: "owner".to_string(),
+                repo: "repo".to_string(),
+                number: None,
+            })
+        );
+        assert_eq!(
+            DashboardTarget::parse("owner/repo#12"),
+            Some(DashboardTarget::Issue {
+                owner: "owner".to_string(),
+                repo: "repo".to_string(),
+                number: Some(12),
+            })
+        );
+        assert_eq!(
+            DashboardTarget::parse("gist"),
+            Some(DashboardTarget::Gist { id: None })
+        );
+        assert_eq!(
+            DashboardTarget::parse("gist:abc"),
+            Some(DashboardTarget::Gist {
+                id: Some("abc".to_string())
+            })
+        );
+        assert_eq!(DashboardTarget::parse("gist:"), None);
+        assert_eq!(DashboardTarget::parse("owner"), None);
+        assert_eq!(DashboardTarget::parse("owner/repo/extra"), None);
+    }
+
+    #[test]
+    fn test_create_dashboard_body() {
+        let body = create_dashboard_body(&digest("title"), Locale::En, &DigestStyle::default());
+        assert!(body.starts_with("# Tasks\n"));
+        assert!(body.contains("[title](https://github.com/owner/name/issues/1)"));
+        assert!(body.ends_with("\n\n_Updated at 2023-01-10 00:00 UTC_\n"));
+    }
+
+    #[test]
+    fn test_truncate_lines() {
+        assert_eq!(truncate_lines("a\nb\n", 10), "a\nb\n");
+        assert_eq!(truncate_lines("aaaa\nbbbb\ncccc\n", 10), "aaaa\n…\n");
+    }
+
+    #[tokio::test]
+    async fn test_notify_creates_and_edits_issue() {
+        let server = MockServer::start();
+        let create = server.mock(|when, then| {
+            when.method(POST)
+                .path("/repos/owner/repo/issues")
+                .body_contains("\"title\":\"Task board\"");
+            then.status(201).json_body(issue_json(12));
+        });
+        let update = server.mock(|when, then| {
+            when.method("PATCH")
+                .path("/repos/owner/repo/issues/12")
+                .body_contains("# Tasks");
+            then.status(200).json_body(issue_json(12));
+        });
+        let pin = server.mock(|when, then| {
+            when.method(POST).path("/graphql").body_contains("I_12");
+            then.status(200)
+                .json_body(serde_json::json!({"data": {"pinIssue": {"issue": {"number": 12}}}}));
+        });
+        let target = DashboardTarget::parse("owner/repo").unwrap();
+        let mut notifier = notifier(
+            format!("http://{}", server.address()),
+            target,
+            state_path("issue.json"),
+        );
+        notifier.pin = true;
+
+        // the first run creates and pins the issue, the second one edits it
+        notifier.notify(&digest("title")).await.unwrap();
+        notifier.notify(&digest("title")).await.unwrap();
+        create.assert_hits(1);
+        pin.assert_hits(1);
+        update.assert_hits(1);
+        let state: DashboardState = load_json(&notifier.state_path);
+        assert_eq!(state.issue, Some("owner/repo#12".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_notify_replaces_deleted_issue() {
+        let server = MockServer::start();
+        let gone = server.mock(|when, then| {
+            when.method("PATCH").path("/repos/owner/repo/issues/12");
+            then.status(410);
+        });
+        let create = server.mock(|when, then| {
+            when.method(POST).path("/repos/owner/repo/issues");
+            then.status(201).json_body(issue_json(13));
+        });
+        let path = state_path("deleted.json");
+        save_json(
+            &path,
+            &DashboardState {
+                issue: Some("owner/repo#12".to_string()),
+                gist_id: None,
+            },
+        )
+        .unwrap();
+        let target = DashboardTarget::parse("owner/repo").unwrap();
+        let notifier = notifier(format!("http://{}", server.address()), target, path);
+
+        notifier.notify(&digest("title")).await.unwrap();
+        gone.assert();
+        create.assert();
+        let state: DashboardState = load_json(&notifier.state_path);
+        assert_eq!(state.issue, Some("owner/repo#13".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_notify_given_issue_is_not_replaced() {
+        let server = MockServer::start();
+        let gone = server.mock(|when, then| {
+            when.method("PATCH").path("/repos/owner/repo/issues/12");
+            then.status(404);
+        });
+        let create = server.mock(|when, then| {
+            when.method(POST).path("/repos/owner/repo/issues");
+            then.status(201).json_body(issue_json(13));
+        });
+        let target = DashboardTarget::parse("owner/repo#12").unwrap();
+        let notifier = notifier(
+            format!("http://{}", server.address()),
+            target,
+            state_path("given.json"),
+        );
+
+        let err = notifier.notify(&digest("title")).await.unwrap_err();
+        gone.assert();
+        create.assert_hits(0);
+        assert_eq!(err.message, "Dashboard Error: owner/repo#12 is not found");
+    }
+
+    #[tokio::test]
+    async fn test_notify_creates_and_edits_gist() {
+        let server = MockServer::start();
+        let create = server.mock(|when, then| {
+            when.method(POST)
+                .path("/gists")
+                .body_contains("\"public\":false")
+                .body_contains("tasks.md");
+            then.status(201).json_body(serde_json::json!({
+                "id": "abc",
+                "html_url": "https://gist.github.com/abc",
+            }));
+        });
+        let update = server.mock(|when, then| {
+            when.method("PATCH")
+                .path("/gists/abc")
+                .body_contains("# Tasks");
+            then.status(200).json_body(serde_json::json!({
+                "id": "abc",
+                "html_url": "https://gist.github.com/abc",
+            }));
+        });
+        let notifier = notifier(
+            format!("http://{}", server.address()),
+            DashboardTarget::Gist { id: None },
+            state_path("gist.json"),
+        );
+
+        notifier.notify(&digest("title")).await.unwrap();
+        notifier.notify(&digest("title")).await.unwrap();
+        create.assert_hits(1);
+        update.assert_hits(1);
+        let state: DashboardState = load_json(&notifier.state_path);
+        assert_eq!(state.gist_id, Some("abc".to_string()));
+    }
+}
@@ -0,0 +1,46 @@
+use crate::models::IssueRef;
+use serde::{Deserialize, Serialize};
+
+pub const DASHBOARD_STATE_FILE_NAME: &str = "dashboard.json";
+
+// the dashboards created by the bot, which later runs edit
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DashboardState {
+    // e.g. "owner/repo#12"
+    pub issue: Option<String>,
+    pub gist_id: Option<String>,
+}
+
+impl DashboardState {
+    // the issue created before in the repository
+    pub fn issue_in(&self, owner: &str, repo: &str) -> Option<IssueRef> {
+        self.issue
+            .as_deref()
+            .and_then(IssueRef::parse)
+            .filter(|issue_ref| issue_ref.owner == owner && issue_ref.repo == repo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_in() {
+        let state = DashboardState {
+            issue: Some("owner/repo#12".to_string()),
+            gist_id: None,
+        };
+        assert_eq!(
+            state.issue_in("owner", "repo"),
+            Some(IssueRef {
+                owner: "owner".to_string(),
+                repo: "repo".to_string(),
+                number: 12,
+            })
+        );
+        // a dashboard moved to another repository starts over
+        assert_eq!(state.issue_in("owner", "other"), None);
+        assert_eq!(DashboardState::default().issue_in("owner", "repo"), None);
+    }
+}
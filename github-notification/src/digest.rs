@@ -0,0 +1,92 @@
+use crate::errors::GetIssueError;
+use crate::models::{Issue, Priority, SortedIssues};
+use chrono::{DateTime, Utc};
+
+// what every notifier delivers, independent of the channel
+#[derive(Debug, Clone)]
+pub struct Digest {
+    // the issues by priority, or why they could not be fetched
+    pub issues: Result<SortedIssues, GetIssueError>,
+    // due dates are relative to this
+    pub generated_at: DateTime<Utc>,
+    // the email addresses of this digest, the ones of the notifier when empty
+    pub recipients: Vec<String>,
+}
+
+impl Digest {
+    pub fn new(issues: Result<SortedIssues, GetIssueError>, generated_at: DateTime<Utc>) -> Self {
+        Self {
+            issues,
+            generated_at,
+            recipients: vec![],
+        }
+    }
+
+    pub fn with_recipients(mut self, recipients: Vec<String>) -> Self {
+        self.recipients = recipients;
+        self
+    }
+
+    // the priorities which have issues, from high to none
+    pub fn groups(&self) -> Vec<(Priority, &Vec<Issue>)> {
+        match &self.issues {
+            Ok(issues) => issues
+                .groups()
+                .into_iter()
+                .filter(|(_, issues)| !issues.is_empty())
+                .collect(),
+            Err(_) => vec![],
+        }
+    }
+
+    pub fn issue_count(&self) -> usize {
+        self.groups().iter().map(|(_, issues)| issues.len()).sum()
+    }
+
+    pub fn overdue_count(&self) -> usize {
+        self.groups()
+            .iter()
+            .flat_map(|(_, issues)| issues.iter())
+            .filter(|issue| issue.is_overdue(self.generated_at))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{issue, now};
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_digest_counts() {
+        let yesterday = Utc.with_ymd_and_hms(2023, 1, 9, 0, 0, 0).unwrap();
+        let digest = Digest::new(
+            Ok(SortedIssues {
+                priority_high_issues: vec![
+                    issue(1, "overdue", Some(yesterday)),
+                    issue(2, "title", None),
+                ],
+                priority_low_issues: vec![issue(3, "due today", Some(now()))],
+                ..SortedIssues::default()
+            }),
+            now(),
+        );
+        let priorities: Vec<Priority> = digest.groups().iter().map(|(p, _)| *p).collect();
+        assert_eq!(priorities, vec![Priority::High, Priority::Low]);
+        assert_eq!(digest.issue_count(), 3);
+        assert_eq!(digest.overdue_count(), 1);
+    }
+
+    #[test]
+    fn test_digest_error() {
+        let digest = Digest::new(
+            Err(GetIssueError {
+                message: "error".to_string(),
+            }),
+            Utc::now(),
+        );
+        assert!(digest.groups().is_empty());
+        assert_eq!(digest.issue_count(), 0);
+    }
+}
@@ -0,0 +1,342 @@
+use super::markdown::{code, escape_text, link};
+use crate::digest::Digest;
+use crate::i18n::{plural_text, relative_date, text, text_with, Locale};
+use crate::models::{Issue, Priority};
+use crate::slack::layout::truncate_text;
+use crate::slack::style::DigestStyle;
+use serde::Serialize;
+
+// limits of Discord
+// https://discord.com/developers/docs/resources/message#embed-object-embed-limits
+pub const MAX_CONTENT_LENGTH: usize = 2000;
+pub const MAX_EMBEDS_PER_MESSAGE: usize = 10;
+pub const MAX_FIELDS_PER_EMBED: usize = 25;
+pub const MAX_TITLE_LENGTH: usize = 256;
+pub const MAX_FIELD_NAME_LENGTH: usize = 256;
+pub const MAX_FIELD_VALUE_LENGTH: usize = 1024;
+// the title, fields and footer of all embeds of a message together
+pub const MAX_EMBED_CHARACTERS_PER_MESSAGE: usize = 6000;
+
+// a digest never spreads over more messages than this
+pub const MAX_MESSAGES_PER_DIGEST: usize = 5;
+
+// room kept in every message for the "+N more" footer
+const FOOTER_RESERVE: usize = 100;
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct DiscordEmbedField {
+    pub name: String,
+    pub value: String,
+    pub inline: bool,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct DiscordEmbedFooter {
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct DiscordEmbed {
+    pub title: String,
+    pub color: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<DiscordEmbedField>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footer: Option<DiscordEmbedFooter>,
+}
+
+impl DiscordEmbed {
+    // the characters which count towards MAX_EMBED_CHARACTERS_PER_MESSAGE
+    pub fn character_count(&self) -> usize {
+        let fields: usize = self
+            .fields
+            .iter()
+            .map(|field| field.name.chars().count() + field.value.chars().count())
+            .sum();
+        let footer = self
+            .footer
+            .as_ref()
+            .map(|footer| footer.text.chars().count())
+            .unwrap_or(0);
+        self.title.chars().count() + fields + footer
+    }
+}
+
+// no one is pinged by a digest
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct DiscordAllowedMentions {
+    pub parse: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct DiscordWebhookMessage {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub content: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub embeds: Vec<DiscordEmbed>,
+    pub allowed_mentions: DiscordAllowedMentions,
+}
+
+impl DiscordWebhookMessage {
+    fn embed_character_count(&self) -> usize {
+        self.embeds.iter().map(DiscordEmbed::character_count).sum()
+    }
+}
+
+// the color of the left border of an embed
+pub fn priority_color(priority: Priority) -> u32 {
+    match priority {
+        Priority::High => 0xE74C3C,
+        Priority::Medium => 0xE67E22,
+        Priority::Low => 0xF1C40F,
+        Priority::None => 0x95A5A6,
+    }
+}
+
+// the issue title as the name, and the link, labels and due date as the value
+fn create_issue_field(
+    issue: &Issue,
+    priority: Priority,
+    locale: Locale,
+    style: &DigestStyle,
+    digest: &Digest,
+) -> DiscordEmbedField {
+    let repository = &issue.repository;
+    let mut parts = vec![link(
+        &issue.html_url,
+        &format!("{}#{}", repository.full_name, issue.number),
+    )];
+    parts.extend(
+        style
+            .visible_labels(issue, priority)
+            .into_iter()
+            .map(|label| code(&label.name)),
+    );
+    if let Some(due_on) = issue
+        .milestone
+        .as_ref()
+        .and_then(|milestone| milestone.due_on)
+    {
+        let date = relative_date(locale, due_on, digest.generated_at);
+        parts.push(text_with(locale, "digest.due", &[("date", &date)]));
+    }
+    DiscordEmbedField {
+        name: truncate_text(&escape_text(&issue.title), MAX_FIELD_NAME_LENGTH),
+        // a cut link would be broken, so labels are cut first
+        value: truncate_text(&parts.join(" "), MAX_FIELD_VALUE_LENGTH),
+        inline: false,
+    }
+}
+
+// one embed per priority which has issues, colored by the priority
+// a priority with more issues than an embed can hold continues in more embeds
+pub fn create_embeds(digest: &Digest, locale: Locale, style: &DigestStyle) -> Vec<DiscordEmbed> {
+    let max_embed_characters = MAX_EMBED_CHARACTERS_PER_MESSAGE - FOOTER_RESERVE;
+    let mut embeds = Vec::new();
+    for (priority, issues) in digest.groups() {
+        let title = truncate_text(&style.priority_title(priority, locale), MAX_TITLE_LENGTH);
+        let continued_title = truncate_text(
+            &text_with(locale, "digest.continued", &[("header", &title)]),
+            MAX_TITLE_LENGTH,
+        );
+        let mut embed = DiscordEmbed {
+            title,
+            color: priority_color(priority),
+            ..DiscordEmbed::default()
+        };
+        for issue in issues {
+            let field = create_issue_field(issue, priority, locale, style, digest);
+            let field_characters = field.name.chars().count() + field.value.chars().count();
+            let is_full = embed.fields.len() >= MAX_FIELDS_PER_EMBED
+                || embed.character_count() + field_characters > max_embed_characters;
+            if is_full && !embed.fields.is_empty() {
+                let next = DiscordEmbed {
+                    title: continued_title.clone(),
+                    color: embed.color,
+                    ..DiscordEmbed::default()
+                };
+                embeds.push(std::mem::replace(&mut embed, next));
+            }
+            embed.fields.push(field);
+        }
+        embeds.push(embed);
+    }
+    embeds
+}
+
+// the digest as webhook messages within the limits of Discord
+// when more than MAX_MESSAGES_PER_DIGEST would be needed, the rest is dropped
+// and the last embed gets a "+N more" footer
+pub fn create_webhook_messages(
+    digest: &Digest,
+    locale: Locale,
+    style: &DigestStyle,
+) -> Vec<DiscordWebhookMessage> {
+    let title = format!("**{}**", text(locale, "digest.title"));
+    let content = match &digest.issues {
+        Err(e) => format!("{title}\n{}", escape_text(&e.message)),
+        Ok(_) if digest.issue_count() == 0 => {
+            format!("{title}\n{}", text(locale, "digest.summary.empty"))
+        }
+        Ok(_) => title,
+    };
+    let mut messages = vec![DiscordWebhookMessage {
+        content: truncate_text(&content, MAX_CONTENT_LENGTH),
+        ..DiscordWebhookMessage::default()
+    }];
+
+    let max_embed_characters = MAX_EMBED_CHARACTERS_PER_MESSAGE - FOOTER_RESERVE;
+    let mut omitted_count = 0;
+    for embed in create_embeds(digest, locale, style) {
+        if omitted_count > 0 {
+            omitted_count += embed.fields.len();
+            continue;
+        }
+        let last = messages.last().unwrap();
+        let fits = last.embeds.len() < MAX_EMBEDS_PER_MESSAGE
+            && last.embed_character_count() + embed.character_count() <= max_embed_characters;
+        if !fits {
+            if messages.len() >= MAX_MESSAGES_PER_DIGEST {
+                omitted_count += embed.fields.len();
+                continue;
+            }
+            messages.push(DiscordWebhookMessage::default());
+        }
+        messages.last_mut().unwrap().embeds.push(embed);
+    }
+
+    if omitted_count > 0 {
+        let footer = plural_text(locale, "digest.more", omitted_count as i64);
+        if let Some(embed) = messages.last_mut().unwrap().embeds.last_mut() {
+            embed.footer = Some(DiscordEmbedFooter { text: footer });
+        }
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::GetIssueError;
+    use crate::fixtures::{issue, now};
+    use crate::models::SortedIssues;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_create_embeds() {
+        let due_issue = issue(
+            2,
+            "*fix*",
+            Some(Utc.with_ymd_and_hms(2023, 1, 12, 0, 0, 0).unwrap()),
+        );
+        let digest = Digest::new(
+            Ok(SortedIssues {
+                priority_high_issues: vec![issue(1, "title", None)],
+                priority_low_issues: vec![due_issue],
+                ..SortedIssues::default()
+            }),
+            now(),
+        );
+        let embeds = create_embeds(&digest, Locale::En, &DigestStyle::default());
+        assert_eq!(embeds.len(), 2);
+        assert_eq!(embeds[0].title, "🔴 Priority: High");
+        assert_eq!(embeds[0].color, 0xE74C3C);
+        assert_eq!(
+            embeds[1].fields,
+            vec![DiscordEmbedField {
+                name: "\\*fix\\*".to_string(),
+                value:
+                    "[owner/name#2](https://github.com/owner/name/issues/2) `bug` (due in 2 days)"
+                        .to_string(),
+                inline: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_create_embeds_splits_by_fields() {
+        let digest = Digest::new(
+            Ok(SortedIssues {
+                priority_medium_issues: (0..30)
+                    .map(|number| issue(number, "title", None))
+                    .collect(),
+                ..SortedIssues::default()
+            }),
+            now(),
+        );
+        let embeds = create_embeds(&digest, Locale::En, &DigestStyle::default());
+        assert_eq!(embeds.len(), 2);
+        assert_eq!(embeds[0].fields.len(), MAX_FIELDS_PER_EMBED);
+        assert_eq!(embeds[1].title, "🟠 Priority: Medium (continued)");
+        assert_eq!(embeds[1].fields.len(), 5);
+    }
+
+    #[test]
+    fn test_create_webhook_messages_within_limits() {
+        let long_title = "a".repeat(1000);
+        let digest = Digest::new(
+            Ok(SortedIssues {
+                priority_high_issues: (0..100)
+                    .map(|number| issue(number, &long_title, None))
+                    .collect(),
+                ..SortedIssues::default()
+            }),
+            now(),
+        );
+        let messages = create_webhook_messages(&digest, Locale::En, &DigestStyle::default());
+        assert_eq!(messages.len(), MAX_MESSAGES_PER_DIGEST);
+        assert_eq!(messages[0].content, "**Tasks**");
+        for message in &messages {
+            assert!(message.embeds.len() <= MAX_EMBEDS_PER_MESSAGE);
+            assert!(message.embed_character_count() <= MAX_EMBED_CHARACTERS_PER_MESSAGE);
+            for embed in &message.embeds {
+                assert!(embed.fields.len() <= MAX_FIELDS_PER_EMBED);
+                assert!(embed
+                    .fields
+                    .iter()
+                    .all(|field| field.name.chars().count() <= MAX_FIELD_NAME_LENGTH));
+            }
+        }
+        // no issue is lost without being counted
+        let shown: usize = messages
+            .iter()
+            .flat_map(|message| &message.embeds)
+            .map(|embed| embed.fields.len())
+            .sum();
+        let footer = &messages.last().unwrap().embeds.last().unwrap().footer;
+        assert_eq!(
+            footer.as_ref().unwrap().text,
+            format!("and {} more issues", 100 - shown)
+        );
+    }
+
+    #[test]
+    fn test_create_webhook_messages_without_issues() {
+        let digest = Digest::new(Ok(SortedIssues::default()), now());
+        let messages = create_webhook_messages(&digest, Locale::Ja, &DigestStyle::default());
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].content, "**タスク一覧**\nタスクはありません");
+        assert!(messages[0].embeds.is_empty());
+
+        let digest = Digest::new(
+            Err(GetIssueError {
+                message: "Get Issue Error: 401".to_string(),
+            }),
+            now(),
+        );
+        let messages = create_webhook_messages(&digest, Locale::En, &DigestStyle::default());
+        assert_eq!(messages[0].content, "**Tasks**\nGet Issue Error: 401");
+    }
+
+    #[test]
+    fn test_webhook_message_json() {
+        let message = DiscordWebhookMessage {
+            content: "**Tasks**".to_string(),
+            ..DiscordWebhookMessage::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&message).unwrap(),
+            serde_json::json!({"content": "**Tasks**", "allowed_mentions": {"parse": []}})
+        );
+    }
+}
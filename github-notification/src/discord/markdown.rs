@@ -0,0 +1,79 @@
+// markdown of Discord
+// https://support.discord.com/hc/en-us/articles/210298617
+
+// characters which start formatting, links and quotes
+const MARKDOWN_CHARACTERS: [char; 11] = ['\\', '*', '_', '~', '`', '|', '>', '[', ']', '(', ')'];
+
+// characters which start headings and lists, only at the start of a line
+const LINE_START_CHARACTERS: [char; 2] = ['#', '-'];
+
+// mentions which ping many people
+const BROADCAST_MENTIONS: [&str; 2] = ["@everyone", "@here"];
+
+const ZERO_WIDTH_SPACE: char = '\u{200B}';
+
+// user-controlled text, e.g. issue titles and label names, shown as it is
+pub fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    if text.starts_with(LINE_START_CHARACTERS) {
+        escaped.push('\\');
+    }
+    for c in text.chars() {
+        if MARKDOWN_CHARACTERS.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    for mention in BROADCAST_MENTIONS {
+        let (at, name) = mention.split_at(1);
+        escaped = escaped.replace(mention, &format!("{at}{ZERO_WIDTH_SPACE}{name}"));
+    }
+    escaped
+}
+
+// characters which end the URL part of a link
+fn escape_url(url: &str) -> String {
+    url.replace('(', "%28")
+        .replace(')', "%29")
+        .replace(' ', "%20")
+}
+
+// [text](url)
+pub fn link(url: &str, text: &str) -> String {
+    format!("[{}]({})", escape_text(text), escape_url(url))
+}
+
+// `text`, as a chip
+// a backtick would end it, so it is replaced with a look-alike
+pub fn code(text: &str) -> String {
+    format!("`{}`", text.replace('`', "\u{02CB}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_text() {
+        assert_eq!(
+            escape_text("fix *bold* [x](y)"),
+            "fix \\*bold\\* \\[x\\]\\(y\\)"
+        );
+        assert_eq!(escape_text("# title - a_b"), "\\# title - a\\_b");
+        assert_eq!(escape_text("ping @everyone"), "ping @\u{200B}everyone");
+    }
+
+    #[test]
+    fn test_link() {
+        assert_eq!(
+            link("https://example.com/a b(1)", "a [b]"),
+            "[a \\[b\\]](https://example.com/a%20b%281%29)"
+        );
+    }
+
+    #[test]
+    fn test_code() {
+        assert_eq!(code("bug"), "`bug`");
+        assert_eq!(code("a`b"), "`a\u{02CB}b`");
+    }
+}
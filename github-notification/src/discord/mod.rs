@@ -0,0 +1,3 @@
+pub mod embed;
+pub mod markdown;
+pub mod notifier;
@@ -0,0 +1,201 @@
+use super::embed::{create_webhook_messages, DiscordWebhookMessage};
+use crate::digest::Digest;
+use crate::errors::{DiscordApiError, NotifyError};
+use crate::i18n::Locale;
+use crate::notifier::{Notifier, DISCORD_NOTIFIER};
+use crate::slack::style::DigestStyle;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::time::Duration;
+
+// a message is tried at most this many times when rate limited
+const MAX_ATTEMPTS: usize = 3;
+// a longer wait fails the message instead
+const MAX_RETRY_AFTER_SECS: f64 = 60.0;
+
+// the body of a 429 response
+// https://discord.com/developers/docs/topics/rate-limits#exceeding-a-rate-limit
+#[derive(Debug, Deserialize)]
+struct DiscordRateLimit {
+    // seconds, with a fraction
+    retry_after: f64,
+}
+
+// how long a 429 response tells to wait, from the body or else the Retry-After header
+async fn read_retry_after(res: reqwest::Response) -> f64 {
+    let header = res
+        .headers()
+        .get("retry-after")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<f64>().ok());
+    match res.json::<DiscordRateLimit>().await {
+        Ok(rate_limit) => rate_limit.retry_after,
+        Err(_) => header.unwrap_or(1.0),
+    }
+}
+
+// post a message to a webhook, waiting as told by a 429 response before trying again
+pub async fn post_webhook_message(
+    webhook_url: &str,
+    message: &DiscordWebhookMessage,
+) -> Result<(), DiscordApiError> {
+    let client = reqwest::Client::new();
+    let mut attempt = 1;
+    loop {
+        let res = client
+            .post(webhook_url)
+            .json(message)
+            .send()
+            .await
+            .map_err(|err| DiscordApiError {
+                message: format!("Discord Webhook Error: {err}"),
+            })?;
+        let status = res.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        if status != 429 {
+            return Err(DiscordApiError {
+                message: format!("Discord Webhook Error: {}", status.as_u16()),
+            });
+        }
+        let retry_after = read_retry_after(res).await;
+        if attempt >= MAX_ATTEMPTS || retry_after > MAX_RETRY_AFTER_SECS {
+            return Err(DiscordApiError {
+                message: format!("Discord Webhook Error: rate limited for {retry_after}s"),
+            });
+        }
+        println!("Discord Webhook Rate Limited: retry after {retry_after}s");
+        tokio::time::sleep(Duration::from_secs_f64(retry_after.max(0.0))).await;
+        attempt += 1;
+    }
+}
+
+pub struct DiscordWebhookNotifier {
+    pub webhook_url: String,
+    pub locale: Locale,
+    pub style: DigestStyle,
+}
+
+#[async_trait]
+impl Notifier for DiscordWebhookNotifier {
+    fn name(&self) -> &str {
+        DISCORD_NOTIFIER
+    }
+
+    // sending stops at the first failure, so the messages stay in order
+    async fn notify(&self, digest: &Digest) -> Result<(), NotifyError> {
+        for message in create_webhook_messages(digest, self.locale, &self.style) {
+            post_webhook_message(&self.webhook_url, &message).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SortedIssues;
+    use chrono::Utc;
+
+    fn message() -> DiscordWebhookMessage {
+        DiscordWebhookMessage {
+            content: "**Tasks**".to_string(),
+            ..DiscordWebhookMessage::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_post_webhook_message() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/webhooks/1/token")
+                .json_body_partial(r#"{"content": "**Tasks**"}"#);
+            then.status(204);
+        });
+        let webhook_url = format!("http://{}/webhooks/1/token", server.address());
+        post_webhook_message(&webhook_url, &message())
+            .await
+            .unwrap();
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_post_webhook_message_retries_after_rate_limit() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start_async().await;
+        // the first matching mock answers, so the rate limit comes first until it is deleted
+        let rate_limit_mock = server
+            .mock_async(|when, then| {
+                when.method(POST).path("/webhooks/1/token");
+                then.status(429)
+                    .json_body(serde_json::json!({"retry_after": 0.5, "global": false}));
+            })
+            .await;
+        let mock = server
+            .mock_async(|when, then| {
+                when.method(POST).path("/webhooks/1/token");
+                then.status(204);
+            })
+            .await;
+        let webhook_url = format!("http://{}/webhooks/1/token", server.address());
+        let message = message();
+
+        let lift_rate_limit = async {
+            while rate_limit_mock.hits_async().await == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            rate_limit_mock.delete_async().await;
+        };
+        let (res, _) = tokio::join!(
+            post_webhook_message(&webhook_url, &message),
+            lift_rate_limit
+        );
+        res.unwrap();
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_post_webhook_message_gives_up() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST).path("/");
+            then.status(429)
+                .header("Retry-After", "0.01")
+                .body("rate limited");
+        });
+        let webhook_url = format!("http://{}", server.address());
+        let err = post_webhook_message(&webhook_url, &message())
+            .await
+            .unwrap_err();
+        mock.assert_hits(MAX_ATTEMPTS);
+        assert_eq!(err.message, "Discord Webhook Error: rate limited for 0.01s");
+    }
+
+    #[tokio::test]
+    async fn test_discord_webhook_notifier() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST).path("/");
+            then.status(400);
+        });
+        let notifier = DiscordWebhookNotifier {
+            webhook_url: format!("http://{}", server.address()),
+            locale: Locale::En,
+            style: DigestStyle::default(),
+        };
+        let digest = Digest::new(Ok(SortedIssues::default()), Utc::now());
+        let err = notifier.notify(&digest).await.unwrap_err();
+        mock.assert();
+        assert_eq!(notifier.name(), "discord");
+        assert_eq!(err.message, "Discord Webhook Error: 400");
+    }
+}
@@ -0,0 +1,267 @@
+use crate::digest::Digest;
+use crate::discord::embed::priority_color;
+use crate::errors::EmailError;
+use crate::i18n::{plural_text, relative_date, text, text_with, Locale};
+use crate::models::{Issue, Priority};
+use crate::render::html::{escape_html, link as html_link};
+use crate::slack::style::DigestStyle;
+use lettre::message::{header::ContentType, Mailbox, MultiPart, SinglePart};
+use lettre::Message;
+
+// e.g. "Tasks: 3 issues", only the title when the issues could not be fetched
+pub fn create_subject(digest: &Digest, locale: Locale) -> String {
+    match &digest.issues {
+        Ok(_) => plural_text(locale, "email.subject", digest.issue_count() as i64),
+        Err(_) => text(locale, "digest.title"),
+    }
+}
+
+fn create_table_row(
+    issue: &Issue,
+    priority: Priority,
+    locale: Locale,
+    style: &DigestStyle,
+    digest: &Digest,
+) -> String {
+    let labels: Vec<String> = style
+        .visible_labels(issue, priority)
+        .into_iter()
+        .map(|label| escape_html(&label.name))
+        .collect();
+    let due = issue
+        .milestone
+        .as_ref()
+        .and_then(|milestone| milestone.due_on)
+        .map(|due_on| escape_html(&relative_date(locale, due_on, digest.generated_at)))
+        .unwrap_or_default();
+    // an overdue date stands out in the color of the high priority
+    let due_cell = match issue.is_overdue(digest.generated_at) {
+        true => format!(
+            "<td style=\"color:#{:06X}\">{due}</td>",
+            priority_color(Priority::High)
+        ),
+        false => format!("<td>{due}</td>"),
+    };
+    format!(
+        "<tr><td>{}</td><td>{}</td><td>{}</td>{due_cell}</tr>",
+        html_link(&issue.html_url, &issue.title),
+        html_link(&issue.repository.html_url, &issue.repository.full_name),
+        labels.join(", "),
+    )
+}
+
+// a table per priority which has issues
+pub fn create_html_body(digest: &Digest, locale: Locale, style: &DigestStyle) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<body>\n<h1>{}</h1>\n",
+        escape_html(&text(locale, "digest.title"))
+    );
+    match &digest.issues {
+        Err(e) => html.push_str(&format!("<p>{}</p>\n", escape_html(&e.message))),
+        Ok(_) if digest.issue_count() == 0 => html.push_str(&format!(
+            "<p>{}</p>\n",
+            escape_html(&text(locale, "digest.summary.empty"))
+        )),
+        Ok(_) => {
+            let header: Vec<String> = ["issue", "repository", "labels", "due"]
+                .iter()
+                .map(|column| {
+                    let column = text(locale, &format!("email.column.{column}"));
+                    format!("<th align=\"left\">{}</th>", escape_html(&column))
+                })
+                .collect();
+            for (priority, issues) in digest.groups() {
+                html.push_str(&format!(
+                    "<h2 style=\"color:#{:06X}\">{}</h2>\n",
+                    priority_color(priority),
+                    escape_html(&style.priority_title(priority, locale))
+                ));
+                html.push_str(
+                    "<table border=\"1\" cellpadding=\"6\" style=\"border-collapse:collapse\">\n",
+                );
+                html.push_str(&format!("<tr>{}</tr>\n", header.join("")));
+                for issue in issues {
+                    html.push_str(&create_table_row(issue, priority, locale, style, digest));
+                    html.push('\n');
+                }
+                html.push_str("</table>\n");
+            }
+        }
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+// the same digest for mail clients which don't show HTML
+pub fn create_text_body(digest: &Digest, locale: Locale, style: &DigestStyle) -> String {
+    let mut lines = vec![text(locale, "digest.title")];
+    match &digest.issues {
+        Err(e) => lines.push(e.message.clone()),
+        Ok(_) if digest.issue_count() == 0 => lines.push(text(locale, "digest.summary.empty")),
+        Ok(_) => {
+            for (priority, issues) in digest.groups() {
+                lines.push(String::new());
+                lines.push(style.priority_title(priority, locale));
+                for issue in issues {
+                    let mut details = vec![issue.repository.full_name.clone()];
+                    details.extend(
+                        style
+                            .visible_labels(issue, priority)
+                            .into_iter()
+                            .map(|label| label.name.clone()),
+                    );
+                    if let Some(due_on) = issue
+                        .milestone
+                        .as_ref()
+                        .and_then(|milestone| milestone.due_on)
+                    {
+                        let date = relative_date(locale, due_on, digest.generated_at);
+                        details.push(text_with(locale, "digest.due", &[("date", &date)]));
+                    }
+                    lines.push(format!("- {}", issue.title));
+                    lines.push(format!("  {}", issue.html_url));
+                    lines.push(format!("  {}", details.join(" · ")));
+                }
+            }
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+fn parse_mailbox(address: &str) -> Result<Mailbox, EmailError> {
+    address.parse().map_err(|err| EmailError {
+        message: format!("Email Error: invalid address {address}: {err}"),
+    })
+}
+
+// a multipart/alternative email, the plain text first as the fallback
+pub fn create_email(
+    from: &str,
+    recipients: &[String],
+    digest: &Digest,
+    locale: Locale,
+    style: &DigestStyle,
+) -> Result<Message, EmailError> {
+    let mut builder = Message::builder()
+        .from(parse_mailbox(from)?)
+        .subject(create_subject(digest, locale));
+    for recipient in recipients {
+        builder = builder.to(parse_mailbox(recipient)?);
+    }
+    builder
+        .multipart(
+            MultiPart::alternative()
+                .singlepart(
+                    SinglePart::builder()
+                        .header(ContentType::TEXT_PLAIN)
+                        .body(create_text_body(digest, locale, style)),
+                )
+                .singlepart(
+                    SinglePart::builder()
+                        .header(ContentType::TEXT_HTML)
+                        .body(create_html_body(digest, locale, style)),
+                ),
+        )
+        .map_err(|err| EmailError {
+            message: format!("Email Error: {err}"),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::GetIssueError;
+    use crate::fixtures::{issue, now};
+    use crate::models::SortedIssues;
+    use chrono::{TimeZone, Utc};
+
+    fn digest() -> Digest {
+        let overdue_issue = issue(
+            2,
+            "overdue",
+            Some(Utc.with_ymd_and_hms(2023, 1, 9, 0, 0, 0).unwrap()),
+        );
+        Digest::new(
+            Ok(SortedIssues {
+                priority_high_issues: vec![issue(1, "<script>", None)],
+                priority_low_issues: vec![overdue_issue],
+                ..SortedIssues::default()
+            }),
+            now(),
+        )
+    }
+
+    #[test]
+    fn test_create_subject() {
+        assert_eq!(create_subject(&digest(), Locale::En), "Tasks: 2 issues");
+        assert_eq!(create_subject(&digest(), Locale::Ja), "タスク一覧: 2件");
+        let error_digest = Digest::new(
+            Err(GetIssueError {
+                message: "Get Issue Error: 401".to_string(),
+            }),
+            Utc::now(),
+        );
+        assert_eq!(create_subject(&error_digest, Locale::En), "Tasks");
+    }
+
+    #[test]
+    fn test_create_html_body() {
+        let html = create_html_body(&digest(), Locale::En, &DigestStyle::default());
+        assert!(html.contains("<h1>Tasks</h1>"));
+        assert!(html.contains("<h2 style=\"color:#E74C3C\">🔴 Priority: High</h2>"));
+        assert!(html.contains("<tr><th align=\"left\">Issue</th><th align=\"left\">Repository</th><th align=\"left\">Labels</th><th align=\"left\">Due</th></tr>"));
+        // titles are escaped
+        assert!(html.contains(
+            "<tr><td><a href=\"https://github.com/owner/name/issues/1\">&lt;script&gt;</a></td><td><a href=\"https://github.com/owner/name\">owner/name</a></td><td>bug</td><td></td></tr>"
+        ));
+        assert!(html.contains("<td style=\"color:#E74C3C\">yesterday</td></tr>"));
+        assert!(!html.contains("Priority: Medium"));
+    }
+
+    #[test]
+    fn test_create_text_body() {
+        let body = create_text_body(&digest(), Locale::En, &DigestStyle::default());
+        assert_eq!(
+            body,
+            "Tasks\n\n🔴 Priority: High\n- <script>\n  https://github.com/owner/name/issues/1\n  owner/name · bug\n\n🟡 Priority: Low\n- overdue\n  https://github.com/owner/name/issues/2\n  owner/name · bug · (due yesterday)\n"
+        );
+        let empty_digest = Digest::new(Ok(SortedIssues::default()), Utc::now());
+        assert_eq!(
+            create_text_body(&empty_digest, Locale::Ja, &DigestStyle::default()),
+            "タスク一覧\nタスクはありません\n"
+        );
+    }
+
+    #[test]
+    fn test_create_email() {
+        let message = create_email(
+            "Tasks <tasks@example.com>",
+            &[
+                "alice@example.com".to_string(),
+                "bob@example.com".to_string(),
+            ],
+            &digest(),
+            Locale::En,
+            &DigestStyle::default(),
+        )
+        .unwrap();
+        assert_eq!(message.envelope().to().len(), 2);
+        let formatted = String::from_utf8_lossy(&message.formatted()).to_string();
+        assert!(formatted.contains("Subject: Tasks: 2 issues"));
+        assert!(formatted.contains("Content-Type: multipart/alternative"));
+        assert!(formatted.contains("Content-Type: text/plain; charset=utf-8"));
+        assert!(formatted.contains("Content-Type: text/html; charset=utf-8"));
+
+        let err = create_email(
+            "tasks@example.com",
+            &["not an address".to_string()],
+            &digest(),
+            Locale::En,
+            &DigestStyle::default(),
+        )
+        .unwrap_err();
+        assert!(err
+            .message
+            .starts_with("Email Error: invalid address not an address"));
+    }
+}
@@ -0,0 +1,257 @@
+use super::message::create_email;
+use crate::digest::Digest;
+use crate::errors::{EmailError, NotifyError};
+use crate::i18n::Locale;
+use crate::notifier::{Notifier, EMAIL_NOTIFIER};
+use crate::slack::style::DigestStyle;
+use async_trait::async_trait;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::client::{Tls, TlsParameters};
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+
+// how the connection to the SMTP server is secured
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    // upgraded by STARTTLS, which must succeed
+    #[default]
+    StartTls,
+    // TLS from the start, a.k.a. SMTPS
+    Tls,
+    // for a local relay or a test server only
+    None,
+}
+
+impl SmtpSecurity {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "starttls" => Some(Self::StartTls),
+            "tls" => Some(Self::Tls),
+            "none" => Some(Self::None),
+            _ => None,
+        }
+    }
+
+    pub fn default_port(self) -> u16 {
+        match self {
+            Self::StartTls => 587,
+            Self::Tls => 465,
+            Self::None => 25,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SmtpSettings {
+    pub host: String,
+    // the default port of the security when None
+    pub port: Option<u16>,
+    pub security: SmtpSecurity,
+    // the server is logged in to only with both
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+pub fn create_smtp_transport(
+    settings: &SmtpSettings,
+) -> Result<AsyncSmtpTransport<Tokio1Executor>, EmailError> {
+    let tls = match settings.security {
+        SmtpSecurity::None => Tls::None,
+        security => {
+            let parameters =
+                TlsParameters::new(settings.host.clone()).map_err(|err| EmailError {
+                    message: format!("Email Error: {err}"),
+                })?;
+            match security {
+                SmtpSecurity::Tls => Tls::Wrapper(parameters),
+                _ => Tls::Required(parameters),
+            }
+        }
+    };
+    let mut builder = AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&settings.host)
+        .port(
+            settings
+                .port
+                .unwrap_or_else(|| settings.security.default_port()),
+        )
+        .tls(tls);
+    if let (Some(username), Some(password)) = (&settings.username, &settings.password) {
+        builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+    }
+    Ok(builder.build())
+}
+
+pub struct EmailNotifier {
+    pub smtp: SmtpSettings,
+    pub from: String,
+    // the recipients of a digest which has none of its own
+    pub to: Vec<String>,
+    pub locale: Locale,
+    pub style: DigestStyle,
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    fn name(&self) -> &str {
+        EMAIL_NOTIFIER
+    }
+
+    async fn notify(&self, digest: &Digest) -> Result<(), NotifyError> {
+        let recipients = match digest.recipients.is_empty() {
+            true => &self.to,
+            false => &digest.recipients,
+        };
+        if recipients.is_empty() {
+            return Err(EmailError {
+                message: "Email Error: no recipients".to_string(),
+            }
+            .into());
+        }
+        let email = create_email(&self.from, recipients, digest, self.locale, &self.style)?;
+        let transport = create_smtp_transport(&self.smtp)?;
+        transport.send(email).await.map_err(|err| EmailError {
+            message: format!("Email Error: {err}"),
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SortedIssues;
+    use chrono::Utc;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    // what a session of the sink received
+    #[derive(Debug, Default)]
+    struct SinkSession {
+        commands: Vec<String>,
+        data: String,
+    }
+
+    // a local SMTP server which accepts one session and keeps what it was sent
+    async fn start_smtp_sink() -> (u16, tokio::task::JoinHandle<SinkSession>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut reader = BufReader::new(reader);
+            let mut session = SinkSession::default();
+            writer.write_all(b"220 sink ESMTP\r\n").await.unwrap();
+            let mut line = String::new();
+            while reader.read_line(&mut line).await.unwrap() > 0 {
+                let command = line.trim_end().to_string();
+                line.clear();
+                let reply: &[u8] = match command.split(' ').next().unwrap_or_default() {
+                    "EHLO" => b"250-sink\r\n250-AUTH PLAIN LOGIN\r\n250 8BITMIME\r\n",
+                    "AUTH" => b"235 2.7.0 Authentication successful\r\n",
+                    "DATA" => {
+                        writer.write_all(b"354 End data with .\r\n").await.unwrap();
+                        while reader.read_line(&mut line).await.unwrap() > 0 {
+                            if line == ".\r\n" {
+                                break;
+                            }
+                            session.data.push_str(&line);
+                            line.clear();
+                        }
+                        line.clear();
+                        b"250 2.0.0 Ok: queued\r\n"
+                    }
+                    "QUIT" => {
+                        session.commands.push(command);
+                        writer.write_all(b"221 2.0.0 Bye\r\n").await.unwrap();
+                        break;
+                    }
+                    _ => b"250 2.0.0 Ok\r\n",
+                };
+                session.commands.push(command);
+                writer.write_all(reply).await.unwrap();
+            }
+            session
+        });
+        (port, handle)
+    }
+
+    fn notifier(port: u16, username: Option<&str>) -> EmailNotifier {
+        EmailNotifier {
+            smtp: SmtpSettings {
+                host: "127.0.0.1".to_string(),
+                port: Some(port),
+                security: SmtpSecurity::None,
+                username: username.map(|username| username.to_string()),
+                password: username.map(|_| "password".to_string()),
+            },
+            from: "tasks@example.com".to_string(),
+            to: vec!["team@example.com".to_string()],
+            locale: Locale::En,
+            style: DigestStyle::default(),
+        }
+    }
+
+    #[test]
+    fn test_smtp_security() {
+        assert_eq!(
+            SmtpSecurity::parse("starttls"),
+            Some(SmtpSecurity::StartTls)
+        );
+        assert_eq!(SmtpSecurity::parse("tls"), Some(SmtpSecurity::Tls));
+        assert_eq!(SmtpSecurity::parse("none"), Some(SmtpSecurity::None));
+        assert_eq!(SmtpSecurity::parse("ssl"), None);
+        assert_eq!(SmtpSecurity::default().default_port(), 587);
+        assert_eq!(SmtpSecurity::Tls.default_port(), 465);
+    }
+
+    #[tokio::test]
+    async fn test_email_notifier() {
+        let (port, sink) = start_smtp_sink().await;
+        let notifier = notifier(port, Some("user"));
+        let digest = Digest::new(Ok(SortedIssues::default()), Utc::now());
+        notifier.notify(&digest).await.unwrap();
+        let session = sink.await.unwrap();
+        assert!(session
+            .commands
+            .iter()
+            .any(|command| command.starts_with("AUTH PLAIN")));
+        assert!(session
+            .commands
+            .contains(&"MAIL FROM:<tasks@example.com>".to_string()));
+        assert!(session
+            .commands
+            .contains(&"RCPT TO:<team@example.com>".to_string()));
+        assert!(session.data.contains("Subject: Tasks: 0 issues"));
+        assert!(session.data.contains("Content-Type: multipart/alternative"));
+        assert_eq!(notifier.name(), "email");
+    }
+
+    #[tokio::test]
+    async fn test_email_notifier_digest_recipients() {
+        let (port, sink) = start_smtp_sink().await;
+        // without credentials the server is not logged in to
+        let notifier = notifier(port, None);
+        let digest = Digest::new(Ok(SortedIssues::default()), Utc::now())
+            .with_recipients(vec!["alice@example.com".to_string()]);
+        notifier.notify(&digest).await.unwrap();
+        let session = sink.await.unwrap();
+        assert!(!session
+            .commands
+            .iter()
+            .any(|command| command.starts_with("AUTH")));
+        let recipients: Vec<&String> = session
+            .commands
+            .iter()
+            .filter(|command| command.starts_with("RCPT TO"))
+            .collect();
+        assert_eq!(recipients, vec!["RCPT TO:<alice@example.com>"]);
+    }
+
+    #[tokio::test]
+    async fn test_email_notifier_no_recipients() {
+        let mut notifier = notifier(25, None);
+        notifier.to = vec![];
+        let digest = Digest::new(Ok(SortedIssues::default()), Utc::now());
+        let err = notifier.notify(&digest).await.unwrap_err();
+        assert_eq!(err.message, "Email Error: no recipients");
+    }
+}
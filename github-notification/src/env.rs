@@ -1,6 +1,66 @@
+use crate::dashboard::notifier::DashboardTarget;
+use crate::email::notifier::{SmtpSecurity, SmtpSettings};
+use crate::i18n::{Locale, LocaleSettings};
+use crate::models::Priority;
+use crate::notifier::{SLACK_API_NOTIFIER, SLACK_WEBHOOK_NOTIFIER};
+use crate::slack::layout::DigestLayout;
+use crate::slack::mention::{MentionCondition, MentionPolicy, MentionTarget};
+use crate::slack::style::DigestStyle;
+use crate::snooze::SNOOZE_DAYS;
+use std::collections::HashMap;
+
 pub const GITHUB_PERSONAL_ACCESS_TOKEN_KEY: &str = "GITHUB_PERSONAL_ACCESS_TOKEN";
 pub const SLACK_WEBHOOK_URL_KEY: &str = "SLACK_WEBHOOK_URL";
 pub const SLACK_SLASH_COMMAND_TOKEN_KEY: &str = "SLACK_SLASH_COMMAND_TOKEN";
+pub const SLACK_SIGNING_SECRET_KEY: &str = "SLACK_SIGNING_SECRET";
+pub const SLACK_BOT_TOKEN_KEY: &str = "SLACK_BOT_TOKEN";
+pub const SLACK_CHANNEL_ID_KEY: &str = "SLACK_CHANNEL_ID";
+pub const SLACK_DIGEST_UPDATE_WINDOW_SECS_KEY: &str = "SLACK_DIGEST_UPDATE_WINDOW_SECS";
+pub const SLACK_HOME_REFRESH_INTERVAL_SECS_KEY: &str = "SLACK_HOME_REFRESH_INTERVAL_SECS";
+pub const SLACK_DIGEST_LAYOUT_KEY: &str = "SLACK_DIGEST_LAYOUT";
+pub const SLACK_INTERACTIVE_DIGEST_KEY: &str = "SLACK_INTERACTIVE_DIGEST";
+pub const SLACK_SNOOZE_DAYS_KEY: &str = "SLACK_SNOOZE_DAYS";
+pub const SLACK_MENTION_KEY: &str = "SLACK_MENTION";
+pub const SLACK_MENTION_CONDITION_KEY: &str = "SLACK_MENTION_CONDITION";
+pub const SLACK_USER_MAP_KEY: &str = "SLACK_USER_MAP";
+pub const SLACK_USER_GITHUB_TOKENS_KEY: &str = "SLACK_USER_GITHUB_TOKENS";
+pub const SLACK_DIGEST_TEMPLATE_KEY: &str = "SLACK_DIGEST_TEMPLATE";
+pub const DIGEST_LOCALE_KEY: &str = "DIGEST_LOCALE";
+pub const SLACK_CHANNEL_LOCALES_KEY: &str = "SLACK_CHANNEL_LOCALES";
+pub const SLACK_USER_LOCALES_KEY: &str = "SLACK_USER_LOCALES";
+pub const DIGEST_PRIORITY_EMOJI_KEY: &str = "DIGEST_PRIORITY_EMOJI";
+pub const DIGEST_HIDE_PRIORITY_LABELS_KEY: &str = "DIGEST_HIDE_PRIORITY_LABELS";
+pub const DISCORD_WEBHOOK_URL_KEY: &str = "DISCORD_WEBHOOK_URL";
+pub const TEAMS_WEBHOOK_URL_KEY: &str = "TEAMS_WEBHOOK_URL";
+pub const SMTP_HOST_KEY: &str = "SMTP_HOST";
+pub const SMTP_PORT_KEY: &str = "SMTP_PORT";
+pub const SMTP_SECURITY_KEY: &str = "SMTP_SECURITY";
+pub const SMTP_USERNAME_KEY: &str = "SMTP_USERNAME";
+pub const SMTP_PASSWORD_KEY: &str = "SMTP_PASSWORD";
+pub const EMAIL_FROM_KEY: &str = "EMAIL_FROM";
+pub const EMAIL_TO_KEY: &str = "EMAIL_TO";
+pub const EMAIL_USER_RECIPIENTS_KEY: &str = "EMAIL_USER_RECIPIENTS";
+pub const WEBHOOK_URL_KEY: &str = "WEBHOOK_URL";
+pub const WEBHOOK_SECRET_KEY: &str = "WEBHOOK_SECRET";
+pub const WEBHOOK_TIMEOUT_SECS_KEY: &str = "WEBHOOK_TIMEOUT_SECS";
+pub const WEBHOOK_MAX_ATTEMPTS_KEY: &str = "WEBHOOK_MAX_ATTEMPTS";
+pub const MATRIX_HOMESERVER_URL_KEY: &str = "MATRIX_HOMESERVER_URL";
+pub const MATRIX_ACCESS_TOKEN_KEY: &str = "MATRIX_ACCESS_TOKEN";
+pub const MATRIX_ROOM_ID_KEY: &str = "MATRIX_ROOM_ID";
+pub const MATRIX_DIGEST_UPDATE_WINDOW_SECS_KEY: &str = "MATRIX_DIGEST_UPDATE_WINDOW_SECS";
+pub const DASHBOARD_TARGET_KEY: &str = "DASHBOARD_TARGET";
+pub const DASHBOARD_PIN_KEY: &str = "DASHBOARD_PIN";
+pub const FEED_TOKENS_KEY: &str = "FEED_TOKENS";
+pub const DIGEST_TRIGGER_TOKEN_KEY: &str = "DIGEST_TRIGGER_TOKEN";
+pub const DIGEST_NOTIFIERS_KEY: &str = "DIGEST_NOTIFIERS";
+pub const STATE_DIR_KEY: &str = "STATE_DIR";
+
+pub const DEFAULT_SLACK_DIGEST_UPDATE_WINDOW_SECS: i64 = 12 * 60 * 60;
+pub const DEFAULT_SLACK_HOME_REFRESH_INTERVAL_SECS: u64 = 60 * 60;
+pub const DEFAULT_SLACK_SNOOZE_DAYS: i64 = 7;
+pub const DEFAULT_WEBHOOK_TIMEOUT_SECS: u64 = 10;
+pub const DEFAULT_WEBHOOK_MAX_ATTEMPTS: u32 = 3;
+pub const DEFAULT_STATE_DIR: &str = ".";
 
 pub fn get_github_personal_access_token() -> String {
     // get token from environment variable
@@ -12,11 +72,306 @@ pub fn get_slack_webhook_url_from_env() -> String {
     std::env::var(SLACK_WEBHOOK_URL_KEY).unwrap()
 }
 
+// None when unset, for the notifiers which are optional
+pub fn get_slack_webhook_url() -> Option<String> {
+    std::env::var(SLACK_WEBHOOK_URL_KEY).ok()
+}
+
 pub fn get_slack_slash_command_token() -> String {
     // get token from environment variable
     std::env::var(SLACK_SLASH_COMMAND_TOKEN_KEY).unwrap()
 }
 
+// None when unset, then no request from Slack can be verified
+pub fn get_slack_signing_secret() -> Option<String> {
+    get_non_empty_var(SLACK_SIGNING_SECRET_KEY)
+}
+
+// Web API delivery is used only when both the bot token and the channel are set
+pub fn get_slack_bot_token() -> Option<String> {
+    std::env::var(SLACK_BOT_TOKEN_KEY).ok()
+}
+
+pub fn get_slack_channel_id() -> Option<String> {
+    std::env::var(SLACK_CHANNEL_ID_KEY).ok()
+}
+
+pub fn get_slack_digest_update_window_secs() -> i64 {
+    std::env::var(SLACK_DIGEST_UPDATE_WINDOW_SECS_KEY)
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .unwrap_or(DEFAULT_SLACK_DIGEST_UPDATE_WINDOW_SECS)
+}
+
+// 0 is left out, an interval of no time can't tick
+pub fn get_slack_home_refresh_interval_secs() -> u64 {
+    std::env::var(SLACK_HOME_REFRESH_INTERVAL_SECS_KEY)
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(DEFAULT_SLACK_HOME_REFRESH_INTERVAL_SECS)
+}
+
+// the digest has an action menu on every issue when this is "true"
+// "message" or "thread", the whole digest in a message when unset or unknown
+// the thread layout needs the Web API, a webhook always gets the whole digest
+pub fn get_slack_digest_layout() -> DigestLayout {
+    std::env::var(SLACK_DIGEST_LAYOUT_KEY)
+        .ok()
+        .and_then(|value| DigestLayout::parse(&value))
+        .unwrap_or_default()
+}
+
+pub fn is_slack_interactive_digest_enabled() -> bool {
+    std::env::var(SLACK_INTERACTIVE_DIGEST_KEY)
+        .map(|value| value == "true")
+        .unwrap_or(false)
+}
+
+// 1, 3 or 7, see snooze::SNOOZE_DAYS
+pub fn get_slack_snooze_days() -> i64 {
+    std::env::var(SLACK_SNOOZE_DAYS_KEY)
+        .ok()
+        .and_then(|days| days.parse().ok())
+        .filter(|days| SNOOZE_DAYS.contains(days))
+        .unwrap_or(DEFAULT_SLACK_SNOOZE_DAYS)
+}
+
+// SLACK_MENTION: "none", "here", "channel", "subteam:<id>" or "assignees"
+// SLACK_MENTION_CONDITION: "always" or "high_or_overdue"
+// unset or unknown values fall back to mentioning the channel always
+pub fn get_slack_mention_policy() -> MentionPolicy {
+    let default = MentionPolicy::default();
+    let target = std::env::var(SLACK_MENTION_KEY)
+        .ok()
+        .and_then(|value| MentionTarget::parse(&value))
+        .unwrap_or(default.target);
+    let condition = std::env::var(SLACK_MENTION_CONDITION_KEY)
+        .ok()
+        .and_then(|value| MentionCondition::parse(&value))
+        .unwrap_or(default.condition);
+    MentionPolicy { target, condition }
+}
+
+// "key=value,key=value", entries without "=" or with an empty side are ignored
+fn get_key_value_map(key: &str) -> HashMap<String, String> {
+    std::env::var(key)
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|entry| entry.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .filter(|(k, v)| !k.is_empty() && !v.is_empty())
+        .collect()
+}
+
+// static entries of GitHub login to Slack user id, e.g. "octocat=U0123,hubot=U0456"
+// they take precedence over the links in the state dir
+pub fn get_slack_user_map() -> HashMap<String, String> {
+    get_key_value_map(SLACK_USER_MAP_KEY)
+}
+
+// GitHub tokens of Slack users for their personal digests, e.g. "U0123=ghp_xxx"
+pub fn get_slack_user_github_tokens() -> HashMap<String, String> {
+    get_key_value_map(SLACK_USER_GITHUB_TOKENS_KEY)
+}
+
+// path to a Tera template of the digest sections, the built-in one when unset
+pub fn get_slack_digest_template_path() -> Option<String> {
+    std::env::var(SLACK_DIGEST_TEMPLATE_KEY)
+        .ok()
+        .filter(|path| !path.is_empty())
+}
+
+fn get_locale_map(key: &str) -> HashMap<String, Locale> {
+    get_key_value_map(key)
+        .into_iter()
+        .filter_map(|(id, locale)| Locale::parse(&locale).map(|locale| (id, locale)))
+        .collect()
+}
+
+// DIGEST_LOCALE: "ja" or "en", Japanese when unset or unknown
+// SLACK_CHANNEL_LOCALES and SLACK_USER_LOCALES: e.g. "C0123=en,U0456=ja" by Slack id
+pub fn get_locale_settings() -> LocaleSettings {
+    LocaleSettings {
+        default: std::env::var(DIGEST_LOCALE_KEY)
+            .ok()
+            .and_then(|value| Locale::parse(&value))
+            .unwrap_or_default(),
+        channels: get_locale_map(SLACK_CHANNEL_LOCALES_KEY),
+        users: get_locale_map(SLACK_USER_LOCALES_KEY),
+    }
+}
+
+// DIGEST_PRIORITY_EMOJI: e.g. "high=:fire:,none=" overrides the default emoji,
+// an empty value leaves out the emoji of that priority
+// DIGEST_HIDE_PRIORITY_LABELS: "true" to hide the priority label in its own section
+pub fn get_digest_style() -> DigestStyle {
+    let mut style = DigestStyle::default();
+    let emoji = std::env::var(DIGEST_PRIORITY_EMOJI_KEY).unwrap_or_default();
+    for (key, value) in emoji.split(',').filter_map(|entry| entry.split_once('=')) {
+        if let Some(priority) = Priority::parse(key.trim()) {
+            style
+                .priority_emoji
+                .insert(priority, value.trim().to_string());
+        }
+    }
+    style.hide_priority_labels = std::env::var(DIGEST_HIDE_PRIORITY_LABELS_KEY)
+        .map(|value| value == "true")
+        .unwrap_or(false);
+    style
+}
+
+pub fn get_discord_webhook_url() -> Option<String> {
+    std::env::var(DISCORD_WEBHOOK_URL_KEY)
+        .ok()
+        .filter(|url| !url.is_empty())
+}
+
+// an incoming webhook or a Workflows endpoint of Teams
+pub fn get_teams_webhook_url() -> Option<String> {
+    std::env::var(TEAMS_WEBHOOK_URL_KEY)
+        .ok()
+        .filter(|url| !url.is_empty())
+}
+
+fn get_non_empty_var(key: &str) -> Option<String> {
+    std::env::var(key).ok().filter(|value| !value.is_empty())
+}
+
+// SMTP_SECURITY: "starttls", "tls" or "none", STARTTLS when unset or unknown
+// SMTP_PORT: the default port of the security when unset
+// None when SMTP_HOST is unset
+pub fn get_smtp_settings() -> Option<SmtpSettings> {
+    Some(SmtpSettings {
+        host: get_non_empty_var(SMTP_HOST_KEY)?,
+        port: std::env::var(SMTP_PORT_KEY)
+            .ok()
+            .and_then(|value| value.parse().ok()),
+        security: std::env::var(SMTP_SECURITY_KEY)
+            .ok()
+            .and_then(|value| SmtpSecurity::parse(&value))
+            .unwrap_or_default(),
+        username: get_non_empty_var(SMTP_USERNAME_KEY),
+        password: get_non_empty_var(SMTP_PASSWORD_KEY),
+    })
+}
+
+// e.g. "Tasks <tasks@example.com>"
+pub fn get_email_from() -> Option<String> {
+    get_non_empty_var(EMAIL_FROM_KEY)
+}
+
+// the recipients of the shared digest, e.g. "alice@example.com,bob@example.com"
+pub fn get_email_to() -> Vec<String> {
+    std::env::var(EMAIL_TO_KEY)
+        .unwrap_or_default()
+        .split(',')
+        .map(|address| address.trim().to_string())
+        .filter(|address| !address.is_empty())
+        .collect()
+}
+
+// the recipient of the personal digest of a Slack user, e.g. "U0123=alice@example.com"
+pub fn get_email_user_recipients() -> HashMap<String, String> {
+    get_key_value_map(EMAIL_USER_RECIPIENTS_KEY)
+}
+
+// the URL and the secret which the request is signed by, None without either
+pub fn get_webhook_url_and_secret() -> Option<(String, String)> {
+    Some((
+        get_non_empty_var(WEBHOOK_URL_KEY)?,
+        get_non_empty_var(WEBHOOK_SECRET_KEY)?,
+    ))
+}
+
+pub fn get_webhook_timeout_secs() -> u64 {
+    std::env::var(WEBHOOK_TIMEOUT_SECS_KEY)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(DEFAULT_WEBHOOK_TIMEOUT_SECS)
+}
+
+// including the first attempt, at least 1
+pub fn get_webhook_max_attempts() -> u32 {
+    std::env::var(WEBHOOK_MAX_ATTEMPTS_KEY)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .filter(|attempts| *attempts > 0)
+        .unwrap_or(DEFAULT_WEBHOOK_MAX_ATTEMPTS)
+}
+
+// the homeserver, e.g. "https://matrix.example.org", the access token and the room id
+// None without any of them
+pub fn get_matrix_settings() -> Option<(String, String, String)> {
+    Some((
+        get_non_empty_var(MATRIX_HOMESERVER_URL_KEY)?,
+        get_non_empty_var(MATRIX_ACCESS_TOKEN_KEY)?,
+        get_non_empty_var(MATRIX_ROOM_ID_KEY)?,
+    ))
+}
+
+// the same default as the Slack digest
+pub fn get_matrix_digest_update_window_secs() -> i64 {
+    std::env::var(MATRIX_DIGEST_UPDATE_WINDOW_SECS_KEY)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_SLACK_DIGEST_UPDATE_WINDOW_SECS)
+}
+
+// "owner/repo" or "owner/repo#12" for an issue, "gist" or "gist:{id}" for a secret gist
+// None when unset or invalid
+pub fn get_dashboard_target() -> Option<DashboardTarget> {
+    let value = get_non_empty_var(DASHBOARD_TARGET_KEY)?;
+    let target = DashboardTarget::parse(&value);
+    if target.is_none() {
+        println!("Invalid {DASHBOARD_TARGET_KEY}: {value}");
+    }
+    target
+}
+
+// only an issue can be pinned, gists can't be pinned by the API
+pub fn is_dashboard_pin_enabled() -> bool {
+    std::env::var(DASHBOARD_PIN_KEY)
+        .map(|value| value == "true")
+        .unwrap_or(false)
+}
+
+// secret tokens of the feeds by GitHub login, e.g. "octocat=3f9a..."
+// a user without a token has no feed
+pub fn get_feed_tokens() -> HashMap<String, String> {
+    get_key_value_map(FEED_TOKENS_KEY)
+}
+
+// the bearer token of POST /digest, which delivers the digest of the team by the notifiers
+// e.g. for a scheduler, the route is disabled when unset
+pub fn get_digest_trigger_token() -> Option<String> {
+    get_non_empty_var(DIGEST_TRIGGER_TOKEN_KEY)
+}
+
+// the notifiers to deliver the digest by, e.g. "slack-api,discord"
+// when unset, the Slack Web API with a bot token and a channel, otherwise the Slack webhook
+pub fn get_notifier_names() -> Vec<String> {
+    let names: Vec<String> = std::env::var(DIGEST_NOTIFIERS_KEY)
+        .unwrap_or_default()
+        .split(',')
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect();
+    if !names.is_empty() {
+        return names;
+    }
+    match (get_slack_bot_token(), get_slack_channel_id()) {
+        (Some(_), Some(_)) => vec![SLACK_API_NOTIFIER.to_string()],
+        _ => vec![SLACK_WEBHOOK_NOTIFIER.to_string()],
+    }
+}
+
+// directory for the state files which must survive restarts
+pub fn get_state_dir() -> String {
+    std::env::var(STATE_DIR_KEY).unwrap_or_else(|_| DEFAULT_STATE_DIR.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -43,6 +398,7 @@ mod tests {
         std::env::set_var(SLACK_WEBHOOK_URL_KEY, test_url);
         let url = get_slack_webhook_url_from_env();
         assert_eq!(url, test_url);
+        assert_eq!(get_slack_webhook_url(), Some(test_url.to_string()));
     }
 
     #[test]
@@ -68,4 +424,367 @@ mod tests {
         std::env::remove_var(SLACK_SLASH_COMMAND_TOKEN_KEY);
         get_slack_slash_command_token();
     }
+
+    #[test]
+    fn test_get_slack_signing_secret() {
+        let test_secret = "test_secret";
+        std::env::set_var(SLACK_SIGNING_SECRET_KEY, test_secret);
+        assert_eq!(get_slack_signing_secret(), Some(test_secret.to_string()));
+        // reset environment variable
+        std::env::remove_var(SLACK_SIGNING_SECRET_KEY);
+        assert_eq!(get_slack_signing_secret(), None);
+    }
+
+    #[test]
+    fn test_get_slack_bot_token_1() {
+        std::env::set_var(SLACK_BOT_TOKEN_KEY, "xoxb-test");
+        assert_eq!(get_slack_bot_token(), Some("xoxb-test".to_string()));
+        std::env::remove_var(SLACK_BOT_TOKEN_KEY);
+        assert_eq!(get_slack_bot_token(), None);
+    }
+
+    #[test]
+    fn test_get_slack_digest_update_window_secs_1() {
+        std::env::set_var(SLACK_DIGEST_UPDATE_WINDOW_SECS_KEY, "60");
+        assert_eq!(get_slack_digest_update_window_secs(), 60);
+        std::env::set_var(SLACK_DIGEST_UPDATE_WINDOW_SECS_KEY, "invalid");
+        assert_eq!(
+            get_slack_digest_update_window_secs(),
+            DEFAULT_SLACK_DIGEST_UPDATE_WINDOW_SECS
+        );
+        std::env::remove_var(SLACK_DIGEST_UPDATE_WINDOW_SECS_KEY);
+    }
+
+    #[test]
+    fn test_get_slack_home_refresh_interval_secs_1() {
+        std::env::set_var(SLACK_HOME_REFRESH_INTERVAL_SECS_KEY, "120");
+        assert_eq!(get_slack_home_refresh_interval_secs(), 120);
+        std::env::set_var(SLACK_HOME_REFRESH_INTERVAL_SECS_KEY, "0");
+        assert_eq!(
+            get_slack_home_refresh_interval_secs(),
+            DEFAULT_SLACK_HOME_REFRESH_INTERVAL_SECS
+        );
+        std::env::remove_var(SLACK_HOME_REFRESH_INTERVAL_SECS_KEY);
+        assert_eq!(
+            get_slack_home_refresh_interval_secs(),
+            DEFAULT_SLACK_HOME_REFRESH_INTERVAL_SECS
+        );
+    }
+
+    #[test]
+    fn test_is_slack_interactive_digest_enabled_1() {
+        std::env::set_var(SLACK_INTERACTIVE_DIGEST_KEY, "true");
+        assert!(is_slack_interactive_digest_enabled());
+        std::env::set_var(SLACK_INTERACTIVE_DIGEST_KEY, "false");
+        assert!(!is_slack_interactive_digest_enabled());
+        std::env::remove_var(SLACK_INTERACTIVE_DIGEST_KEY);
+        assert!(!is_slack_interactive_digest_enabled());
+    }
+
+    #[test]
+    fn test_get_slack_snooze_days_1() {
+        std::env::set_var(SLACK_SNOOZE_DAYS_KEY, "3");
+        assert_eq!(get_slack_snooze_days(), 3);
+        std::env::set_var(SLACK_SNOOZE_DAYS_KEY, "2");
+        assert_eq!(get_slack_snooze_days(), DEFAULT_SLACK_SNOOZE_DAYS);
+        std::env::remove_var(SLACK_SNOOZE_DAYS_KEY);
+        assert_eq!(get_slack_snooze_days(), DEFAULT_SLACK_SNOOZE_DAYS);
+    }
+
+    #[test]
+    fn test_get_slack_mention_policy() {
+        std::env::remove_var(SLACK_MENTION_KEY);
+        std::env::remove_var(SLACK_MENTION_CONDITION_KEY);
+        assert_eq!(get_slack_mention_policy(), MentionPolicy::default());
+
+        std::env::set_var(SLACK_MENTION_KEY, "subteam:S1");
+        std::env::set_var(SLACK_MENTION_CONDITION_KEY, "high_or_overdue");
+        assert_eq!(
+            get_slack_mention_policy(),
+            MentionPolicy {
+                target: MentionTarget::UserGroup("S1".to_string()),
+                condition: MentionCondition::HighPriorityOrOverdue,
+            }
+        );
+
+        std::env::set_var(SLACK_MENTION_KEY, "unknown");
+        assert_eq!(get_slack_mention_policy().target, MentionTarget::Channel);
+        std::env::remove_var(SLACK_MENTION_KEY);
+        std::env::remove_var(SLACK_MENTION_CONDITION_KEY);
+    }
+
+    #[test]
+    fn test_get_slack_user_map() {
+        std::env::remove_var(SLACK_USER_MAP_KEY);
+        assert!(get_slack_user_map().is_empty());
+
+        std::env::set_var(SLACK_USER_MAP_KEY, "octocat=U1, hubot = U2,broken,=U3");
+        let map = get_slack_user_map();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["octocat"], "U1");
+        assert_eq!(map["hubot"], "U2");
+        std::env::remove_var(SLACK_USER_MAP_KEY);
+    }
+
+    #[test]
+    fn test_get_slack_user_github_tokens() {
+        std::env::set_var(SLACK_USER_GITHUB_TOKENS_KEY, "U1=token1,U2=");
+        let tokens = get_slack_user_github_tokens();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens["U1"], "token1");
+        std::env::remove_var(SLACK_USER_GITHUB_TOKENS_KEY);
+        assert!(get_slack_user_github_tokens().is_empty());
+    }
+
+    #[test]
+    fn test_get_slack_digest_template_path_1() {
+        std::env::set_var(SLACK_DIGEST_TEMPLATE_KEY, "digest.tera");
+        assert_eq!(
+            get_slack_digest_template_path(),
+            Some("digest.tera".to_string())
+        );
+        std::env::set_var(SLACK_DIGEST_TEMPLATE_KEY, "");
+        assert_eq!(get_slack_digest_template_path(), None);
+        std::env::remove_var(SLACK_DIGEST_TEMPLATE_KEY);
+        assert_eq!(get_slack_digest_template_path(), None);
+    }
+
+    #[test]
+    fn test_get_locale_settings() {
+        std::env::remove_var(DIGEST_LOCALE_KEY);
+        std::env::remove_var(SLACK_CHANNEL_LOCALES_KEY);
+        std::env::remove_var(SLACK_USER_LOCALES_KEY);
+        assert_eq!(get_locale_settings(), LocaleSettings::default());
+
+        std::env::set_var(DIGEST_LOCALE_KEY, "en-US");
+        std::env::set_var(SLACK_CHANNEL_LOCALES_KEY, "C1=ja,C2=fr");
+        std::env::set_var(SLACK_USER_LOCALES_KEY, "U1=en");
+        let settings = get_locale_settings();
+        assert_eq!(settings.default, Locale::En);
+        assert_eq!(
+            settings.channels,
+            HashMap::from([("C1".to_string(), Locale::Ja)])
+        );
+        assert_eq!(
+            settings.users,
+            HashMap::from([("U1".to_string(), Locale::En)])
+        );
+
+        std::env::set_var(DIGEST_LOCALE_KEY, "unknown");
+        assert_eq!(get_locale_settings().default, Locale::Ja);
+        std::env::remove_var(DIGEST_LOCALE_KEY);
+        std::env::remove_var(SLACK_CHANNEL_LOCALES_KEY);
+        std::env::remove_var(SLACK_USER_LOCALES_KEY);
+    }
+
+    #[test]
+    fn test_get_digest_style() {
+        std::env::remove_var(DIGEST_PRIORITY_EMOJI_KEY);
+        std::env::remove_var(DIGEST_HIDE_PRIORITY_LABELS_KEY);
+        assert_eq!(get_digest_style(), DigestStyle::default());
+
+        std::env::set_var(DIGEST_PRIORITY_EMOJI_KEY, "high=:fire:, none= ,unknown=x");
+        std::env::set_var(DIGEST_HIDE_PRIORITY_LABELS_KEY, "true");
+        let style = get_digest_style();
+        assert_eq!(style.emoji(Priority::High), ":fire:");
+        assert_eq!(style.emoji(Priority::Medium), "🟠");
+        assert_eq!(style.emoji(Priority::None), "");
+        assert_eq!(style.priority_emoji.len(), 4);
+        assert!(style.hide_priority_labels);
+        std::env::remove_var(DIGEST_PRIORITY_EMOJI_KEY);
+        std::env::remove_var(DIGEST_HIDE_PRIORITY_LABELS_KEY);
+    }
+
+    #[test]
+    fn test_get_slack_digest_layout() {
+        std::env::remove_var(SLACK_DIGEST_LAYOUT_KEY);
+        assert_eq!(get_slack_digest_layout(), DigestLayout::Message);
+        std::env::set_var(SLACK_DIGEST_LAYOUT_KEY, "thread");
+        assert_eq!(get_slack_digest_layout(), DigestLayout::Thread);
+        std::env::set_var(SLACK_DIGEST_LAYOUT_KEY, "unknown");
+        assert_eq!(get_slack_digest_layout(), DigestLayout::Message);
+        std::env::remove_var(SLACK_DIGEST_LAYOUT_KEY);
+    }
+
+    #[test]
+    fn test_get_notifier_names() {
+        std::env::set_var(DIGEST_NOTIFIERS_KEY, "slack-api, slack-webhook,");
+        assert_eq!(get_notifier_names(), vec!["slack-api", "slack-webhook"]);
+        std::env::remove_var(DIGEST_NOTIFIERS_KEY);
+    }
+
+    #[test]
+    fn test_get_discord_webhook_url() {
+        std::env::set_var(
+            DISCORD_WEBHOOK_URL_KEY,
+            "https://discord.com/api/webhooks/1/token",
+        );
+        assert_eq!(
+            get_discord_webhook_url(),
+            Some("https://discord.com/api/webhooks/1/token".to_string())
+        );
+        std::env::set_var(DISCORD_WEBHOOK_URL_KEY, "");
+        assert_eq!(get_discord_webhook_url(), None);
+        std::env::remove_var(DISCORD_WEBHOOK_URL_KEY);
+    }
+
+    #[test]
+    fn test_get_teams_webhook_url() {
+        std::env::set_var(
+            TEAMS_WEBHOOK_URL_KEY,
+            "https://example.webhook.office.com/webhookb2/1",
+        );
+        assert_eq!(
+            get_teams_webhook_url(),
+            Some("https://example.webhook.office.com/webhookb2/1".to_string())
+        );
+        std::env::remove_var(TEAMS_WEBHOOK_URL_KEY);
+        assert_eq!(get_teams_webhook_url(), None);
+    }
+
+    #[test]
+    fn test_get_smtp_settings() {
+        std::env::remove_var(SMTP_HOST_KEY);
+        assert_eq!(get_smtp_settings(), None);
+        std::env::set_var(SMTP_HOST_KEY, "smtp.example.com");
+        std::env::set_var(SMTP_SECURITY_KEY, "tls");
+        std::env::set_var(SMTP_USERNAME_KEY, "user");
+        std::env::set_var(SMTP_PASSWORD_KEY, "password");
+        assert_eq!(
+            get_smtp_settings(),
+            Some(SmtpSettings {
+                host: "smtp.example.com".to_string(),
+                port: None,
+                security: SmtpSecurity::Tls,
+                username: Some("user".to_string()),
+                password: Some("password".to_string()),
+            })
+        );
+        std::env::set_var(SMTP_PORT_KEY, "2525");
+        std::env::set_var(SMTP_SECURITY_KEY, "ssl");
+        let settings = get_smtp_settings().unwrap();
+        assert_eq!(settings.port, Some(2525));
+        assert_eq!(settings.security, SmtpSecurity::StartTls);
+        std::env::remove_var(SMTP_HOST_KEY);
+        std::env::remove_var(SMTP_PORT_KEY);
+        std::env::remove_var(SMTP_SECURITY_KEY);
+        std::env::remove_var(SMTP_USERNAME_KEY);
+        std::env::remove_var(SMTP_PASSWORD_KEY);
+    }
+
+    #[test]
+    fn test_get_email_recipients() {
+        std::env::set_var(EMAIL_TO_KEY, "alice@example.com, bob@example.com,");
+        assert_eq!(
+            get_email_to(),
+            vec![
+                "alice@example.com".to_string(),
+                "bob@example.com".to_string()
+            ]
+        );
+        std::env::set_var(EMAIL_USER_RECIPIENTS_KEY, "U0123=alice@example.com");
+        assert_eq!(
+            get_email_user_recipients().get("U0123"),
+            Some(&"alice@example.com".to_string())
+        );
+        std::env::remove_var(EMAIL_TO_KEY);
+        std::env::remove_var(EMAIL_USER_RECIPIENTS_KEY);
+        assert!(get_email_to().is_empty());
+    }
+
+    #[test]
+    fn test_get_webhook_settings() {
+        std::env::set_var(WEBHOOK_URL_KEY, "https://example.com/hooks/tasks");
+        std::env::remove_var(WEBHOOK_SECRET_KEY);
+        assert_eq!(get_webhook_url_and_secret(), None);
+        std::env::set_var(WEBHOOK_SECRET_KEY, "secret");
+        assert_eq!(
+            get_webhook_url_and_secret(),
+            Some((
+                "https://example.com/hooks/tasks".to_string(),
+                "secret".to_string()
+            ))
+        );
+        std::env::remove_var(WEBHOOK_URL_KEY);
+        std::env::remove_var(WEBHOOK_SECRET_KEY);
+
+        std::env::set_var(WEBHOOK_TIMEOUT_SECS_KEY, "30");
+        std::env::set_var(WEBHOOK_MAX_ATTEMPTS_KEY, "0");
+        assert_eq!(get_webhook_timeout_secs(), 30);
+        assert_eq!(get_webhook_max_attempts(), DEFAULT_WEBHOOK_MAX_ATTEMPTS);
+        std::env::remove_var(WEBHOOK_TIMEOUT_SECS_KEY);
+        std::env::remove_var(WEBHOOK_MAX_ATTEMPTS_KEY);
+        assert_eq!(get_webhook_timeout_secs(), DEFAULT_WEBHOOK_TIMEOUT_SECS);
+    }
+
+    #[test]
+    fn test_get_matrix_settings() {
+        std::env::set_var(MATRIX_HOMESERVER_URL_KEY, "https://matrix.example.org");
+        std::env::set_var(MATRIX_ACCESS_TOKEN_KEY, "token");
+        std::env::remove_var(MATRIX_ROOM_ID_KEY);
+        assert_eq!(get_matrix_settings(), None);
+        std::env::set_var(MATRIX_ROOM_ID_KEY, "!room:example.org");
+        assert_eq!(
+            get_matrix_settings(),
+            Some((
+                "https://matrix.example.org".to_string(),
+                "token".to_string(),
+                "!room:example.org".to_string()
+            ))
+        );
+        std::env::remove_var(MATRIX_HOMESERVER_URL_KEY);
+        std::env::remove_var(MATRIX_ACCESS_TOKEN_KEY);
+        std::env::remove_var(MATRIX_ROOM_ID_KEY);
+
+        std::env::set_var(MATRIX_DIGEST_UPDATE_WINDOW_SECS_KEY, "600");
+        assert_eq!(get_matrix_digest_update_window_secs(), 600);
+        std::env::remove_var(MATRIX_DIGEST_UPDATE_WINDOW_SECS_KEY);
+        assert_eq!(
+            get_matrix_digest_update_window_secs(),
+            DEFAULT_SLACK_DIGEST_UPDATE_WINDOW_SECS
+        );
+    }
+
+    #[test]
+    fn test_get_dashboard_target() {
+        std::env::set_var(DASHBOARD_TARGET_KEY, "owner/repo");
+        assert_eq!(
+            get_dashboard_target(),
+            Some(DashboardTarget::Issue {
+                owner: "owner".to_string(),
+                repo: "repo".to_string(),
+                number: None,
+            })
+        );
+        std::env::set_var(DASHBOARD_TARGET_KEY, "owner");
+        assert_eq!(get_dashboard_target(), None);
+        std::env::remove_var(DASHBOARD_TARGET_KEY);
+        assert_eq!(get_dashboard_target(), None);
+
+        std::env::set_var(DASHBOARD_PIN_KEY, "true");
+        assert!(is_dashboard_pin_enabled());
+        std::env::remove_var(DASHBOARD_PIN_KEY);
+        assert!(!is_dashboard_pin_enabled());
+    }
+
+    #[test]
+    fn test_get_feed_tokens() {
+        std::env::set_var(FEED_TOKENS_KEY, "octocat=secret, hubot=");
+        assert_eq!(
+            get_feed_tokens(),
+            HashMap::from([("octocat".to_string(), "secret".to_string())])
+        );
+        std::env::remove_var(FEED_TOKENS_KEY);
+        assert!(get_feed_tokens().is_empty());
+    }
+
+    #[test]
+    fn test_get_digest_trigger_token() {
+        std::env::set_var(DIGEST_TRIGGER_TOKEN_KEY, "secret");
+        assert_eq!(get_digest_trigger_token(), Some("secret".to_string()));
+        std::env::set_var(DIGEST_TRIGGER_TOKEN_KEY, "");
+        assert_eq!(get_digest_trigger_token(), None);
+        std::env::remove_var(DIGEST_TRIGGER_TOKEN_KEY);
+        assert_eq!(get_digest_trigger_token(), None);
+    }
 }
@@ -14,6 +14,211 @@ impl std::fmt::Display for GetIssueError {
 
 impl error::Error for GetIssueError {}
 
+#[derive(Debug, Clone)]
+pub struct GitHubApiError {
+    pub message: String,
+}
+
+// implement for GitHubApiError
+impl std::fmt::Display for GitHubApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl error::Error for GitHubApiError {}
+
+#[derive(Debug, Clone)]
+pub struct SlackApiError {
+    pub message: String,
+}
+
+// implement for SlackApiError
+impl std::fmt::Display for SlackApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl error::Error for SlackApiError {}
+
+#[derive(Debug, Clone)]
+pub struct StoreError {
+    pub message: String,
+}
+
+// implement for StoreError
+impl std::fmt::Display for StoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl error::Error for StoreError {}
+
+#[derive(Debug, Clone)]
+pub struct DigestTemplateError {
+    pub message: String,
+}
+
+// implement for DigestTemplateError
+impl std::fmt::Display for DigestTemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl error::Error for DigestTemplateError {}
+
+#[derive(Debug, Clone)]
+pub struct DiscordApiError {
+    pub message: String,
+}
+
+// implement for DiscordApiError
+impl std::fmt::Display for DiscordApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl error::Error for DiscordApiError {}
+
+#[derive(Debug, Clone)]
+pub struct TeamsApiError {
+    pub message: String,
+}
+
+// implement for TeamsApiError
+impl std::fmt::Display for TeamsApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl error::Error for TeamsApiError {}
+
+#[derive(Debug, Clone)]
+pub struct EmailError {
+    pub message: String,
+}
+
+// implement for EmailError
+impl std::fmt::Display for EmailError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl error::Error for EmailError {}
+
+#[derive(Debug, Clone)]
+pub struct WebhookError {
+    pub message: String,
+}
+
+// implement for WebhookError
+impl std::fmt::Display for WebhookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl error::Error for WebhookError {}
+
+#[derive(Debug, Clone)]
+pub struct MatrixApiError {
+    pub message: String,
+}
+
+// implement for MatrixApiError
+impl std::fmt::Display for MatrixApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl error::Error for MatrixApiError {}
+
+#[derive(Debug, Clone)]
+pub struct NotifyError {
+    pub message: String,
+}
+
+// implement for NotifyError
+impl std::fmt::Display for NotifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl error::Error for NotifyError {}
+
+// a notifier reports the error of the API it delivers with
+impl From<SlackApiError> for NotifyError {
+    fn from(err: SlackApiError) -> Self {
+        Self {
+            message: err.message,
+        }
+    }
+}
+
+impl From<DiscordApiError> for NotifyError {
+    fn from(err: DiscordApiError) -> Self {
+        Self {
+            message: err.message,
+        }
+    }
+}
+
+impl From<TeamsApiError> for NotifyError {
+    fn from(err: TeamsApiError) -> Self {
+        Self {
+            message: err.message,
+        }
+    }
+}
+
+impl From<EmailError> for NotifyError {
+    fn from(err: EmailError) -> Self {
+        Self {
+            message: err.message,
+        }
+    }
+}
+
+impl From<WebhookError> for NotifyError {
+    fn from(err: WebhookError) -> Self {
+        Self {
+            message: err.message,
+        }
+    }
+}
+
+impl From<MatrixApiError> for NotifyError {
+    fn from(err: MatrixApiError) -> Self {
+        Self {
+            message: err.message,
+        }
+    }
+}
+
+impl From<GitHubApiError> for NotifyError {
+    fn from(err: GitHubApiError) -> Self {
+        Self {
+            message: err.message,
+        }
+    }
+}
+
+impl From<GitHubApiError> for GetIssueError {
+    fn from(err: GitHubApiError) -> Self {
+        Self {
+            message: err.message,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -25,4 +230,107 @@ mod tests {
         };
         assert_eq!(error.message, "test error");
     }
+
+    #[test]
+    fn test_github_api_error_1() {
+        let error = GitHubApiError {
+            message: "test error".to_string(),
+        };
+        assert_eq!(error.to_string(), "test error");
+    }
+
+    #[test]
+    fn test_slack_api_error_1() {
+        let error = SlackApiError {
+            message: "channel_not_found".to_string(),
+        };
+        assert_eq!(error.to_string(), "channel_not_found");
+    }
+
+    #[test]
+    fn test_store_error_1() {
+        let error = StoreError {
+            message: "test error".to_string(),
+        };
+        assert_eq!(error.to_string(), "test error");
+    }
+
+    #[test]
+    fn test_digest_template_error_1() {
+        let error = DigestTemplateError {
+            message: "test error".to_string(),
+        };
+        assert_eq!(error.to_string(), "test error");
+    }
+
+    #[test]
+    fn test_notify_error_1() {
+        let error = NotifyError::from(SlackApiError {
+            message: "channel_not_found".to_string(),
+        });
+        assert_eq!(error.to_string(), "channel_not_found");
+    }
+
+    #[test]
+    fn test_discord_api_error_1() {
+        let error = DiscordApiError {
+            message: "Discord Webhook Error: 400".to_string(),
+        };
+        assert_eq!(
+            NotifyError::from(error).to_string(),
+            "Discord Webhook Error: 400"
+        );
+    }
+
+    #[test]
+    fn test_teams_api_error_1() {
+        let error = TeamsApiError {
+            message: "Teams Webhook Error: 400".to_string(),
+        };
+        assert_eq!(
+            NotifyError::from(error).to_string(),
+            "Teams Webhook Error: 400"
+        );
+    }
+
+    #[test]
+    fn test_email_error_1() {
+        let error = EmailError {
+            message: "Email Error: no recipients".to_string(),
+        };
+        assert_eq!(
+            NotifyError::from(error).to_string(),
+            "Email Error: no recipients"
+        );
+    }
+
+    #[test]
+    fn test_webhook_error_1() {
+        let error = WebhookError {
+            message: "Webhook Error: 503".to_string(),
+        };
+        assert_eq!(NotifyError::from(error).to_string(), "Webhook Error: 503");
+    }
+
+    #[test]
+    fn test_matrix_api_error_1() {
+        let error = MatrixApiError {
+            message: "Matrix API Error: 403 M_FORBIDDEN".to_string(),
+        };
+        assert_eq!(
+            NotifyError::from(error).to_string(),
+            "Matrix API Error: 403 M_FORBIDDEN"
+        );
+    }
+
+    #[test]
+    fn test_github_api_error_into_notify_error() {
+        let error = GitHubApiError {
+            message: "Create Issue Error: status code is 410 Gone".to_string(),
+        };
+        assert_eq!(
+            NotifyError::from(error).to_string(),
+            "Create Issue Error: status code is 410 Gone"
+        );
+    }
 }
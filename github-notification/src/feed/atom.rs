@@ -0,0 +1,145 @@
+use crate::i18n::{text_with, Locale};
+use crate::models::{Issue, SortedIssues};
+use crate::render::group_views;
+use crate::render::html::{escape_html, HtmlRenderer};
+use crate::render::DigestRenderer;
+use crate::slack::style::DigestStyle;
+use chrono::{DateTime, SecondsFormat, Utc};
+
+// https://datatracker.ietf.org/doc/html/rfc4287
+pub const ATOM_CONTENT_TYPE: &str = "application/atom+xml; charset=utf-8";
+
+// the id of GitHub is kept when the issue is transferred, unlike its URL
+pub fn issue_entry_id(issue: &Issue) -> String {
+    format!("tag:github.com,2008:Issue/{}", issue.id)
+}
+
+fn timestamp(date: DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+// the issues as a feed, from high to no priority
+// an entry is updated when GitHub says the issue was, so readers don't show it again on every fetch
+pub fn create_atom_feed(
+    login: &str,
+    issues: &SortedIssues,
+    locale: Locale,
+    style: &DigestStyle,
+    now: DateTime<Utc>,
+) -> String {
+    let groups = group_views(issues, locale, style, now);
+    let mut entries = Vec::new();
+    let mut feed_updated: Option<DateTime<Utc>> = None;
+    for group in &groups {
+        for view in &group.issues {
+            let issue = view.issue;
+            let updated = issue.updated_at.or(issue.created_at).unwrap_or(now);
+            feed_updated = feed_updated.max(Some(updated));
+            let mut entry = vec![
+                "<entry>".to_string(),
+                format!("<id>{}</id>", issue_entry_id(issue)),
+                format!("<title>{}</title>", escape_html(&issue.title)),
+                format!(
+                    "<link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>",
+                    escape_html(&issue.html_url)
+                ),
+                format!("<updated>{}</updated>", timestamp(updated)),
+            ];
+            if let Some(created_at) = issue.created_at {
+                entry.push(format!("<published>{}</published>", timestamp(created_at)));
+            }
+            entry.push(format!(
+                "<category term=\"{}\" label=\"{}\"/>",
+                group.priority.key(),
+                escape_html(&group.title)
+            ));
+            entry.push(format!(
+                "<content type=\"html\">{}</content>",
+                escape_html(&HtmlRenderer.render_issue(view))
+            ));
+            entry.push("</entry>".to_string());
+            entries.push(entry.join("\n"));
+        }
+    }
+
+    let title = text_with(locale, "feed.title", &[("login", login)]);
+    let mut lines = vec![
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>".to_string(),
+        format!(
+            "<feed xmlns=\"http://www.w3.org/2005/Atom\" xml:lang=\"{}\">",
+            locale.code()
+        ),
+        format!(
+            "<id>urn:github-notification:feeds:{}</id>",
+            escape_html(login)
+        ),
+        format!("<title>{}</title>", escape_html(&title)),
+        format!(
+            "<updated>{}</updated>",
+            timestamp(feed_updated.unwrap_or(now))
+        ),
+        format!("<author><name>{}</name></author>", escape_html(login)),
+        "<generator>github-notification</generator>".to_string(),
+    ];
+    lines.extend(entries);
+    lines.push("</feed>".to_string());
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{issue, now};
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_create_atom_feed() {
+        let mut high = issue(1, "a <b> & c", None);
+        high.id = 101;
+        high.created_at = Some(Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap());
+        high.updated_at = Some(Utc.with_ymd_and_hms(2023, 1, 8, 9, 30, 0).unwrap());
+        let mut low = issue(2, "low", None);
+        low.id = 102;
+        let issues = SortedIssues {
+            priority_high_issues: vec![high],
+            priority_low_issues: vec![low],
+            ..SortedIssues::default()
+        };
+
+        let feed = create_atom_feed(
+            "octocat",
+            &issues,
+            Locale::En,
+            &DigestStyle::default(),
+            now(),
+        );
+        assert!(feed.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\" xml:lang=\"en\">\n"));
+        assert!(feed.contains("<id>urn:github-notification:feeds:octocat</id>"));
+        assert!(feed.contains("<title>Tasks of octocat</title>"));
+        // the latest update of the entries
+        assert!(feed.contains("<updated>2023-01-10T00:00:00Z</updated>\n<author>"));
+        assert!(feed.contains(
+            "<entry>\n<id>tag:github.com,2008:Issue/101</id>\n<title>a &lt;b&gt; &amp; c</title>\n\
+             <link rel=\"alternate\" type=\"text/html\" href=\"https://github.com/owner/name/issues/1\"/>\n\
+             <updated>2023-01-08T09:30:00Z</updated>\n<published>2023-01-01T00:00:00Z</published>\n\
+             <category term=\"high\" label=\"Priority: High\"/>\n"
+        ));
+        assert!(feed.contains("<content type=\"html\">&lt;a href="));
+        assert!(feed.contains("<id>tag:github.com,2008:Issue/102</id>"));
+        assert!(feed.ends_with("</entry>\n</feed>\n"));
+    }
+
+    #[test]
+    fn test_create_atom_feed_empty() {
+        let feed = create_atom_feed(
+            "octocat",
+            &SortedIssues::default(),
+            Locale::Ja,
+            &DigestStyle::default(),
+            now(),
+        );
+        assert!(feed.contains("xml:lang=\"ja\""));
+        assert!(feed.contains("<updated>2023-01-10T00:00:00Z</updated>"));
+        assert!(!feed.contains("<entry>"));
+    }
+}
@@ -0,0 +1,262 @@
+use crate::i18n::{text, Locale};
+use crate::models::{Issue, Milestone, Priority, SortedIssues};
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use std::collections::HashMap;
+
+// https://datatracker.ietf.org/doc/html/rfc5545
+pub const ICALENDAR_CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+// a content line longer than this is folded
+const MAX_LINE_OCTETS: usize = 75;
+
+// commas, semicolons and backslashes separate values, newlines end a line
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+// a long line continues on the next line after a space, without splitting a character
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            // the space counts
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded + "\r\n"
+}
+
+fn date_time(date: DateTime<Utc>) -> String {
+    date.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn date(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+// 1 is the highest and 9 the lowest, 0 is undefined
+fn ical_priority(priority: Priority) -> u8 {
+    match priority {
+        Priority::High => 1,
+        Priority::Medium => 5,
+        Priority::Low => 9,
+        Priority::None => 0,
+    }
+}
+
+// UIDs stay the same between exports, so that calendar clients update the items in place
+pub fn issue_uid(issue: &Issue) -> String {
+    format!("issue-{}@github-notification", issue.id)
+}
+
+// a milestone has no global id, but its number is unique in the repository
+pub fn milestone_uid(repository_full_name: &str, milestone: &Milestone) -> String {
+    format!(
+        "milestone-{}-{}@github-notification",
+        repository_full_name.replace('/', "-"),
+        milestone.number
+    )
+}
+
+// the time of the last change on GitHub, so that an unchanged item looks unchanged
+fn stamp(issue: &Issue, now: DateTime<Utc>) -> DateTime<Utc> {
+    issue.updated_at.or(issue.created_at).unwrap_or(now)
+}
+
+// a milestone is due on a day, GitHub keeps it as a time of the day
+// `stamp` is the latest of its issues, as the event changes with any of them
+fn milestone_event(
+    issue: &Issue,
+    milestone: &Milestone,
+    due_on: DateTime<Utc>,
+    stamp: DateTime<Utc>,
+) -> Vec<String> {
+    let day = due_on.date_naive();
+    let next_day = day.checked_add_days(Days::new(1)).unwrap_or(day);
+    vec![
+        "BEGIN:VEVENT".to_string(),
+        format!(
+            "UID:{}",
+            milestone_uid(&issue.repository.full_name, milestone)
+        ),
+        format!("DTSTAMP:{}", date_time(stamp)),
+        format!("DTSTART;VALUE=DATE:{}", date(day)),
+        format!("DTEND;VALUE=DATE:{}", date(next_day)),
+        format!(
+            "SUMMARY:{}",
+            escape_text(&format!(
+                "{} ({})",
+                milestone.title, issue.repository.full_name
+            ))
+        ),
+        format!("URL:{}", milestone.html_url),
+        "TRANSP:TRANSPARENT".to_string(),
+        "END:VEVENT".to_string(),
+    ]
+}
+
+fn issue_todo(
+    issue: &Issue,
+    priority: Priority,
+    due_on: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    let mut lines = vec![
+        "BEGIN:VTODO".to_string(),
+        format!("UID:{}", issue_uid(issue)),
+        format!("DTSTAMP:{}", date_time(stamp(issue, now))),
+        format!("DUE;VALUE=DATE:{}", date(due_on.date_naive())),
+        format!("SUMMARY:{}", escape_text(&issue.title)),
+        format!("DESCRIPTION:{}", escape_text(&issue.repository.full_name)),
+        format!("URL:{}", issue.html_url),
+        "STATUS:NEEDS-ACTION".to_string(),
+        format!("PRIORITY:{}", ical_priority(priority)),
+    ];
+    if let Some(updated_at) = issue.updated_at {
+        lines.push(format!("LAST-MODIFIED:{}", date_time(updated_at)));
+    }
+    let labels: Vec<String> = issue
+        .labels
+        .iter()
+        .flatten()
+        .map(|label| escape_text(&label.name))
+        .collect();
+    if !labels.is_empty() {
+        lines.push(format!("CATEGORIES:{}", labels.join(",")));
+    }
+    lines.push("END:VTODO".to_string());
+    lines
+}
+
+// a to-do for every issue with a due date, and an event for the due date of every milestone
+// the issues without a due date are left out
+pub fn create_icalendar(issues: &SortedIssues, locale: Locale, now: DateTime<Utc>) -> String {
+    // the issues of a milestone share its event
+    let mut milestone_stamps: HashMap<String, DateTime<Utc>> = HashMap::new();
+    for (_, issues) in issues.groups() {
+        for issue in issues {
+            if let Some(milestone) = &issue.milestone {
+                let uid = milestone_uid(&issue.repository.full_name, milestone);
+                let issue_stamp = stamp(issue, now);
+                milestone_stamps
+                    .entry(uid)
+                    .and_modify(|stamp| *stamp = (*stamp).max(issue_stamp))
+                    .or_insert(issue_stamp);
+            }
+        }
+    }
+
+    let mut components = Vec::new();
+    let mut milestone_uids = Vec::new();
+    for (priority, issues) in issues.groups() {
+        for issue in issues {
+            let Some(milestone) = &issue.milestone else {
+                continue;
+            };
+            let Some(due_on) = milestone.due_on else {
+                continue;
+            };
+            let uid = milestone_uid(&issue.repository.full_name, milestone);
+            if !milestone_uids.contains(&uid) {
+                let stamp = milestone_stamps.get(&uid).copied().unwrap_or(now);
+                milestone_uids.push(uid);
+                components.extend(milestone_event(issue, milestone, due_on, stamp));
+            }
+            components.extend(issue_todo(issue, priority, due_on, now));
+        }
+    }
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//github-notification//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!(
+            "X-WR-CALNAME:{}",
+            escape_text(&text(locale, "calendar.title"))
+        ),
+    ];
+    lines.extend(components);
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|line| fold_line(line)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{issue, now};
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_escape_text() {
+        assert_eq!(escape_text("a, b; c\\d\ne"), "a\\, b\\; c\\\\d\\ne");
+    }
+
+    #[test]
+    fn test_fold_line() {
+        assert_eq!(fold_line("short"), "short\r\n");
+        let line = "a".repeat(80);
+        assert_eq!(
+            fold_line(&line),
+            format!("{}\r\n {}\r\n", "a".repeat(75), "a".repeat(5))
+        );
+        // a character of 3 octets is moved to the next line as a whole
+        let line = format!("{}あ", "a".repeat(73));
+        assert_eq!(fold_line(&line), format!("{}\r\n あ\r\n", "a".repeat(73)));
+    }
+
+    #[test]
+    fn test_create_icalendar() {
+        let due_on = Utc.with_ymd_and_hms(2023, 1, 11, 8, 0, 0).unwrap();
+        let mut first = issue(1, "fix, then ship", Some(due_on));
+        first.id = 101;
+        first.updated_at = Some(Utc.with_ymd_and_hms(2023, 1, 8, 9, 30, 0).unwrap());
+        let mut second = issue(2, "second", Some(due_on));
+        second.id = 102;
+        second.updated_at = Some(Utc.with_ymd_and_hms(2023, 1, 9, 12, 0, 0).unwrap());
+        let mut no_due = issue(3, "no due date", None);
+        no_due.id = 103;
+        let issues = SortedIssues {
+            priority_high_issues: vec![first],
+            priority_low_issues: vec![second, no_due],
+            ..SortedIssues::default()
+        };
+
+        let calendar = create_icalendar(&issues, Locale::En, now());
+        assert!(calendar.starts_with(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//github-notification//EN\r\n"
+        ));
+        assert!(calendar.contains("X-WR-CALNAME:GitHub deadlines\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        // one event for the milestone shared by the issues
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 1);
+        assert!(calendar.contains("DTSTART;VALUE=DATE:20230111\r\nDTEND;VALUE=DATE:20230112\r\n"));
+        // stamped by the latest change among its issues
+        assert!(calendar.contains(
+            "UID:milestone-owner-name-1@github-notification\r\nDTSTAMP:20230109T120000Z\r\n"
+        ));
+        // a to-do for each issue with a due date
+        assert_eq!(calendar.matches("BEGIN:VTODO").count(), 2);
+        assert!(calendar.contains(
+            "BEGIN:VTODO\r\nUID:issue-101@github-notification\r\nDTSTAMP:20230108T093000Z\r\n\
+             DUE;VALUE=DATE:20230111\r\nSUMMARY:fix\\, then ship\r\nDESCRIPTION:owner/name\r\n\
+             URL:https://github.com/owner/name/issues/1\r\nSTATUS:NEEDS-ACTION\r\nPRIORITY:1\r\n\
+             LAST-MODIFIED:20230108T093000Z\r\nCATEGORIES:bug\r\nEND:VTODO\r\n"
+        ));
+        assert!(calendar.contains("UID:issue-102@github-notification\r\n"));
+        assert!(calendar.contains("PRIORITY:9\r\n"));
+        assert!(!calendar.contains("no due date"));
+
+        // the same issues make the same calendar
+        assert_eq!(calendar, create_icalendar(&issues, Locale::En, now()));
+    }
+}
@@ -0,0 +1,190 @@
+// feeds of the task list of a user, which the server serves to feed readers
+pub mod atom;
+pub mod ical;
+
+use crate::errors::GetIssueError;
+use crate::github::{get_assigned_issues_if_modified, Conditional};
+use crate::models::Issue;
+use chrono::NaiveDate;
+use sha2::{Digest as _, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// the token in the URL must be the one of the user, see env::get_feed_tokens
+// compared in constant time, so that it can't be guessed byte by byte
+pub fn verify_feed_token(
+    feed_tokens: &HashMap<String, String>,
+    login: &str,
+    token: Option<&str>,
+) -> bool {
+    match (feed_tokens.get(login), token) {
+        (Some(expected), Some(token)) => {
+            expected.len() == token.len()
+                && expected
+                    .bytes()
+                    .zip(token.bytes())
+                    .fold(0, |acc, (a, b)| acc | (a ^ b))
+                    == 0
+        }
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CachedIssues {
+    // of the GitHub response
+    pub etag: String,
+    pub issues: Vec<Issue>,
+}
+
+// the issues last fetched for each user, for conditional requests to GitHub
+#[derive(Debug, Default)]
+pub struct FeedCache {
+    entries: HashMap<String, CachedIssues>,
+}
+
+// the open issues assigned to the user, with the GitHub ETag when there is one
+// the cached issues are used while GitHub answers they are not modified
+pub async fn get_feed_issues(
+    github_api_addr: &str,
+    token: &str,
+    login: &str,
+    cache: &Mutex<FeedCache>,
+) -> Result<(Vec<Issue>, Option<String>), GetIssueError> {
+    // the lock isn't held while fetching
+    let cached = cache.lock().unwrap().entries.get(login).cloned();
+    let etag = cached.as_ref().map(|cached| cached.etag.as_str());
+    match get_assigned_issues_if_modified(github_api_addr, token, login, etag).await? {
+        Conditional::NotModified => match cached {
+            Some(cached) => Ok((cached.issues, Some(cached.etag))),
+            // only sent with a cached ETag
+            None => Err(GetIssueError {
+                message: "Search Issues Error: not modified without a cache".to_string(),
+            }),
+        },
+        Conditional::Modified { etag, value } => {
+            let mut cache = cache.lock().unwrap();
+            match &etag {
+                Some(etag) => {
+                    cache.entries.insert(
+                        login.to_string(),
+                        CachedIssues {
+                            etag: etag.clone(),
+                            issues: value.clone(),
+                        },
+                    );
+                }
+                None => {
+                    cache.entries.remove(login);
+                }
+            }
+            Ok((value, etag))
+        }
+    }
+}
+
+// a weak ETag of a feed, from the GitHub ETag of its issues
+// the shown issues are also hashed, as snoozing one changes the feed but not the GitHub response,
+// and so is the day, as due dates like "tomorrow" are relative to it
+pub fn feed_etag(
+    github_etag: &str,
+    format: &str,
+    rendered_on: NaiveDate,
+    shown_issue_ids: &[i64],
+) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(github_etag.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(format.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(rendered_on.to_string().as_bytes());
+    for id in shown_issue_ids {
+        hasher.update(b"\n");
+        hasher.update(id.to_string().as_bytes());
+    }
+    format!("W/\"{}\"", hex::encode(&hasher.finalize()[..16]))
+}
+
+// whether an If-None-Match header has the ETag, e.g. "W/\"a\", W/\"b\"" or "*"
+pub fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let weak = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    if_none_match
+        .split(',')
+        .any(|tag| tag.trim() == "*" || weak(tag) == weak(etag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+
+    #[test]
+    fn test_verify_feed_token() {
+        let feed_tokens = HashMap::from([("octocat".to_string(), "secret".to_string())]);
+        assert!(verify_feed_token(&feed_tokens, "octocat", Some("secret")));
+        assert!(!verify_feed_token(&feed_tokens, "octocat", Some("secreT")));
+        assert!(!verify_feed_token(&feed_tokens, "octocat", Some("secret2")));
+        assert!(!verify_feed_token(&feed_tokens, "octocat", None));
+        // a user without a token has no feed
+        assert!(!verify_feed_token(&feed_tokens, "hubot", Some("secret")));
+    }
+
+    #[test]
+    fn test_feed_etag() {
+        let today = NaiveDate::from_ymd_opt(2023, 1, 10).unwrap();
+        let tomorrow = NaiveDate::from_ymd_opt(2023, 1, 11).unwrap();
+        let etag = feed_etag("\"v1\"", "atom", today, &[1, 2]);
+        assert!(etag.starts_with("W/\""));
+        assert_eq!(etag, feed_etag("\"v1\"", "atom", today, &[1, 2]));
+        assert_ne!(etag, feed_etag("\"v2\"", "atom", today, &[1, 2]));
+        assert_ne!(etag, feed_etag("\"v1\"", "atom", today, &[1]));
+        assert_ne!(etag, feed_etag("\"v1\"", "ics", today, &[1, 2]));
+        // the relative due dates change overnight
+        assert_ne!(etag, feed_etag("\"v1\"", "atom", tomorrow, &[1, 2]));
+    }
+
+    #[test]
+    fn test_etag_matches() {
+        assert!(etag_matches("W/\"a\"", "W/\"a\""));
+        assert!(etag_matches("\"b\", W/\"a\"", "W/\"a\""));
+        assert!(etag_matches("*", "W/\"a\""));
+        assert!(!etag_matches("W/\"b\"", "W/\"a\""));
+    }
+
+    #[tokio::test]
+    async fn test_get_feed_issues() {
+        let server = MockServer::start();
+        let mut modified = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search/issues")
+                .header_exists("Authorization");
+            then.status(200)
+                .header("ETag", "\"v1\"")
+                .json_body(serde_json::json!({"total_count": 0, "items": []}));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+        let cache = Mutex::new(FeedCache::default());
+
+        let (issues, etag) = get_feed_issues(&mock_api_addr, "token", "octocat", &cache)
+            .await
+            .unwrap();
+        modified.assert();
+        assert!(issues.is_empty());
+        assert_eq!(etag, Some("\"v1\"".to_string()));
+
+        // the ETag is sent next time, and the cached issues are used
+        modified.delete();
+        let not_modified = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search/issues")
+                .header("If-None-Match", "\"v1\"");
+            then.status(304);
+        });
+        let (issues, etag) = get_feed_issues(&mock_api_addr, "token", "octocat", &cache)
+            .await
+            .unwrap();
+        not_modified.assert();
+        assert!(issues.is_empty());
+        assert_eq!(etag, Some("\"v1\"".to_string()));
+    }
+}
@@ -0,0 +1,55 @@
+// issues and digests shared by the tests of the renderers and the notifiers
+use crate::digest::Digest;
+use crate::models::{Issue, Label, Milestone, Repository, SortedIssues};
+use chrono::{DateTime, TimeZone, Utc};
+
+// an open issue of "owner/name" labeled "bug", due on `due_on` when it is given
+pub(crate) fn issue(number: i64, title: &str, due_on: Option<DateTime<Utc>>) -> Issue {
+    Issue {
+        html_url: format!("https://github.com/owner/name/issues/{number}"),
+        title: title.to_string(),
+        labels: Some(vec![Label {
+            id: 0,
+            name: "bug".to_string(),
+            color: None,
+            description: None,
+        }]),
+        repository: Repository {
+            html_url: "https://github.com/owner/name".to_string(),
+            name: "name".to_string(),
+            full_name: "owner/name".to_string(),
+            id: 0,
+        },
+        body: None,
+        id: 0,
+        label_string: None,
+        assignees: None,
+        milestone: due_on.map(|due_on| Milestone {
+            number: 1,
+            title: "v1".to_string(),
+            html_url: "milestone_html_url".to_string(),
+            due_on: Some(due_on),
+        }),
+        created_at: None,
+        updated_at: None,
+        number,
+        state: "open".to_string(),
+    }
+}
+
+pub(crate) fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2023, 1, 10, 0, 0, 0).unwrap()
+}
+
+// a high priority issue with a special title, and a low priority one which is overdue
+pub(crate) fn digest(title: &str) -> Digest {
+    let yesterday = Utc.with_ymd_and_hms(2023, 1, 9, 0, 0, 0).unwrap();
+    Digest::new(
+        Ok(SortedIssues {
+            priority_high_issues: vec![issue(1, title, None)],
+            priority_low_issues: vec![issue(2, "overdue", Some(yesterday))],
+            ..SortedIssues::default()
+        }),
+        now(),
+    )
+}
@@ -1,5 +1,8 @@
-use crate::errors::GetIssueError;
-use crate::models::{Issue, SortedIssues};
+use crate::errors::{GetIssueError, GitHubApiError};
+use crate::models::{
+    GistSummary, Issue, IssueRef, IssueSummary, NewIssue, Priority, RepositorySummary,
+    SearchIssuesResult, SortedIssues, User, UserProfile,
+};
 
 pub async fn get_my_issues(
     github_api_addr: String,
@@ -50,6 +53,474 @@ pub async fn get_my_issues(
     Ok(issues)
 }
 
+// open issues assigned to the user, for a token which is not the user's
+// the search endpoint returns up to 100 issues per page, only the first page is used
+pub async fn get_assigned_issues(
+    github_api_addr: &str,
+    token: &str,
+    login: &str,
+) -> Result<Vec<Issue>, GetIssueError> {
+    let url = assigned_issues_url(github_api_addr, login)?;
+    let req = github_request(reqwest::Method::GET, url, token);
+    let res = send_github_request(req, "Search Issues")
+        .await
+        .map_err(|e| GetIssueError { message: e.message })?;
+    parse_search_issues(res).await
+}
+
+// like get_assigned_issues, but only in the public repositories
+// for a login which the user only claims, so that the shared token shows nothing private of it
+pub async fn get_public_assigned_issues(
+    github_api_addr: &str,
+    token: &str,
+    login: &str,
+) -> Result<Vec<Issue>, GetIssueError> {
+    let url = search_issues_url(
+        github_api_addr,
+        &format!("is:open is:issue is:public assignee:{login}"),
+    )?;
+    let req = github_request(reqwest::Method::GET, url, token);
+    let res = send_github_request(req, "Search Issues")
+        .await
+        .map_err(|e| GetIssueError { message: e.message })?;
+    parse_search_issues(res).await
+}
+
+// the result of a conditional request
+#[derive(Debug, PartialEq, Eq)]
+pub enum Conditional<T> {
+    // the ETag sent is still current
+    NotModified,
+    Modified { etag: Option<String>, value: T },
+}
+
+// like get_assigned_issues, but nothing is fetched when `etag` is still current
+// a request answered with 304 doesn't count against the rate limit
+pub async fn get_assigned_issues_if_modified(
+    github_api_addr: &str,
+    token: &str,
+    login: &str,
+    etag: Option<&str>,
+) -> Result<Conditional<Vec<Issue>>, GetIssueError> {
+    let url = assigned_issues_url(github_api_addr, login)?;
+    let mut req = github_request(reqwest::Method::GET, url, token);
+    if let Some(etag) = etag {
+        req = req.header("If-None-Match", etag);
+    }
+    let res = req.send().await.map_err(|err| {
+        let error_message = format!("Search Issues Error: {err}");
+        println!("{error_message}");
+        GetIssueError {
+            message: error_message,
+        }
+    })?;
+    if res.status() == reqwest::StatusCode::NOT_MODIFIED {
+        println!("Search Issues Not Modified");
+        return Ok(Conditional::NotModified);
+    }
+    if !res.status().is_success() {
+        let error_message = format!("Search Issues Error: status code is {}", res.status());
+        println!("{error_message}");
+        return Err(GetIssueError {
+            message: error_message,
+        });
+    }
+    println!("Search Issues OK");
+    let etag = res
+        .headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    Ok(Conditional::Modified {
+        etag,
+        value: parse_search_issues(res).await?,
+    })
+}
+
+// {github_api_addr}/search/issues of the open issues assigned to the user
+fn assigned_issues_url(github_api_addr: &str, login: &str) -> Result<reqwest::Url, GetIssueError> {
+    search_issues_url(
+        github_api_addr,
+        &format!("is:open is:issue assignee:{login}"),
+    )
+}
+
+fn search_issues_url(github_api_addr: &str, query: &str) -> Result<reqwest::Url, GetIssueError> {
+    let mut url =
+        reqwest::Url::parse(&format!("{github_api_addr}/search/issues")).map_err(|e| {
+            GetIssueError {
+                message: format!("Invalid GitHub API address: {e}"),
+            }
+        })?;
+    url.query_pairs_mut()
+        .append_pair("q", query)
+        .append_pair("per_page", "100");
+    Ok(url)
+}
+
+async fn parse_search_issues(res: reqwest::Response) -> Result<Vec<Issue>, GetIssueError> {
+    let result = res.json::<SearchIssuesResult>().await.map_err(|e| {
+        let error_message = format!("Parse Issues Error: {e}");
+        println!("{error_message}");
+        GetIssueError {
+            message: error_message,
+        }
+    })?;
+    Ok(result
+        .items
+        .into_iter()
+        .filter_map(|item| item.into_issue())
+        .collect())
+}
+
+pub async fn get_issue(
+    github_api_addr: &str,
+    token: &str,
+    issue_ref: &IssueRef,
+) -> Result<IssueSummary, GetIssueError> {
+    let url = issue_url(github_api_addr, issue_ref, &[])?;
+    let req = github_request(reqwest::Method::GET, url, token);
+    let res = send_github_request(req, "Fetch Issue").await?;
+    res.json::<IssueSummary>().await.map_err(|e| GetIssueError {
+        message: format!("Parse Issue Error: {e}"),
+    })
+}
+
+fn github_request(
+    method: reqwest::Method,
+    url: reqwest::Url,
+    token: &str,
+) -> reqwest::RequestBuilder {
+    reqwest::Client::new()
+        .request(method, url)
+        .header("User-Agent", "reqwest")
+        .header("Accept", "application/vnd.github+json")
+        .header("Authorization", format!("Bearer {token}"))
+}
+
+// send a request and fail unless the status is 2xx
+async fn send_github_request(
+    req: reqwest::RequestBuilder,
+    action: &str,
+) -> Result<reqwest::Response, GitHubApiError> {
+    let res = req.send().await.map_err(|err| {
+        let error_message = format!("{action} Error: {err}");
+        println!("{error_message}");
+        GitHubApiError {
+            message: error_message,
+        }
+    })?;
+    if !res.status().is_success() {
+        let error_message = format!("{action} Error: status code is {}", res.status());
+        println!("{error_message}");
+        return Err(GitHubApiError {
+            message: error_message,
+        });
+    }
+    println!("{action} OK");
+    Ok(res)
+}
+
+// like send_github_request, but None when the resource is gone, e.g. deleted or transferred
+async fn send_github_request_if_found(
+    req: reqwest::RequestBuilder,
+    action: &str,
+) -> Result<Option<reqwest::Response>, GitHubApiError> {
+    let res = req.send().await.map_err(|err| {
+        let error_message = format!("{action} Error: {err}");
+        println!("{error_message}");
+        GitHubApiError {
+            message: error_message,
+        }
+    })?;
+    let status = res.status();
+    if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::GONE {
+        println!("{action} Error: status code is {status}");
+        return Ok(None);
+    }
+    if !status.is_success() {
+        let error_message = format!("{action} Error: status code is {status}");
+        println!("{error_message}");
+        return Err(GitHubApiError {
+            message: error_message,
+        });
+    }
+    println!("{action} OK");
+    Ok(Some(res))
+}
+
+// {github_api_addr}/{segments...}
+fn api_url(github_api_addr: &str, segments: &[&str]) -> Result<reqwest::Url, GitHubApiError> {
+    let mut url = reqwest::Url::parse(github_api_addr).map_err(|e| GitHubApiError {
+        message: format!("Invalid GitHub API address: {e}"),
+    })?;
+    url.path_segments_mut()
+        .map_err(|_| GitHubApiError {
+            message: "Invalid GitHub API address".to_string(),
+        })?
+        .pop_if_empty()
+        .extend(segments);
+    Ok(url)
+}
+
+// {github_api_addr}/repos/{owner}/{repo}/issues/{number}/{segments...}
+fn issue_url(
+    github_api_addr: &str,
+    issue_ref: &IssueRef,
+    segments: &[&str],
+) -> Result<reqwest::Url, GitHubApiError> {
+    let number = issue_ref.number.to_string();
+    let mut url = api_url(
+        github_api_addr,
+        &[
+            "repos",
+            &issue_ref.owner,
+            &issue_ref.repo,
+            "issues",
+            &number,
+        ],
+    )?;
+    url.path_segments_mut()
+        .map_err(|_| GitHubApiError {
+            message: "Invalid GitHub API address".to_string(),
+        })?
+        .extend(segments);
+    Ok(url)
+}
+
+// whether anyone can see the repository, e.g. before showing its issue to a channel
+pub async fn is_public_repository(
+    github_api_addr: &str,
+    token: &str,
+    owner: &str,
+    repo: &str,
+) -> Result<bool, GitHubApiError> {
+    let url = api_url(github_api_addr, &["repos", owner, repo])?;
+    let req = github_request(reqwest::Method::GET, url, token);
+    let res = send_github_request(req, "Fetch Repository").await?;
+    let repository = res
+        .json::<RepositorySummary>()
+        .await
+        .map_err(|e| GitHubApiError {
+            message: format!("Parse Repository Error: {e}"),
+        })?;
+    Ok(!repository.private)
+}
+
+pub async fn get_authenticated_user(
+    github_api_addr: &str,
+    token: &str,
+) -> Result<User, GitHubApiError> {
+    let url = api_url(github_api_addr, &["user"])?;
+    let req = github_request(reqwest::Method::GET, url, token);
+    let res = send_github_request(req, "Fetch User").await?;
+    res.json::<User>().await.map_err(|e| GitHubApiError {
+        message: format!("Parse User Error: {e}"),
+    })
+}
+
+pub async fn get_user_profile(
+    github_api_addr: &str,
+    token: &str,
+    login: &str,
+) -> Result<UserProfile, GitHubApiError> {
+    let url = api_url(github_api_addr, &["users", login])?;
+    let req = github_request(reqwest::Method::GET, url, token);
+    let res = send_github_request(req, "Fetch User Profile").await?;
+    res.json::<UserProfile>().await.map_err(|e| GitHubApiError {
+        message: format!("Parse User Profile Error: {e}"),
+    })
+}
+
+pub async fn create_issue(
+    github_api_addr: &str,
+    token: &str,
+    owner: &str,
+    repo: &str,
+    new_issue: &NewIssue,
+) -> Result<IssueSummary, GitHubApiError> {
+    let url = api_url(github_api_addr, &["repos", owner, repo, "issues"])?;
+    let req = github_request(reqwest::Method::POST, url, token).json(new_issue);
+    let res = send_github_request(req, "Create Issue").await?;
+    res.json::<IssueSummary>()
+        .await
+        .map_err(|e| GitHubApiError {
+            message: format!("Parse Issue Error: {e}"),
+        })
+}
+
+// repositories of the issues, in order of first appearance
+pub fn collect_repository_names(issues: &[Issue]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for issue in issues {
+        if !names.contains(&issue.repository.full_name) {
+            names.push(issue.repository.full_name.clone());
+        }
+    }
+    names
+}
+
+pub async fn close_issue(
+    github_api_addr: &str,
+    token: &str,
+    issue_ref: &IssueRef,
+) -> Result<(), GitHubApiError> {
+    let url = issue_url(github_api_addr, issue_ref, &[])?;
+    let req = github_request(reqwest::Method::PATCH, url, token)
+        .json(&serde_json::json!({"state": "closed"}));
+    send_github_request(req, "Close Issue").await?;
+    Ok(())
+}
+
+pub async fn add_assignees(
+    github_api_addr: &str,
+    token: &str,
+    issue_ref: &IssueRef,
+    assignees: &[String],
+) -> Result<(), GitHubApiError> {
+    let url = issue_url(github_api_addr, issue_ref, &["assignees"])?;
+    let req = github_request(reqwest::Method::POST, url, token)
+        .json(&serde_json::json!({ "assignees": assignees }));
+    send_github_request(req, "Add Assignees").await?;
+    Ok(())
+}
+
+pub async fn add_labels(
+    github_api_addr: &str,
+    token: &str,
+    issue_ref: &IssueRef,
+    labels: &[String],
+) -> Result<(), GitHubApiError> {
+    let url = issue_url(github_api_addr, issue_ref, &["labels"])?;
+    let req = github_request(reqwest::Method::POST, url, token)
+        .json(&serde_json::json!({ "labels": labels }));
+    send_github_request(req, "Add Labels").await?;
+    Ok(())
+}
+
+pub async fn remove_label(
+    github_api_addr: &str,
+    token: &str,
+    issue_ref: &IssueRef,
+    label: &str,
+) -> Result<(), GitHubApiError> {
+    let url = issue_url(github_api_addr, issue_ref, &["labels", label])?;
+    let req = github_request(reqwest::Method::DELETE, url, token);
+    send_github_request(req, "Remove Label").await?;
+    Ok(())
+}
+
+// replace the body of an issue, None when the issue is gone
+pub async fn update_issue_body(
+    github_api_addr: &str,
+    token: &str,
+    issue_ref: &IssueRef,
+    body: &str,
+) -> Result<Option<IssueSummary>, GitHubApiError> {
+    let url = issue_url(github_api_addr, issue_ref, &[])?;
+    let req = github_request(reqwest::Method::PATCH, url, token)
+        .json(&serde_json::json!({ "body": body }));
+    let res = match send_github_request_if_found(req, "Update Issue").await? {
+        Some(res) => res,
+        None => return Ok(None),
+    };
+    res.json::<IssueSummary>()
+        .await
+        .map(Some)
+        .map_err(|e| GitHubApiError {
+            message: format!("Parse Issue Error: {e}"),
+        })
+}
+
+// pinning is only in the GraphQL API, which takes the node id of the issue
+// https://docs.github.com/en/graphql/reference/mutations#pinissue
+pub async fn pin_issue(
+    github_api_addr: &str,
+    token: &str,
+    node_id: &str,
+) -> Result<(), GitHubApiError> {
+    let url = api_url(github_api_addr, &["graphql"])?;
+    let req = github_request(reqwest::Method::POST, url, token).json(&serde_json::json!({
+        "query": "mutation($id: ID!) { pinIssue(input: {issueId: $id}) { issue { number } } }",
+        "variables": { "id": node_id },
+    }));
+    let res = send_github_request(req, "Pin Issue").await?;
+    // GraphQL errors come with 200
+    let body = res
+        .json::<serde_json::Value>()
+        .await
+        .map_err(|e| GitHubApiError {
+            message: format!("Parse Pin Issue Error: {e}"),
+        })?;
+    match body["errors"][0]["message"].as_str() {
+        Some(message) => Err(GitHubApiError {
+            message: format!("Pin Issue Error: {message}"),
+        }),
+        None => Ok(()),
+    }
+}
+
+// a secret gist with a single file
+pub async fn create_gist(
+    github_api_addr: &str,
+    token: &str,
+    description: &str,
+    file_name: &str,
+    content: &str,
+) -> Result<GistSummary, GitHubApiError> {
+    let url = api_url(github_api_addr, &["gists"])?;
+    let req = github_request(reqwest::Method::POST, url, token).json(&serde_json::json!({
+        "description": description,
+        "public": false,
+        "files": { file_name: { "content": content } },
+    }));
+    let res = send_github_request(req, "Create Gist").await?;
+    res.json::<GistSummary>().await.map_err(|e| GitHubApiError {
+        message: format!("Parse Gist Error: {e}"),
+    })
+}
+
+// replace the content of a file of a gist, None when the gist is gone
+pub async fn update_gist(
+    github_api_addr: &str,
+    token: &str,
+    gist_id: &str,
+    file_name: &str,
+    content: &str,
+) -> Result<Option<GistSummary>, GitHubApiError> {
+    let url = api_url(github_api_addr, &["gists", gist_id])?;
+    let req = github_request(reqwest::Method::PATCH, url, token).json(&serde_json::json!({
+        "files": { file_name: { "content": content } },
+    }));
+    let res = match send_github_request_if_found(req, "Update Gist").await? {
+        Some(res) => res,
+        None => return Ok(None),
+    };
+    res.json::<GistSummary>()
+        .await
+        .map(Some)
+        .map_err(|e| GitHubApiError {
+            message: format!("Parse Gist Error: {e}"),
+        })
+}
+
+// parse "https://github.com/{owner}/{repo}/issues/{number}"
+pub fn parse_github_issue_url(url: &str) -> Option<IssueRef> {
+    let path = url
+        .strip_prefix("https://github.com/")
+        .or_else(|| url.strip_prefix("http://github.com/"))?;
+    let path = path.split(['?', '#']).next()?;
+    let parts: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+    match parts.as_slice() {
+        [owner, repo, "issues", number] => Some(IssueRef {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            number: number.parse().ok()?,
+        }),
+        _ => None,
+    }
+}
+
 pub fn sort_issues(
     issues: Result<Vec<Issue>, GetIssueError>,
 ) -> Result<SortedIssues, GetIssueError> {
@@ -60,36 +531,12 @@ pub fn sort_issues(
     match issues {
         Ok(issues) => {
             for issue in issues {
-                let issue_labels = &issue.labels;
-                match issue_labels {
-                    Some(labels) => {
-                        let mut is_priority_high = false;
-                        let mut is_priority_medium = false;
-                        let mut is_priority_low = false;
-                        for label in labels {
-                            if label.name == "Priority: High" {
-                                is_priority_high = true;
-                            }
-                            if label.name == "Priority: Medium" {
-                                is_priority_medium = true;
-                            }
-                            if label.name == "Priority: Low" {
-                                is_priority_low = true;
-                            }
-                        }
-                        if is_priority_high {
-                            priority_high_issues.push(issue);
-                        } else if is_priority_medium {
-                            priority_medium_issues.push(issue);
-                        } else if is_priority_low {
-                            priority_low_issues.push(issue);
-                        } else {
-                            priority_none_issues.push(issue);
-                        }
-                    }
-                    None => {
-                        priority_none_issues.push(issue);
-                    }
+                let priority = Priority::from_labels(issue.labels.as_deref().unwrap_or_default());
+                match priority {
+                    Priority::High => priority_high_issues.push(issue),
+                    Priority::Medium => priority_medium_issues.push(issue),
+                    Priority::Low => priority_low_issues.push(issue),
+                    Priority::None => priority_none_issues.push(issue),
                 }
             }
         }
@@ -113,25 +560,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_my_issues() {
-        use super::super::models::{Issue, Repository};
+        use crate::fixtures::issue;
         use httpmock::prelude::*;
 
-        let mock_repo = Repository {
-            id: 0,
-            name: "test_repo".to_string(),
-            html_url: "repo_url".to_string(),
-        };
-        let mock_issue = Issue {
-            id: 1,
-            title: "test".to_string(),
-            body: Some("test".to_string()),
-            labels: None,
-            state: "open".to_string(),
-            repository: mock_repo,
-            html_url: "html_url".to_string(),
-            label_string: None,
-        };
-        let mock_issues = vec![mock_issue];
+        let mock_issues = vec![issue(1, "test", None)];
         let token = String::from("token");
 
         let server = MockServer::start();
@@ -155,30 +587,454 @@ mod tests {
 
     #[test]
     fn test_sort_issues() {
-        use super::super::models::{Issue, Repository};
-        let mock_repo = Repository {
-            id: 0,
-            name: "test_repo".to_string(),
-            html_url: "repo_url".to_string(),
-        };
-        let mock_issue = Issue {
-            id: 1,
-            title: "test".to_string(),
-            body: Some("test".to_string()),
-            labels: None,
-            state: "open".to_string(),
-            repository: mock_repo,
-            html_url: "html_url".to_string(),
-            label_string: None,
-        };
-        let mock_issues = vec![mock_issue];
-        let issues = Ok(mock_issues);
+        use crate::fixtures::issue;
+        use crate::models::{Label, PRIORITY_HIGH_LABEL, PRIORITY_LOW_LABEL};
+
+        // the highest priority among the labels is taken
+        let mut prioritized_issue = issue(2, "test", None);
+        prioritized_issue.labels = Some(
+            [PRIORITY_LOW_LABEL, PRIORITY_HIGH_LABEL]
+                .iter()
+                .map(|name| Label {
+                    id: 0,
+                    name: name.to_string(),
+                    color: None,
+                    description: None,
+                })
+                .collect(),
+        );
+        let issues = Ok(vec![issue(1, "test", None), prioritized_issue]);
         let sorted_issues = sort_issues(issues);
         assert!(sorted_issues.is_ok());
         let sorted_issues = sorted_issues.unwrap();
-        assert_eq!(sorted_issues.priority_high_issues.len(), 0);
+        assert_eq!(sorted_issues.priority_high_issues.len(), 1);
+        assert_eq!(sorted_issues.priority_high_issues[0].number, 2);
         assert_eq!(sorted_issues.priority_medium_issues.len(), 0);
         assert_eq!(sorted_issues.priority_low_issues.len(), 0);
         assert_eq!(sorted_issues.priority_none_issues.len(), 1);
     }
+
+    #[test]
+    fn test_parse_github_issue_url() {
+        let issue_ref = parse_github_issue_url("https://github.com/owner/repo/issues/12");
+        assert_eq!(
+            issue_ref,
+            Some(IssueRef {
+                owner: "owner".to_string(),
+                repo: "repo".to_string(),
+                number: 12,
+            })
+        );
+        let issue_ref =
+            parse_github_issue_url("https://github.com/owner/repo/issues/12#issuecomment-1");
+        assert_eq!(issue_ref.unwrap().number, 12);
+        assert!(parse_github_issue_url("https://github.com/owner/repo/pull/12").is_none());
+        assert!(parse_github_issue_url("https://example.com/owner/repo/issues/12").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_issue() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/repos/owner/repo/issues/12")
+                .header("Authorization", "Bearer token");
+            then.status(200).json_body(serde_json::json!({
+                "number": 12,
+                "title": "title",
+                "html_url": "https://github.com/owner/repo/issues/12",
+                "state": "open",
+                "labels": [],
+            }));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+        let issue_ref = IssueRef {
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            number: 12,
+        };
+
+        let issue = get_issue(&mock_api_addr, "token", &issue_ref).await;
+        mock.assert();
+        assert_eq!(issue.unwrap().title, "title");
+    }
+
+    #[test]
+    fn test_issue_url() {
+        let issue_ref = IssueRef {
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            number: 3,
+        };
+        let url = issue_url(
+            "https://api.github.com",
+            &issue_ref,
+            &["labels", "Priority: High"],
+        );
+        assert_eq!(
+            url.unwrap().as_str(),
+            "https://api.github.com/repos/owner/repo/issues/3/labels/Priority:%20High"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_close_issue() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method("PATCH")
+                .path("/repos/owner/repo/issues/3")
+                .header("Authorization", "Bearer token")
+                .json_body(serde_json::json!({"state": "closed"}));
+            then.status(200).json_body(serde_json::json!({}));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+        let issue_ref = IssueRef {
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            number: 3,
+        };
+
+        let res = close_issue(&mock_api_addr, "token", &issue_ref).await;
+        mock.assert();
+        assert!(res.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_add_assignees_error() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/repos/owner/repo/issues/3/assignees")
+                .json_body(serde_json::json!({"assignees": ["octocat"]}));
+            then.status(404);
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+        let issue_ref = IssueRef {
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            number: 3,
+        };
+
+        let res = add_assignees(
+            &mock_api_addr,
+            "token",
+            &issue_ref,
+            &["octocat".to_string()],
+        )
+        .await;
+        mock.assert();
+        assert_eq!(
+            res.unwrap_err().message,
+            "Add Assignees Error: status code is 404 Not Found"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_authenticated_user() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/user");
+            then.status(200)
+                .json_body(serde_json::json!({"login": "octocat", "id": 1}));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+
+        let user = get_authenticated_user(&mock_api_addr, "token").await;
+        mock.assert();
+        assert_eq!(user.unwrap().login, "octocat");
+    }
+
+    #[tokio::test]
+    async fn test_get_assigned_issues() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search/issues")
+                .query_param("q", "is:open is:issue assignee:octocat")
+                .query_param("per_page", "100");
+            then.status(200).json_body(serde_json::json!({
+                "total_count": 1,
+                "items": [{
+                    "id": 1,
+                    "number": 5,
+                    "title": "test",
+                    "html_url": "https://github.com/owner/repo/issues/5",
+                    "state": "open",
+                    "body": null,
+                    "labels": [{"id": 1, "name": "Priority: High"}],
+                    "assignees": [{"login": "octocat"}],
+                    "milestone": null,
+                    "repository_url": "https://api.github.com/repos/owner/repo"
+                }]
+            }));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+
+        let issues = get_assigned_issues(&mock_api_addr, "token", "octocat")
+            .await
+            .unwrap();
+        mock.assert();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].repository.full_name, "owner/repo");
+    }
+
+    #[tokio::test]
+    async fn test_get_assigned_issues_if_modified() {
+        use chrono::{TimeZone, Utc};
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let not_modified = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search/issues")
+                .header("If-None-Match", "\"v1\"");
+            then.status(304);
+        });
+        let modified = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search/issues")
+                .query_param("q", "is:open is:issue assignee:octocat")
+                .header("If-None-Match", "\"v0\"");
+            then.status(200)
+                .header("ETag", "\"v2\"")
+                .json_body(serde_json::json!({
+                    "total_count": 1,
+                    "items": [{
+                        "id": 1,
+                        "number": 5,
+                        "title": "test",
+                        "html_url": "https://github.com/owner/repo/issues/5",
+                        "state": "open",
+                        "body": null,
+                        "labels": [],
+                        "assignees": [],
+                        "milestone": null,
+                        "updated_at": "2023-01-09T12:00:00Z",
+                        "repository_url": "https://api.github.com/repos/owner/repo"
+                    }]
+                }));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+
+        let result =
+            get_assigned_issues_if_modified(&mock_api_addr, "token", "octocat", Some("\"v1\""))
+                .await
+                .unwrap();
+        not_modified.assert();
+        assert_eq!(result, Conditional::NotModified);
+        let result =
+            get_assigned_issues_if_modified(&mock_api_addr, "token", "octocat", Some("\"v0\""))
+                .await
+                .unwrap();
+        modified.assert();
+        match result {
+            Conditional::Modified { etag, value } => {
+                assert_eq!(etag, Some("\"v2\"".to_string()));
+                assert_eq!(
+                    value[0].updated_at,
+                    Some(Utc.with_ymd_and_hms(2023, 1, 9, 12, 0, 0).unwrap())
+                );
+            }
+            Conditional::NotModified => panic!("the issues are modified"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_user_profile() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/users/octocat");
+            then.status(200).json_body(serde_json::json!({
+                "login": "octocat",
+                "id": 1,
+                "email": "octocat@example.com"
+            }));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+
+        let profile = get_user_profile(&mock_api_addr, "token", "octocat").await;
+        mock.assert();
+        assert_eq!(
+            profile.unwrap().email.as_deref(),
+            Some("octocat@example.com")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_issue() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/repos/owner/repo/issues")
+                .json_body(serde_json::json!({
+                    "title": "title",
+                    "labels": ["Priority: High"],
+                }));
+            then.status(201).json_body(serde_json::json!({
+                "number": 7,
+                "title": "title",
+                "html_url": "https://github.com/owner/repo/issues/7",
+                "state": "open",
+                "labels": [{"id": 1, "name": "Priority: High"}],
+            }));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+        let new_issue = NewIssue {
+            title: "title".to_string(),
+            labels: vec!["Priority: High".to_string()],
+            ..Default::default()
+        };
+
+        let issue = create_issue(&mock_api_addr, "token", "owner", "repo", &new_issue).await;
+        mock.assert();
+        assert_eq!(issue.unwrap().number, 7);
+    }
+
+    #[tokio::test]
+    async fn test_update_issue_body() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let found = server.mock(|when, then| {
+            when.method("PATCH")
+                .path("/repos/owner/repo/issues/7")
+                .json_body(serde_json::json!({"body": "body"}));
+            then.status(200).json_body(serde_json::json!({
+                "number": 7,
+                "title": "title",
+                "html_url": "https://github.com/owner/repo/issues/7",
+                "state": "open",
+                "labels": [],
+                "node_id": "I_7",
+            }));
+        });
+        let gone = server.mock(|when, then| {
+            when.method("PATCH").path("/repos/owner/repo/issues/8");
+            then.status(410);
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+        let issue_ref = |number| IssueRef {
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            number,
+        };
+
+        let issue = update_issue_body(&mock_api_addr, "token", &issue_ref(7), "body").await;
+        found.assert();
+        assert_eq!(issue.unwrap().unwrap().node_id, Some("I_7".to_string()));
+        let issue = update_issue_body(&mock_api_addr, "token", &issue_ref(8), "body").await;
+        gone.assert();
+        assert_eq!(issue.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_pin_issue() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let pinned = server.mock(|when, then| {
+            when.method(POST)
+                .path("/graphql")
+                .body_contains("\"variables\":{\"id\":\"I_7\"}");
+            then.status(200)
+                .json_body(serde_json::json!({"data": {"pinIssue": {"issue": {"number": 7}}}}));
+        });
+        let failed = server.mock(|when, then| {
+            when.method(POST)
+                .path("/graphql")
+                .body_contains("\"variables\":{\"id\":\"I_8\"}");
+            then.status(200).json_body(serde_json::json!({
+                "data": {"pinIssue": null},
+                "errors": [{"message": "Repository has too many pinned issues"}],
+            }));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+
+        assert!(pin_issue(&mock_api_addr, "token", "I_7").await.is_ok());
+        pinned.assert();
+        let err = pin_issue(&mock_api_addr, "token", "I_8").await.unwrap_err();
+        failed.assert();
+        assert_eq!(
+            err.message,
+            "Pin Issue Error: Repository has too many pinned issues"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_and_update_gist() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let created = server.mock(|when, then| {
+            when.method(POST)
+                .path("/gists")
+                .json_body(serde_json::json!({
+                    "description": "Task board",
+                    "public": false,
+                    "files": {"tasks.md": {"content": "# Tasks"}},
+                }));
+            then.status(201).json_body(serde_json::json!({
+                "id": "abc",
+                "html_url": "https://gist.github.com/abc",
+            }));
+        });
+        let updated = server.mock(|when, then| {
+            when.method("PATCH")
+                .path("/gists/abc")
+                .json_body(serde_json::json!({"files": {"tasks.md": {"content": "# Tasks"}}}));
+            then.status(200).json_body(serde_json::json!({
+                "id": "abc",
+                "html_url": "https://gist.github.com/abc",
+            }));
+        });
+        let gone = server.mock(|when, then| {
+            when.method("PATCH").path("/gists/deleted");
+            then.status(404);
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+
+        let gist = create_gist(&mock_api_addr, "token", "Task board", "tasks.md", "# Tasks").await;
+        created.assert();
+        assert_eq!(gist.unwrap().id, "abc");
+        let gist = update_gist(&mock_api_addr, "token", "abc", "tasks.md", "# Tasks").await;
+        updated.assert();
+        assert_eq!(
+            gist.unwrap().unwrap().html_url,
+            "https://gist.github.com/abc"
+        );
+        let gist = update_gist(&mock_api_addr, "token", "deleted", "tasks.md", "# Tasks").await;
+        gone.assert();
+        assert_eq!(gist.unwrap(), None);
+    }
+
+    #[test]
+    fn test_collect_repository_names() {
+        use crate::fixtures;
+
+        // the shared fixture in the repository `full_name`
+        let issue = |full_name: &str| {
+            let mut issue = fixtures::issue(1, "test", None);
+            issue.repository.full_name = full_name.to_string();
+            issue
+        };
+        let issues = vec![issue("owner/b"), issue("owner/a"), issue("owner/b")];
+        assert_eq!(
+            collect_repository_names(&issues),
+            vec!["owner/b".to_string(), "owner/a".to_string()]
+        );
+    }
 }
@@ -0,0 +1,88 @@
+// English catalog, also the fallback of the other catalogs
+pub(super) const MESSAGES: &[(&str, &str)] = &[
+    ("digest.title", "Tasks"),
+    ("digest.group.high", "Priority: High"),
+    ("digest.group.medium", "Priority: Medium"),
+    ("digest.group.low", "Priority: Low"),
+    ("digest.group.none", "Priority: None"),
+    ("digest.continued", "{header} (continued)"),
+    ("digest.more.one", "and {count} more issue"),
+    ("digest.more.other", "and {count} more issues"),
+    ("digest.previous", "Previous tasks"),
+    ("digest.due", "(due {date})"),
+    ("digest.summary.count.one", "{count} issue"),
+    ("digest.summary.count.other", "{count} issues"),
+    ("digest.summary.overdue.one", "{count} overdue issue"),
+    ("digest.summary.overdue.other", "{count} overdue issues"),
+    ("digest.summary.empty", "No tasks"),
+    ("digest.summary.thread", "The issues are in the thread"),
+    ("digest.thread.failed.one", "{count} message could not be posted in the thread"),
+    ("digest.thread.failed.other", "{count} messages could not be posted in the thread"),
+    ("email.subject.one", "Tasks: {count} issue"),
+    ("email.subject.other", "Tasks: {count} issues"),
+    ("email.column.issue", "Issue"),
+    ("email.column.repository", "Repository"),
+    ("email.column.labels", "Labels"),
+    ("email.column.due", "Due"),
+    ("dashboard.title", "Task board"),
+    ("dashboard.updated", "Updated at {time}"),
+    ("feed.title", "Tasks of {login}"),
+    ("calendar.title", "GitHub deadlines"),
+    ("date.today", "today"),
+    ("date.tomorrow", "tomorrow"),
+    ("date.yesterday", "yesterday"),
+    ("date.days_later.one", "in {count} day"),
+    ("date.days_later.other", "in {count} days"),
+    ("date.days_ago.one", "{count} day ago"),
+    ("date.days_ago.other", "{count} days ago"),
+    ("action.close", "Close"),
+    ("action.snooze.one", "Snooze for {count} day"),
+    ("action.snooze.other", "Snooze for {count} days"),
+    ("action.assign_to_me", "Assign to me"),
+    ("action.priority_up", "Raise priority"),
+    ("action.priority_down", "Lower priority"),
+    (
+        "action.not_linked",
+        "Link your GitHub account with `/mygithub link <GitHub username>` to assign issues to yourself",
+    ),
+    ("action.result.closed", "Closed"),
+    ("action.result.snoozed.one", "Snoozed for {count} day"),
+    ("action.result.snoozed.other", "Snoozed for {count} days"),
+    ("action.result.assigned", "Assigned to {login}"),
+    ("action.result.priority_changed", "Changed the priority to \"{label}\""),
+    ("action.result.priority_removed", "Removed the priority"),
+    ("action.result.priority_unchanged", "The priority was not changed"),
+    ("action.result.error", "Error: {message}"),
+    ("modal.create_issue.title", "Create a GitHub issue"),
+    ("modal.submit", "Submit"),
+    ("modal.cancel", "Cancel"),
+    ("modal.repository.label", "Repository (owner/repo)"),
+    ("modal.repository.placeholder", "Select a repository"),
+    ("modal.repository.invalid", "Enter the repository as owner/repo"),
+    ("modal.title.label", "Title"),
+    ("modal.title.required", "Enter a title"),
+    ("modal.body.label", "Body"),
+    ("modal.priority.label", "Priority"),
+    ("modal.priority.placeholder", "Select a priority"),
+    ("modal.assignee.label", "Assignee (GitHub username)"),
+    ("modal.requested_by", "Requested by {user} on Slack"),
+    ("modal.issue_created", "Created the issue: {link}"),
+    ("modal.issue_failed", "Could not create the issue: {error}"),
+    (
+        "command.help",
+        "Please provide command like \"health-check\", \"create-notification\", \"new\", \"link\"",
+    ),
+    ("command.not_configured", "Slack app is not configured"),
+    (
+        "personal.onboarding",
+        "Your GitHub account is not linked yet.\nLink it with `/mygithub link <GitHub username>` to get the tasks of your own issues.",
+    ),
+    ("link.usage", "Link your account with `/mygithub link <GitHub username>`"),
+    ("link.current", "Linked to GitHub user {login}"),
+    (
+        "link.done",
+        "Now linked to GitHub user {login}. Only the issues in public repositories are shown",
+    ),
+    ("link.taken", "GitHub user {login} is already linked to another Slack user"),
+    ("link.invalid", "Invalid GitHub username: {login}"),
+];
@@ -0,0 +1,80 @@
+// Japanese catalog
+// Japanese has no plural forms, so plural messages have only ".other"
+pub(super) const MESSAGES: &[(&str, &str)] = &[
+    ("digest.title", "タスク一覧"),
+    ("digest.group.high", "優先度: 高"),
+    ("digest.group.medium", "優先度: 中"),
+    ("digest.group.low", "優先度: 低"),
+    ("digest.group.none", "優先度: なし"),
+    ("digest.continued", "{header} (続き)"),
+    ("digest.more.other", "ほか {count} 件"),
+    ("digest.previous", "前回のタスク一覧"),
+    ("digest.due", "(期限: {date})"),
+    ("digest.summary.count.other", "{count}件"),
+    ("digest.summary.overdue.other", "期限切れ: {count}件"),
+    ("digest.summary.empty", "タスクはありません"),
+    ("digest.summary.thread", "詳細はスレッドにあります"),
+    ("digest.thread.failed.other", "{count}件のメッセージをスレッドに投稿できませんでした"),
+    ("email.subject.other", "タスク一覧: {count}件"),
+    ("email.column.issue", "Issue"),
+    ("email.column.repository", "リポジトリ"),
+    ("email.column.labels", "ラベル"),
+    ("email.column.due", "期限"),
+    ("dashboard.title", "タスクボード"),
+    ("dashboard.updated", "更新日時: {time}"),
+    ("feed.title", "{login} のタスク一覧"),
+    ("calendar.title", "GitHub の期限"),
+    ("date.today", "今日"),
+    ("date.tomorrow", "明日"),
+    ("date.yesterday", "昨日"),
+    ("date.days_later.other", "{count}日後"),
+    ("date.days_ago.other", "{count}日前"),
+    ("action.close", "クローズ"),
+    ("action.snooze.other", "{count}日間スヌーズ"),
+    ("action.assign_to_me", "自分にアサイン"),
+    ("action.priority_up", "優先度を上げる"),
+    ("action.priority_down", "優先度を下げる"),
+    (
+        "action.not_linked",
+        "自分にアサインするには `/mygithub link <GitHub ユーザー名>` で GitHub アカウントを連携してください",
+    ),
+    ("action.result.closed", "クローズしました"),
+    ("action.result.snoozed.other", "{count}日間スヌーズしました"),
+    ("action.result.assigned", "{login} にアサインしました"),
+    ("action.result.priority_changed", "優先度を「{label}」に変更しました"),
+    ("action.result.priority_removed", "優先度を外しました"),
+    ("action.result.priority_unchanged", "優先度は変更されませんでした"),
+    ("action.result.error", "エラー: {message}"),
+    ("modal.create_issue.title", "GitHub Issue を作成"),
+    ("modal.submit", "送信"),
+    ("modal.cancel", "キャンセル"),
+    ("modal.repository.label", "リポジトリ (owner/repo)"),
+    ("modal.repository.placeholder", "リポジトリを選択"),
+    ("modal.repository.invalid", "owner/repo の形式で入力してください"),
+    ("modal.title.label", "タイトル"),
+    ("modal.title.required", "タイトルを入力してください"),
+    ("modal.body.label", "本文"),
+    ("modal.priority.label", "優先度"),
+    ("modal.priority.placeholder", "優先度を選択"),
+    ("modal.assignee.label", "アサイン (GitHub ユーザー名)"),
+    ("modal.requested_by", "Slack で {user} が依頼しました"),
+    ("modal.issue_created", "Issue を作成しました: {link}"),
+    ("modal.issue_failed", "Issue の作成に失敗しました: {error}"),
+    (
+        "command.help",
+        "\"health-check\", \"create-notification\", \"new\", \"link\" のいずれかのコマンドを指定してください",
+    ),
+    ("command.not_configured", "Slack アプリが設定されていません"),
+    (
+        "personal.onboarding",
+        "GitHub アカウントが連携されていません。\n`/mygithub link <GitHub ユーザー名>` で連携すると、自分の Issue のタスク一覧を作成できます。",
+    ),
+    ("link.usage", "`/mygithub link <GitHub ユーザー名>` で連携してください"),
+    ("link.current", "GitHub ユーザー {login} と連携しています"),
+    (
+        "link.done",
+        "GitHub ユーザー {login} と連携しました。公開リポジトリの Issue のみ表示されます",
+    ),
+    ("link.taken", "GitHub ユーザー {login} は別の Slack ユーザーと連携しています"),
+    ("link.invalid", "GitHub ユーザー名が正しくありません: {login}"),
+];
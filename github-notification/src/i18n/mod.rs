@@ -0,0 +1,236 @@
+// message catalogs of the texts shown to the users
+// a message is looked up by its key, e.g. "digest.title", and "{name}" in it is
+// replaced with the argument of the name
+// a plural message has a key per form, e.g. "digest.more.one" and "digest.more.other"
+mod en;
+mod ja;
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Locale {
+    // the texts were all Japanese before the catalogs
+    #[default]
+    Ja,
+    En,
+}
+
+impl Locale {
+    // "ja", "en" or a locale of Slack like "ja-JP" and "en-US"
+    pub fn parse(value: &str) -> Option<Self> {
+        let language = value.trim().split(['-', '_']).next()?.to_lowercase();
+        match language.as_str() {
+            "ja" => Some(Locale::Ja),
+            "en" => Some(Locale::En),
+            _ => None,
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::Ja => "ja",
+            Locale::En => "en",
+        }
+    }
+
+    fn messages(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::Ja => ja::MESSAGES,
+            Locale::En => en::MESSAGES,
+        }
+    }
+
+    // https://www.unicode.org/cldr/charts/latest/supplemental/language_plural_rules.html
+    fn plural_form(self, count: i64) -> &'static str {
+        match self {
+            Locale::Ja => "other",
+            Locale::En if count == 1 => "one",
+            Locale::En => "other",
+        }
+    }
+}
+
+fn lookup(locale: Locale, key: &str) -> Option<&'static str> {
+    locale
+        .messages()
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, message)| *message)
+}
+
+// a missing message falls back to English, and then to the key itself
+pub fn text_with(locale: Locale, key: &str, args: &[(&str, &str)]) -> String {
+    let mut text = lookup(locale, key)
+        .or_else(|| lookup(Locale::En, key))
+        .unwrap_or(key)
+        .to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{{name}}}"), value);
+    }
+    text
+}
+
+pub fn text(locale: Locale, key: &str) -> String {
+    text_with(locale, key, &[])
+}
+
+// "{count}" is replaced with the count
+pub fn plural_text(locale: Locale, key: &str, count: i64) -> String {
+    let form = locale.plural_form(count);
+    let plural_key = match lookup(locale, &format!("{key}.{form}")) {
+        Some(_) => format!("{key}.{form}"),
+        None => format!("{key}.other"),
+    };
+    text_with(locale, &plural_key, &[("count", &count.to_string())])
+}
+
+// "today", "in 3 days", "2 days ago" and so on
+// days are counted on UTC dates, as GitHub gives the due dates in UTC
+pub fn relative_date(locale: Locale, date: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let days = (date.date_naive() - now.date_naive()).num_days();
+    match days {
+        0 => text(locale, "date.today"),
+        1 => text(locale, "date.tomorrow"),
+        -1 => text(locale, "date.yesterday"),
+        days if days > 0 => plural_text(locale, "date.days_later", days),
+        days => plural_text(locale, "date.days_ago", -days),
+    }
+}
+
+// which locale the texts are in
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LocaleSettings {
+    pub default: Locale,
+    // Slack channel id -> locale
+    pub channels: HashMap<String, Locale>,
+    // Slack user id -> locale, preferred over the locale the user set in Slack
+    pub users: HashMap<String, Locale>,
+}
+
+impl LocaleSettings {
+    // for the messages posted to a channel
+    pub fn channel_locale(&self, channel_id: Option<&str>) -> Locale {
+        channel_id
+            .and_then(|channel_id| self.channels.get(channel_id))
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    // for the texts shown to a user, `user_locale` is the locale of the user in Slack
+    // the user's own choice comes first, then the channel where the user is
+    pub fn user_locale(
+        &self,
+        user_id: &str,
+        user_locale: Option<&str>,
+        channel_id: Option<&str>,
+    ) -> Locale {
+        self.users
+            .get(user_id)
+            .copied()
+            .or_else(|| user_locale.and_then(Locale::parse))
+            .unwrap_or_else(|| self.channel_locale(channel_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_locale_parse() {
+        assert_eq!(Locale::parse("ja"), Some(Locale::Ja));
+        assert_eq!(Locale::parse("ja-JP"), Some(Locale::Ja));
+        assert_eq!(Locale::parse("en-US"), Some(Locale::En));
+        assert_eq!(Locale::parse("EN_gb"), Some(Locale::En));
+        assert_eq!(Locale::parse("fr-FR"), None);
+        assert_eq!(Locale::parse(""), None);
+        assert_eq!(Locale::parse(Locale::En.code()), Some(Locale::En));
+    }
+
+    #[test]
+    fn test_catalogs_have_same_keys() {
+        let keys = |locale: Locale| {
+            let mut keys: Vec<&str> = locale
+                .messages()
+                .iter()
+                .map(|(key, _)| {
+                    // plural forms differ between languages
+                    key.strip_suffix(".one")
+                        .or_else(|| key.strip_suffix(".other"))
+                        .unwrap_or(key)
+                })
+                .collect();
+            keys.sort();
+            keys.dedup();
+            keys
+        };
+        assert_eq!(keys(Locale::Ja), keys(Locale::En));
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(text(Locale::Ja, "digest.title"), "タスク一覧");
+        assert_eq!(text(Locale::En, "digest.title"), "Tasks");
+        assert_eq!(text(Locale::Ja, "unknown.key"), "unknown.key");
+        assert_eq!(
+            text_with(
+                Locale::En,
+                "digest.continued",
+                &[("header", "*Priority: High*")]
+            ),
+            "*Priority: High* (continued)"
+        );
+    }
+
+    #[test]
+    fn test_plural_text() {
+        assert_eq!(
+            plural_text(Locale::En, "digest.more", 1),
+            "and 1 more issue"
+        );
+        assert_eq!(
+            plural_text(Locale::En, "digest.more", 2),
+            "and 2 more issues"
+        );
+        assert_eq!(
+            plural_text(Locale::En, "digest.more", 0),
+            "and 0 more issues"
+        );
+        assert_eq!(plural_text(Locale::Ja, "digest.more", 1), "ほか 1 件");
+        assert_eq!(plural_text(Locale::Ja, "digest.more", 21), "ほか 21 件");
+    }
+
+    #[test]
+    fn test_relative_date() {
+        let now = Utc.with_ymd_and_hms(2023, 1, 10, 9, 0, 0).unwrap();
+        let date = |day, hour| Utc.with_ymd_and_hms(2023, 1, day, hour, 0, 0).unwrap();
+        assert_eq!(relative_date(Locale::En, date(10, 23), now), "today");
+        assert_eq!(relative_date(Locale::En, date(11, 0), now), "tomorrow");
+        assert_eq!(relative_date(Locale::En, date(9, 0), now), "yesterday");
+        assert_eq!(relative_date(Locale::En, date(13, 0), now), "in 3 days");
+        assert_eq!(relative_date(Locale::En, date(8, 0), now), "2 days ago");
+        assert_eq!(relative_date(Locale::Ja, date(11, 0), now), "明日");
+        assert_eq!(relative_date(Locale::Ja, date(13, 0), now), "3日後");
+        assert_eq!(relative_date(Locale::Ja, date(8, 0), now), "2日前");
+    }
+
+    #[test]
+    fn test_locale_settings() {
+        let settings = LocaleSettings {
+            default: Locale::Ja,
+            channels: HashMap::from([("C1".to_string(), Locale::En)]),
+            users: HashMap::from([("U1".to_string(), Locale::Ja)]),
+        };
+        assert_eq!(settings.channel_locale(Some("C1")), Locale::En);
+        assert_eq!(settings.channel_locale(Some("C2")), Locale::Ja);
+        assert_eq!(settings.channel_locale(None), Locale::Ja);
+
+        // the configured locale of the user wins over the one in Slack
+        assert_eq!(settings.user_locale("U1", Some("en-US"), None), Locale::Ja);
+        assert_eq!(settings.user_locale("U2", Some("en-US"), None), Locale::En);
+        assert_eq!(settings.user_locale("U2", None, Some("C1")), Locale::En);
+        assert_eq!(settings.user_locale("U2", Some("fr-FR"), None), Locale::Ja);
+    }
+}
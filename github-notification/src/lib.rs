@@ -1,6 +1,21 @@
+pub mod dashboard;
+pub mod digest;
+pub mod discord;
+pub mod email;
 pub mod env;
 pub mod errors;
+pub mod feed;
+#[cfg(test)]
+pub(crate) mod fixtures;
 pub mod github;
+pub mod i18n;
+pub mod matrix;
 pub mod models;
+pub mod notifier;
+pub mod render;
 pub mod sentry;
 pub mod slack;
+pub mod snooze;
+pub mod store;
+pub mod teams;
+pub mod webhook;
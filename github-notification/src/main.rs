@@ -1,22 +1,88 @@
-use github_notification::env::{get_github_personal_access_token, get_slack_webhook_url_from_env};
+use github_notification::env::{
+    get_digest_style, get_github_personal_access_token, get_locale_settings, get_notifier_names,
+    get_slack_digest_template_path, get_state_dir,
+};
+use github_notification::feed::ical::create_icalendar;
 use github_notification::github::{get_my_issues, sort_issues};
-use github_notification::sentry::initialize_sentry;
-use github_notification::slack::{
-    notification::notify_by_slack, payload::create_payload_for_slack,
+use github_notification::notifier::{
+    create_notifiers, create_team_digest, failure_message, notify_all, team_snooze_scope,
 };
+use github_notification::sentry::initialize_sentry;
+use github_notification::slack::template::load_digest_template;
+use github_notification::snooze::{SnoozedIssues, SNOOZED_ISSUES_FILE_NAME};
+use github_notification::store::{load_json, state_file_path};
+
+const EXPORT_ICS_COMMAND: &str = "export-ics";
+
+// write the due dates of the issues to an iCalendar file, e.g. for a calendar app to import
+// the file is written by the path, as the log goes to stdout
+async fn export_icalendar(path: &str) {
+    let token = get_github_personal_access_token();
+    let my_issues = get_my_issues("https://api.github.com".to_string(), token).await;
+    let snoozed_issues: SnoozedIssues =
+        load_json(&state_file_path(&get_state_dir(), SNOOZED_ISSUES_FILE_NAME));
+    let my_issues = snoozed_issues.filter_issues(
+        &team_snooze_scope(),
+        my_issues,
+        chrono::Utc::now().timestamp(),
+    );
+    let sorted_issues = match sort_issues(my_issues) {
+        Ok(sorted_issues) => sorted_issues,
+        Err(err) => {
+            println!("{err}");
+            std::process::exit(1);
+        }
+    };
+    let calendar = create_icalendar(
+        &sorted_issues,
+        get_locale_settings().default,
+        chrono::Utc::now(),
+    );
+    match std::fs::write(path, calendar) {
+        Ok(()) => println!("Export Calendar OK: {path}"),
+        Err(err) => {
+            println!("Export Calendar Error: {err}");
+            std::process::exit(1);
+        }
+    }
+}
 
 #[tokio::main]
 async fn main() {
     let _guard = initialize_sentry();
 
-    let token = get_github_personal_access_token();
-    let webhook_url = get_slack_webhook_url_from_env();
+    // `github-notification export-ics <path>` exports the calendar instead of notifying
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some(EXPORT_ICS_COMMAND) {
+        match args.get(2) {
+            Some(path) => export_icalendar(path).await,
+            None => {
+                println!("Usage: github-notification {EXPORT_ICS_COMMAND} <path>");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
 
-    let github_api_addr = "https://api.github.com".to_string();
-    let my_issues = get_my_issues(github_api_addr, token).await;
-    let sorted_issues = sort_issues(my_issues);
-    let payload = create_payload_for_slack(sorted_issues);
+    // a broken template is reported before anything is fetched
+    let template = match load_digest_template(get_slack_digest_template_path().as_deref()) {
+        Ok(template) => template.with_style(get_digest_style()),
+        Err(err) => {
+            println!("{err}");
+            std::process::exit(1);
+        }
+    };
 
-    // notify by slack
-    notify_by_slack(webhook_url, payload).await;
+    let (digest, format) = create_team_digest("https://api.github.com", template).await;
+    let notifiers = create_notifiers(&get_notifier_names(), &format);
+    if notifiers.is_empty() {
+        println!("No Notifier is configured");
+        std::process::exit(1);
+    }
+    let reports = notify_all(&notifiers, &digest).await;
+    // the run fails when any notifier failed, after all of them have been tried
+    if let Some(message) = failure_message(&reports) {
+        println!("{message}");
+        std::process::exit(1);
+    }
 }
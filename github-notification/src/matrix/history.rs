@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+pub const MATRIX_EVENT_HISTORY_FILE_NAME: &str = "matrix_event_history.json";
+const MAX_RECORDS: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MatrixEventRecord {
+    pub room_id: String,
+    // the original event, which every edit replaces
+    pub event_id: String,
+    // unix time when the event was first sent
+    pub posted_at: i64,
+}
+
+// digests sent by the bot, oldest first
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MatrixEventHistory {
+    pub records: Vec<MatrixEventRecord>,
+}
+
+impl MatrixEventHistory {
+    pub fn latest(&self, room_id: &str) -> Option<&MatrixEventRecord> {
+        self.records.iter().rev().find(|r| r.room_id == room_id)
+    }
+
+    // the latest digest in the room, if it was sent within `max_age_secs`
+    pub fn latest_recent(
+        &self,
+        room_id: &str,
+        now: i64,
+        max_age_secs: i64,
+    ) -> Option<&MatrixEventRecord> {
+        self.latest(room_id)
+            .filter(|r| now - r.posted_at < max_age_secs)
+    }
+
+    pub fn push(&mut self, record: MatrixEventRecord) {
+        self.records.push(record);
+        if self.records.len() > MAX_RECORDS {
+            let overflow = self.records.len() - MAX_RECORDS;
+            self.records.drain(..overflow);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(room_id: &str, event_id: &str, posted_at: i64) -> MatrixEventRecord {
+        MatrixEventRecord {
+            room_id: room_id.to_string(),
+            event_id: event_id.to_string(),
+            posted_at,
+        }
+    }
+
+    #[test]
+    fn test_latest_recent() {
+        let mut history = MatrixEventHistory::default();
+        history.push(record("!a:example.org", "$1", 100));
+        history.push(record("!b:example.org", "$2", 100));
+        assert_eq!(history.latest("!a:example.org").unwrap().event_id, "$1");
+        assert!(history.latest_recent("!a:example.org", 150, 100).is_some());
+        assert!(history.latest_recent("!a:example.org", 200, 100).is_none());
+        assert!(history.latest("!c:example.org").is_none());
+    }
+
+    #[test]
+    fn test_push_keeps_max_records() {
+        let mut history = MatrixEventHistory::default();
+        for i in 0..(MAX_RECORDS + 5) {
+            history.push(record("!a:example.org", &i.to_string(), i as i64));
+        }
+        assert_eq!(history.records.len(), MAX_RECORDS);
+        assert_eq!(history.records[0].event_id, "5");
+    }
+}
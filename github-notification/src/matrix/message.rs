@@ -0,0 +1,280 @@
+use crate::digest::Digest;
+use crate::i18n::{plural_text, relative_date, text, text_with, Locale};
+use crate::models::{Issue, Priority};
+use crate::render::html::escape_html;
+use crate::slack::style::DigestStyle;
+use serde::Serialize;
+
+// an event is limited to 64KiB, and an edit carries the formatted body twice
+// https://spec.matrix.org/v1.8/client-server-api/#size-limits
+pub const MAX_FORMATTED_BODY_LENGTH: usize = 16_000;
+// for the closing tags and the "and N more" line
+const FORMATTED_BODY_RESERVE: usize = 200;
+
+pub const MATRIX_HTML_FORMAT: &str = "org.matrix.custom.html";
+
+// a notice, which clients don't answer automatically
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct MatrixMessageContent {
+    pub msgtype: String,
+    pub body: String,
+    pub format: String,
+    pub formatted_body: String,
+}
+
+impl MatrixMessageContent {
+    pub fn new(body: String, formatted_body: String) -> Self {
+        Self {
+            msgtype: "m.notice".to_string(),
+            body,
+            format: MATRIX_HTML_FORMAT.to_string(),
+            formatted_body,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct MatrixRelation {
+    pub rel_type: String,
+    pub event_id: String,
+}
+
+// an edit of `event_id`, with a fallback for clients which don't know edits
+// https://spec.matrix.org/v1.8/client-server-api/#event-replacements
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct MatrixEditContent {
+    #[serde(flatten)]
+    pub fallback: MatrixMessageContent,
+    #[serde(rename = "m.new_content")]
+    pub new_content: MatrixMessageContent,
+    #[serde(rename = "m.relates_to")]
+    pub relates_to: MatrixRelation,
+}
+
+impl MatrixEditContent {
+    pub fn new(event_id: &str, content: MatrixMessageContent) -> Self {
+        Self {
+            fallback: MatrixMessageContent::new(
+                format!("* {}", content.body),
+                format!("* {}", content.formatted_body),
+            ),
+            new_content: content,
+            relates_to: MatrixRelation {
+                rel_type: "m.replace".to_string(),
+                event_id: event_id.to_string(),
+            },
+        }
+    }
+}
+
+// a link which any client opens, e.g. "https://matrix.to/#/!room:example.org/$event"
+pub fn event_link(room_id: &str, event_id: &str) -> String {
+    format!("https://matrix.to/#/{room_id}/{event_id}")
+}
+
+// the text line and the list item of an issue
+fn render_issue(
+    issue: &Issue,
+    priority: Priority,
+    locale: Locale,
+    style: &DigestStyle,
+    digest: &Digest,
+) -> (String, String) {
+    let repository = &issue.repository;
+    let labels = style.visible_labels(issue, priority);
+    let due = issue
+        .milestone
+        .as_ref()
+        .and_then(|milestone| milestone.due_on)
+        .map(|due_on| {
+            let date = relative_date(locale, due_on, digest.generated_at);
+            text_with(locale, "digest.due", &[("date", &date)])
+        });
+
+    let mut line = format!(
+        "- {} ({}#{}) {}",
+        issue.title, repository.full_name, issue.number, issue.html_url
+    );
+    let mut item = format!(
+        "<li><a href=\"{}\">{}</a> (<a href=\"{}\">{}</a>)",
+        escape_html(&issue.html_url),
+        escape_html(&issue.title),
+        escape_html(&repository.html_url),
+        escape_html(&repository.full_name)
+    );
+    for label in labels {
+        line.push_str(&format!(" [{}]", label.name));
+        item.push_str(&format!(" <code>{}</code>", escape_html(&label.name)));
+    }
+    if let Some(due) = due {
+        line.push_str(&format!(" {due}"));
+        item.push_str(&format!(" {}", escape_html(&due)));
+    }
+    item.push_str("</li>");
+    (line, item)
+}
+
+// the digest as a plain body and an HTML body with the same issues
+// issues past the size limit are left out and counted at the end
+pub fn create_message_content(
+    digest: &Digest,
+    locale: Locale,
+    style: &DigestStyle,
+    previous_link: Option<&str>,
+) -> MatrixMessageContent {
+    let title = text(locale, "digest.title");
+    let mut lines = vec![title.clone()];
+    let mut html = format!("<h3>{}</h3>", escape_html(&title));
+    match &digest.issues {
+        Err(e) => {
+            lines.push(e.message.clone());
+            html.push_str(&format!("<p>{}</p>", escape_html(&e.message)));
+        }
+        Ok(_) if digest.issue_count() == 0 => {
+            let empty = text(locale, "digest.summary.empty");
+            html.push_str(&format!("<p>{}</p>", escape_html(&empty)));
+            lines.push(empty);
+        }
+        Ok(_) => {
+            let mut omitted_count = 0;
+            for (priority, issues) in digest.groups() {
+                let header = style.priority_title(priority, locale);
+                let header_html = format!("<p><strong>{}</strong></p><ul>", escape_html(&header));
+                // once an issue is left out, the rest are too, so that the order is kept
+                if omitted_count > 0
+                    || html.len() + header_html.len() + FORMATTED_BODY_RESERVE
+                        > MAX_FORMATTED_BODY_LENGTH
+                {
+                    omitted_count += issues.len();
+                    continue;
+                }
+                lines.push(header);
+                html.push_str(&header_html);
+                for issue in issues {
+                    let (line, item) = render_issue(issue, priority, locale, style, digest);
+                    if omitted_count > 0
+                        || html.len() + item.len() + FORMATTED_BODY_RESERVE
+                            > MAX_FORMATTED_BODY_LENGTH
+                    {
+                        omitted_count += 1;
+                        continue;
+                    }
+                    lines.push(line);
+                    html.push_str(&item);
+                }
+                html.push_str("</ul>");
+            }
+            if omitted_count > 0 {
+                let more = plural_text(locale, "digest.more", omitted_count as i64);
+                html.push_str(&format!("<p>{}</p>", escape_html(&more)));
+                lines.push(more);
+            }
+        }
+    }
+    if let Some(previous_link) = previous_link {
+        let previous = text(locale, "digest.previous");
+        lines.push(format!("{previous}: {previous_link}"));
+        html.push_str(&format!(
+            "<p><a href=\"{}\">{}</a></p>",
+            escape_html(previous_link),
+            escape_html(&previous)
+        ));
+    }
+    MatrixMessageContent::new(lines.join("\n"), html)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::GetIssueError;
+    use crate::fixtures::{issue, now};
+    use crate::models::SortedIssues;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_create_message_content() {
+        let due_issue = issue(
+            2,
+            "due",
+            Some(Utc.with_ymd_and_hms(2023, 1, 11, 0, 0, 0).unwrap()),
+        );
+        let digest = Digest::new(
+            Ok(SortedIssues {
+                priority_high_issues: vec![issue(1, "<b>bold</b>", None)],
+                priority_none_issues: vec![due_issue],
+                ..SortedIssues::default()
+            }),
+            now(),
+        );
+        let content = create_message_content(
+            &digest,
+            Locale::En,
+            &DigestStyle::default(),
+            Some("https://matrix.to/#/!room:example.org/$1"),
+        );
+        assert_eq!(content.msgtype, "m.notice");
+        assert_eq!(content.format, "org.matrix.custom.html");
+        assert_eq!(
+            content.body,
+            "Tasks\n🔴 Priority: High\n- <b>bold</b> (owner/name#1) https://github.com/owner/name/issues/1 [bug]\n⚪ Priority: None\n- due (owner/name#2) https://github.com/owner/name/issues/2 [bug] (due tomorrow)\nPrevious tasks: https://matrix.to/#/!room:example.org/$1"
+        );
+        assert_eq!(
+            content.formatted_body,
+            "<h3>Tasks</h3><p><strong>🔴 Priority: High</strong></p><ul><li><a href=\"https://github.com/owner/name/issues/1\">&lt;b&gt;bold&lt;/b&gt;</a> (<a href=\"https://github.com/owner/name\">owner/name</a>) <code>bug</code></li></ul><p><strong>⚪ Priority: None</strong></p><ul><li><a href=\"https://github.com/owner/name/issues/2\">due</a> (<a href=\"https://github.com/owner/name\">owner/name</a>) <code>bug</code> (due tomorrow)</li></ul><p><a href=\"https://matrix.to/#/!room:example.org/$1\">Previous tasks</a></p>"
+        );
+    }
+
+    #[test]
+    fn test_create_message_content_leaves_out_issues_past_limit() {
+        let digest = Digest::new(
+            Ok(SortedIssues {
+                priority_high_issues: (0..200).map(|i| issue(i, "title", None)).collect(),
+                priority_low_issues: vec![issue(200, "title", None)],
+                ..SortedIssues::default()
+            }),
+            now(),
+        );
+        let content = create_message_content(&digest, Locale::En, &DigestStyle::default(), None);
+        assert!(content.formatted_body.len() <= MAX_FORMATTED_BODY_LENGTH);
+        let shown_count = content.formatted_body.matches("<li>").count();
+        assert!(shown_count < 200);
+        let more = format!("and {} more issues", 201 - shown_count);
+        assert!(content.body.ends_with(&more));
+        assert!(content.formatted_body.ends_with(&format!("<p>{more}</p>")));
+        assert!(!content.body.contains("Priority: Low"));
+    }
+
+    #[test]
+    fn test_create_message_content_error() {
+        let digest = Digest::new(
+            Err(GetIssueError {
+                message: "Get Issue Error: 401".to_string(),
+            }),
+            now(),
+        );
+        let content = create_message_content(&digest, Locale::Ja, &DigestStyle::default(), None);
+        assert_eq!(content.body, "タスク一覧\nGet Issue Error: 401");
+    }
+
+    #[test]
+    fn test_matrix_edit_content() {
+        let content = MatrixMessageContent::new("Tasks".to_string(), "<h3>Tasks</h3>".to_string());
+        let edit = serde_json::to_value(MatrixEditContent::new("$1", content)).unwrap();
+        assert_eq!(
+            edit,
+            serde_json::json!({
+                "msgtype": "m.notice",
+                "body": "* Tasks",
+                "format": "org.matrix.custom.html",
+                "formatted_body": "* <h3>Tasks</h3>",
+                "m.new_content": {
+                    "msgtype": "m.notice",
+                    "body": "Tasks",
+                    "format": "org.matrix.custom.html",
+                    "formatted_body": "<h3>Tasks</h3>"
+                },
+                "m.relates_to": {"rel_type": "m.replace", "event_id": "$1"}
+            })
+        );
+    }
+}
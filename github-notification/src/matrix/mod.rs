@@ -0,0 +1,3 @@
+pub mod history;
+pub mod message;
+pub mod notifier;
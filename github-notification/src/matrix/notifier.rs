@@ -0,0 +1,312 @@
+use super::history::{MatrixEventHistory, MatrixEventRecord};
+use super::message::{create_message_content, event_link, MatrixEditContent};
+use crate::digest::Digest;
+use crate::errors::{MatrixApiError, NotifyError};
+use crate::i18n::Locale;
+use crate::notifier::{Notifier, MATRIX_NOTIFIER};
+use crate::slack::style::DigestStyle;
+use crate::store::{load_json, save_json};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize)]
+struct MatrixSendResponse {
+    event_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MatrixErrorResponse {
+    errcode: String,
+    error: Option<String>,
+}
+
+// a room id like "!room:example.org" has to be escaped in the path
+fn encode_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+// a transaction id makes a retried request idempotent, so it is unique per event
+fn new_transaction_id() -> String {
+    let now = chrono::Utc::now();
+    format!(
+        "github-notification-{}",
+        now.timestamp_nanos_opt().unwrap_or(now.timestamp_micros())
+    )
+}
+
+// send an event to the room, and return its id
+// https://spec.matrix.org/v1.8/client-server-api/#put_matrixclientv3roomsroomidsendeventtypetxnid
+pub async fn send_room_message<T: Serialize>(
+    homeserver_url: &str,
+    access_token: &str,
+    room_id: &str,
+    content: &T,
+) -> Result<String, MatrixApiError> {
+    let url = format!(
+        "{}/_matrix/client/v3/rooms/{}/send/m.room.message/{}",
+        homeserver_url.trim_end_matches('/'),
+        encode_path_segment(room_id),
+        new_transaction_id()
+    );
+    let client = reqwest::Client::new();
+    let res = client
+        .put(url)
+        .bearer_auth(access_token)
+        .json(content)
+        .send()
+        .await
+        .map_err(|err| MatrixApiError {
+            message: format!("Matrix API Error: {err}"),
+        })?;
+    let status = res.status();
+    if !status.is_success() {
+        let message = match res.json::<MatrixErrorResponse>().await {
+            Ok(body) => format!(
+                "Matrix API Error: {} {} {}",
+                status.as_u16(),
+                body.errcode,
+                body.error.unwrap_or_default()
+            ),
+            Err(_) => format!("Matrix API Error: {}", status.as_u16()),
+        };
+        return Err(MatrixApiError {
+            message: message.trim_end().to_string(),
+        });
+    }
+    res.json::<MatrixSendResponse>()
+        .await
+        .map(|body| body.event_id)
+        .map_err(|err| MatrixApiError {
+            message: format!("Matrix API Error: {err}"),
+        })
+}
+
+pub struct MatrixNotifier {
+    pub homeserver_url: String,
+    pub access_token: String,
+    pub room_id: String,
+    pub history_path: PathBuf,
+    pub update_window_secs: i64,
+    pub locale: Locale,
+    pub style: DigestStyle,
+}
+
+#[async_trait]
+impl Notifier for MatrixNotifier {
+    fn name(&self) -> &str {
+        MATRIX_NOTIFIER
+    }
+
+    // like the Slack digest, the previous digest in the room is edited if it is recent enough,
+    // otherwise a new message is sent with a link to the previous one
+    async fn notify(&self, digest: &Digest) -> Result<(), NotifyError> {
+        let mut history: MatrixEventHistory = load_json(&self.history_path);
+        let now = chrono::Utc::now().timestamp();
+
+        if let Some(record) = history
+            .latest_recent(&self.room_id, now, self.update_window_secs)
+            .cloned()
+        {
+            let content = create_message_content(digest, self.locale, &self.style, None);
+            let edit = MatrixEditContent::new(&record.event_id, content);
+            match send_room_message(
+                &self.homeserver_url,
+                &self.access_token,
+                &record.room_id,
+                &edit,
+            )
+            .await
+            {
+                Ok(_) => {
+                    println!("Update Matrix Message OK");
+                    return Ok(());
+                }
+                // the previous message may have been redacted, so send a new one
+                Err(err) => println!("Update Matrix Message Error: {err}"),
+            }
+        }
+
+        let previous_link = history
+            .latest(&self.room_id)
+            .map(|record| event_link(&record.room_id, &record.event_id));
+        let content =
+            create_message_content(digest, self.locale, &self.style, previous_link.as_deref());
+        let event_id = send_room_message(
+            &self.homeserver_url,
+            &self.access_token,
+            &self.room_id,
+            &content,
+        )
+        .await?;
+        println!("Send Matrix Message OK");
+        history.push(MatrixEventRecord {
+            room_id: self.room_id.clone(),
+            event_id,
+            posted_at: now,
+        });
+        if let Err(err) = save_json(&self.history_path, &history) {
+            println!("{err}");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SortedIssues;
+    use chrono::Utc;
+
+    const ROOM_PATH: &str = "/_matrix/client/v3/rooms/%21room%3Aexample.org/send/m.room.message/";
+
+    fn history_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir()
+            .join("github_notification_matrix_test")
+            .join(name);
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn notifier(homeserver_url: String, history_path: PathBuf) -> MatrixNotifier {
+        MatrixNotifier {
+            homeserver_url,
+            access_token: "token".to_string(),
+            room_id: "!room:example.org".to_string(),
+            history_path,
+            update_window_secs: 3600,
+            locale: Locale::En,
+            style: DigestStyle::default(),
+        }
+    }
+
+    fn digest() -> Digest {
+        Digest::new(Ok(SortedIssues::default()), Utc::now())
+    }
+
+    #[test]
+    fn test_encode_path_segment() {
+        assert_eq!(
+            encode_path_segment("!room:example.org"),
+            "%21room%3Aexample.org"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_matrix_notifier_sends_new_message() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let send_mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path_contains(ROOM_PATH)
+                .header("authorization", "Bearer token")
+                .json_body_partial(
+                    r#"{"msgtype": "m.notice", "format": "org.matrix.custom.html"}"#,
+                );
+            then.status(200)
+                .json_body(serde_json::json!({"event_id": "$1"}));
+        });
+        let path = history_path("send_new.json");
+        let notifier = notifier(format!("http://{}/", server.address()), path.clone());
+        notifier.notify(&digest()).await.unwrap();
+        send_mock.assert();
+        let history: MatrixEventHistory = load_json(&path);
+        assert_eq!(history.latest("!room:example.org").unwrap().event_id, "$1");
+        assert_eq!(notifier.name(), "matrix");
+    }
+
+    #[tokio::test]
+    async fn test_matrix_notifier_edits_recent_message() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let edit_mock = server.mock(|when, then| {
+            when.method(PUT).path_contains(ROOM_PATH).json_body_partial(
+                r#"{"m.relates_to": {"rel_type": "m.replace", "event_id": "$1"}}"#,
+            );
+            then.status(200)
+                .json_body(serde_json::json!({"event_id": "$2"}));
+        });
+        let path = history_path("edit_recent.json");
+        let mut history = MatrixEventHistory::default();
+        history.push(MatrixEventRecord {
+            room_id: "!room:example.org".to_string(),
+            event_id: "$1".to_string(),
+            posted_at: Utc::now().timestamp(),
+        });
+        save_json(&path, &history).unwrap();
+
+        notifier(format!("http://{}", server.address()), path.clone())
+            .notify(&digest())
+            .await
+            .unwrap();
+        edit_mock.assert();
+        // the original event stays the one to edit
+        let history: MatrixEventHistory = load_json(&path);
+        assert_eq!(history.records.len(), 1);
+        assert_eq!(history.latest("!room:example.org").unwrap().event_id, "$1");
+    }
+
+    #[tokio::test]
+    async fn test_matrix_notifier_links_old_message() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let send_mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path_contains(ROOM_PATH)
+                .body_contains("https://matrix.to/#/!room:example.org/$1");
+            then.status(200)
+                .json_body(serde_json::json!({"event_id": "$2"}));
+        });
+        let path = history_path("link_old.json");
+        let mut history = MatrixEventHistory::default();
+        history.push(MatrixEventRecord {
+            room_id: "!room:example.org".to_string(),
+            event_id: "$1".to_string(),
+            posted_at: 0,
+        });
+        save_json(&path, &history).unwrap();
+
+        notifier(format!("http://{}", server.address()), path.clone())
+            .notify(&digest())
+            .await
+            .unwrap();
+        send_mock.assert();
+        let history: MatrixEventHistory = load_json(&path);
+        assert_eq!(history.records.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_matrix_notifier_error() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let send_mock = server.mock(|when, then| {
+            when.method(PUT).path_contains(ROOM_PATH);
+            then.status(403).json_body(serde_json::json!({
+                "errcode": "M_FORBIDDEN",
+                "error": "You are not in this room"
+            }));
+        });
+        let path = history_path("error.json");
+        let err = notifier(format!("http://{}", server.address()), path)
+            .notify(&digest())
+            .await
+            .unwrap_err();
+        send_mock.assert();
+        assert_eq!(
+            err.message,
+            "Matrix API Error: 403 M_FORBIDDEN You are not in this room"
+        );
+    }
+}
@@ -1,21 +1,49 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Label {
     pub id: i64,
     pub name: String,
+    // e.g. "d73a4a", without "#"
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct User {
+    pub login: String,
 }
 
+// the public profile of a user
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UserProfile {
+    pub login: String,
+    // the public email, null when the user hides it
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Repository {
     pub id: i64,
     pub name: String,
+    // owner/name
+    pub full_name: String,
     pub html_url: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Milestone {
+    pub number: i64,
+    pub title: String,
+    pub html_url: String,
+    pub due_on: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Issue {
     pub id: i64,
+    pub number: i64,
     pub title: String,
     pub html_url: String,
     pub state: String,
@@ -23,9 +51,253 @@ pub struct Issue {
     pub labels: Option<Vec<Label>>,
     pub repository: Repository,
     pub label_string: Option<String>,
+    pub assignees: Option<Vec<User>>,
+    pub milestone: Option<Milestone>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl Issue {
+    // the issue is overdue when the due date of its milestone has passed
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        self.milestone
+            .as_ref()
+            .and_then(|milestone| milestone.due_on)
+            .is_some_and(|due_on| due_on < now)
+    }
+
+    pub fn assignee_logins(&self) -> Vec<&str> {
+        self.assignees
+            .iter()
+            .flatten()
+            .map(|user| user.login.as_str())
+            .collect()
+    }
+}
+
+// an issue in the results of the search endpoint, which has only the repository URL
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SearchIssue {
+    pub id: i64,
+    pub number: i64,
+    pub title: String,
+    pub html_url: String,
+    pub state: String,
+    pub body: Option<String>,
+    pub labels: Option<Vec<Label>>,
+    pub assignees: Option<Vec<User>>,
+    pub milestone: Option<Milestone>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    // https://api.github.com/repos/owner/name
+    pub repository_url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchIssuesResult {
+    pub total_count: i64,
+    pub items: Vec<SearchIssue>,
+}
+
+impl SearchIssue {
+    // the repository is made up from the URLs, without its id
+    pub fn into_issue(self) -> Option<Issue> {
+        let mut segments = self.repository_url.rsplit('/');
+        let name = segments.next()?.to_string();
+        let owner = segments.next()?;
+        let issue_path = format!("/issues/{}", self.number);
+        let html_url = self
+            .html_url
+            .strip_suffix(&issue_path)
+            .or_else(|| {
+                self.html_url
+                    .strip_suffix(&format!("/pull/{}", self.number))
+            })?
+            .to_string();
+        Some(Issue {
+            id: self.id,
+            number: self.number,
+            title: self.title,
+            html_url: self.html_url.clone(),
+            state: self.state,
+            body: self.body,
+            labels: self.labels,
+            repository: Repository {
+                id: 0,
+                full_name: format!("{owner}/{name}"),
+                name,
+                html_url,
+            },
+            label_string: None,
+            assignees: self.assignees,
+            milestone: self.milestone,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        })
+    }
 }
 
-#[derive(Debug, Default)]
+// a single issue fetched from the repository endpoint, which has no repository object
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IssueSummary {
+    pub number: i64,
+    pub title: String,
+    pub html_url: String,
+    pub state: String,
+    pub labels: Option<Vec<Label>>,
+    // the GraphQL id, e.g. to pin the issue
+    #[serde(default)]
+    pub node_id: Option<String>,
+}
+
+// a repository fetched from the repository endpoint, for its visibility
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RepositorySummary {
+    pub full_name: String,
+    pub private: bool,
+}
+
+// a gist created or edited by the bot
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GistSummary {
+    pub id: String,
+    pub html_url: String,
+}
+
+// body of the create issue endpoint
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NewIssue {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub assignees: Vec<String>,
+}
+
+// owner/repo#number parsed from an issue URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueRef {
+    pub owner: String,
+    pub repo: String,
+    pub number: i64,
+}
+
+impl IssueRef {
+    pub fn from_issue(issue: &Issue) -> Option<Self> {
+        let (owner, repo) = issue.repository.full_name.split_once('/')?;
+        Some(Self {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            number: issue.number,
+        })
+    }
+
+    // parse "owner/repo#number"
+    pub fn parse(value: &str) -> Option<Self> {
+        let (full_name, number) = value.split_once('#')?;
+        let (owner, repo) = full_name.split_once('/')?;
+        Some(Self {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            number: number.parse().ok()?,
+        })
+    }
+}
+
+impl std::fmt::Display for IssueRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}/{}#{}", self.owner, self.repo, self.number)
+    }
+}
+
+pub const PRIORITY_HIGH_LABEL: &str = "Priority: High";
+pub const PRIORITY_MEDIUM_LABEL: &str = "Priority: Medium";
+pub const PRIORITY_LOW_LABEL: &str = "Priority: Low";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Priority {
+    High,
+    Medium,
+    Low,
+    None,
+}
+
+impl Priority {
+    pub const ALL: [Priority; 4] = [
+        Priority::High,
+        Priority::Medium,
+        Priority::Low,
+        Priority::None,
+    ];
+
+    // "high", "medium", "low" or "none"
+    pub fn key(&self) -> &'static str {
+        match self {
+            Priority::High => "high",
+            Priority::Medium => "medium",
+            Priority::Low => "low",
+            Priority::None => "none",
+        }
+    }
+
+    pub fn parse(key: &str) -> Option<Self> {
+        Priority::ALL
+            .into_iter()
+            .find(|priority| priority.key() == key)
+    }
+
+    pub fn label_name(&self) -> Option<&'static str> {
+        match self {
+            Priority::High => Some(PRIORITY_HIGH_LABEL),
+            Priority::Medium => Some(PRIORITY_MEDIUM_LABEL),
+            Priority::Low => Some(PRIORITY_LOW_LABEL),
+            Priority::None => None,
+        }
+    }
+
+    pub fn is_priority_label(name: &str) -> bool {
+        [
+            PRIORITY_HIGH_LABEL,
+            PRIORITY_MEDIUM_LABEL,
+            PRIORITY_LOW_LABEL,
+        ]
+        .contains(&name)
+    }
+
+    // the highest priority among the labels
+    pub fn from_labels(labels: &[Label]) -> Self {
+        let has_label = |name: &str| labels.iter().any(|label| label.name == name);
+        if has_label(PRIORITY_HIGH_LABEL) {
+            Priority::High
+        } else if has_label(PRIORITY_MEDIUM_LABEL) {
+            Priority::Medium
+        } else if has_label(PRIORITY_LOW_LABEL) {
+            Priority::Low
+        } else {
+            Priority::None
+        }
+    }
+
+    pub fn raise(&self) -> Self {
+        match self {
+            Priority::High | Priority::Medium => Priority::High,
+            Priority::Low => Priority::Medium,
+            Priority::None => Priority::Low,
+        }
+    }
+
+    pub fn lower(&self) -> Self {
+        match self {
+            Priority::High => Priority::Medium,
+            Priority::Medium => Priority::Low,
+            Priority::Low | Priority::None => Priority::None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct SortedIssues {
     pub priority_high_issues: Vec<Issue>,
     pub priority_medium_issues: Vec<Issue>,
@@ -33,6 +305,38 @@ pub struct SortedIssues {
     pub priority_none_issues: Vec<Issue>,
 }
 
+impl SortedIssues {
+    // the issues of each priority, from high to none
+    pub fn groups(&self) -> [(Priority, &Vec<Issue>); 4] {
+        [
+            (Priority::High, &self.priority_high_issues),
+            (Priority::Medium, &self.priority_medium_issues),
+            (Priority::Low, &self.priority_low_issues),
+            (Priority::None, &self.priority_none_issues),
+        ]
+    }
+
+    // all issues, higher priority first
+    pub fn iter(&self) -> impl Iterator<Item = &Issue> {
+        self.priority_high_issues
+            .iter()
+            .chain(&self.priority_medium_issues)
+            .chain(&self.priority_low_issues)
+            .chain(&self.priority_none_issues)
+    }
+
+    // logins of the assignees of all issues, without duplicates
+    pub fn assignee_logins(&self) -> Vec<&str> {
+        let mut logins: Vec<&str> = Vec::new();
+        for login in self.iter().flat_map(|issue| issue.assignee_logins()) {
+            if !logins.contains(&login) {
+                logins.push(login);
+            }
+        }
+        logins
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -42,20 +346,53 @@ mod tests {
         let label = Label {
             id: 1,
             name: "test".to_string(),
+            color: None,
+            description: None,
         };
         assert_eq!(label.id, 1);
         assert_eq!(label.name, "test");
     }
 
+    #[test]
+    fn test_label_from_api() {
+        let label: Label = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "name": "bug",
+            "color": "d73a4a",
+            "description": "Something isn't working",
+        }))
+        .unwrap();
+        assert_eq!(label.color.as_deref(), Some("d73a4a"));
+        assert_eq!(
+            label.description.as_deref(),
+            Some("Something isn't working")
+        );
+
+        // labels in the state files of older versions have neither
+        let label: Label =
+            serde_json::from_value(serde_json::json!({"id": 1, "name": "bug"})).unwrap();
+        assert_eq!(label.color, None);
+    }
+
+    #[test]
+    fn test_priority_key() {
+        for priority in Priority::ALL {
+            assert_eq!(Priority::parse(priority.key()), Some(priority));
+        }
+        assert_eq!(Priority::parse("urgent"), None);
+    }
+
     #[test]
     fn test_repository_1() {
         let repo = Repository {
             id: 1,
             name: "test".to_string(),
+            full_name: "owner/test".to_string(),
             html_url: "test".to_string(),
         };
         assert_eq!(repo.id, 1);
         assert_eq!(repo.name, "test");
+        assert_eq!(repo.full_name, "owner/test");
         assert_eq!(repo.html_url, "test");
     }
 
@@ -71,9 +408,15 @@ mod tests {
             repository: Repository {
                 id: 1,
                 name: "test".to_string(),
+                full_name: "owner/test".to_string(),
                 html_url: "test".to_string(),
             },
             label_string: None,
+            assignees: None,
+            milestone: None,
+            created_at: None,
+            updated_at: None,
+            number: 1,
         };
         assert_eq!(issue.id, 1);
         assert_eq!(issue.title, "test");
@@ -85,6 +428,8 @@ mod tests {
         assert_eq!(issue.repository.name, "test");
         assert_eq!(issue.repository.html_url, "test");
         assert_eq!(issue.label_string, None);
+        assert_eq!(issue.number, 1);
+        assert_eq!(issue.repository.full_name, "owner/test");
     }
 
     #[test]
@@ -94,5 +439,120 @@ mod tests {
         assert_eq!(sorted_issues.priority_medium_issues.len(), 0);
         assert_eq!(sorted_issues.priority_low_issues.len(), 0);
         assert_eq!(sorted_issues.priority_none_issues.len(), 0);
+        assert_eq!(sorted_issues.iter().count(), 0);
+        assert!(sorted_issues.assignee_logins().is_empty());
+    }
+
+    #[test]
+    fn test_issue_ref_1() {
+        let issue_ref = IssueRef::parse("owner/repo#12").unwrap();
+        assert_eq!(issue_ref.owner, "owner");
+        assert_eq!(issue_ref.repo, "repo");
+        assert_eq!(issue_ref.number, 12);
+        assert_eq!(issue_ref.to_string(), "owner/repo#12");
+        assert!(IssueRef::parse("owner/repo").is_none());
+        assert!(IssueRef::parse("repo#12").is_none());
+    }
+
+    #[test]
+    fn test_priority_from_labels() {
+        let labels = vec![
+            Label {
+                id: 1,
+                name: PRIORITY_LOW_LABEL.to_string(),
+                color: None,
+                description: None,
+            },
+            Label {
+                id: 2,
+                name: PRIORITY_HIGH_LABEL.to_string(),
+                color: None,
+                description: None,
+            },
+        ];
+        assert_eq!(Priority::from_labels(&labels), Priority::High);
+        assert_eq!(Priority::from_labels(&[]), Priority::None);
+    }
+
+    #[test]
+    fn test_priority_raise_and_lower() {
+        assert_eq!(Priority::None.raise(), Priority::Low);
+        assert_eq!(Priority::High.raise(), Priority::High);
+        assert_eq!(Priority::High.lower(), Priority::Medium);
+        assert_eq!(Priority::None.lower(), Priority::None);
+        assert_eq!(Priority::Medium.label_name(), Some(PRIORITY_MEDIUM_LABEL));
+        assert_eq!(Priority::None.label_name(), None);
+        assert!(Priority::is_priority_label(PRIORITY_LOW_LABEL));
+        assert!(!Priority::is_priority_label("bug"));
+    }
+
+    #[test]
+    fn test_issue_assignees_and_milestone() {
+        use chrono::TimeZone;
+
+        let issue: Issue = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "number": 1,
+            "title": "test",
+            "html_url": "test",
+            "state": "open",
+            "body": null,
+            "labels": [],
+            "repository": {"id": 1, "name": "test", "full_name": "owner/test", "html_url": "test"},
+            "label_string": null,
+            "assignees": [{"login": "alice"}, {"login": "bob"}],
+            "milestone": {
+                "number": 1,
+                "title": "v1",
+                "html_url": "milestone",
+                "due_on": "2023-01-10T08:00:00Z"
+            }
+        }))
+        .unwrap();
+        assert_eq!(issue.assignee_logins(), vec!["alice", "bob"]);
+        let due_on = Utc.with_ymd_and_hms(2023, 1, 10, 8, 0, 0).unwrap();
+        assert!(!issue.is_overdue(due_on));
+        assert!(issue.is_overdue(due_on + chrono::Duration::seconds(1)));
+    }
+
+    #[test]
+    fn test_issue_without_assignees_and_milestone() {
+        let issue: Issue = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "number": 1,
+            "title": "test",
+            "html_url": "test",
+            "state": "open",
+            "body": null,
+            "labels": null,
+            "repository": {"id": 1, "name": "test", "full_name": "owner/test", "html_url": "test"},
+            "label_string": null
+        }))
+        .unwrap();
+        assert!(issue.assignee_logins().is_empty());
+        assert!(!issue.is_overdue(Utc::now()));
+    }
+
+    #[test]
+    fn test_search_issue_into_issue() {
+        let search_issue: SearchIssue = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "number": 5,
+            "title": "test",
+            "html_url": "https://github.com/owner/repo/issues/5",
+            "state": "open",
+            "body": null,
+            "labels": [],
+            "assignees": [{"login": "octocat"}],
+            "milestone": null,
+            "repository_url": "https://api.github.com/repos/owner/repo"
+        }))
+        .unwrap();
+        let issue = search_issue.into_issue().unwrap();
+        assert_eq!(issue.repository.name, "repo");
+        assert_eq!(issue.repository.full_name, "owner/repo");
+        assert_eq!(issue.repository.html_url, "https://github.com/owner/repo");
+        assert_eq!(issue.html_url, "https://github.com/owner/repo/issues/5");
+        assert_eq!(issue.assignee_logins(), vec!["octocat"]);
     }
 }
@@ -0,0 +1,332 @@
+use crate::dashboard::notifier::DashboardNotifier;
+use crate::dashboard::state::DASHBOARD_STATE_FILE_NAME;
+use crate::digest::Digest;
+use crate::discord::notifier::DiscordWebhookNotifier;
+use crate::email::notifier::EmailNotifier;
+use crate::env::{
+    get_dashboard_target, get_discord_webhook_url, get_email_from, get_email_to,
+    get_github_personal_access_token, get_locale_settings, get_matrix_digest_update_window_secs,
+    get_matrix_settings, get_slack_bot_token, get_slack_channel_id, get_slack_digest_layout,
+    get_slack_digest_update_window_secs, get_slack_mention_policy, get_slack_snooze_days,
+    get_slack_user_map, get_slack_webhook_url, get_smtp_settings, get_state_dir,
+    get_teams_webhook_url, get_webhook_max_attempts, get_webhook_timeout_secs,
+    get_webhook_url_and_secret, is_dashboard_pin_enabled, is_slack_interactive_digest_enabled,
+    DASHBOARD_TARGET_KEY, DISCORD_WEBHOOK_URL_KEY, EMAIL_FROM_KEY, MATRIX_ACCESS_TOKEN_KEY,
+    MATRIX_HOMESERVER_URL_KEY, MATRIX_ROOM_ID_KEY, SLACK_BOT_TOKEN_KEY, SLACK_CHANNEL_ID_KEY,
+    SLACK_WEBHOOK_URL_KEY, SMTP_HOST_KEY, TEAMS_WEBHOOK_URL_KEY, WEBHOOK_SECRET_KEY,
+    WEBHOOK_URL_KEY,
+};
+use crate::errors::NotifyError;
+use crate::github::{get_my_issues, sort_issues};
+use crate::matrix::history::MATRIX_EVENT_HISTORY_FILE_NAME;
+use crate::matrix::notifier::MatrixNotifier;
+use crate::slack::history::SLACK_MESSAGE_HISTORY_FILE_NAME;
+use crate::slack::mention::{create_mention_text, MentionTarget};
+use crate::slack::notifier::{SlackApiNotifier, SlackDigestFormat, SlackWebhookNotifier};
+use crate::slack::template::DigestTemplate;
+use crate::slack::user_map::{resolve_slack_users, SLACK_USER_MAP_FILE_NAME};
+use crate::snooze::{SnoozeScope, SnoozedIssues, SNOOZED_ISSUES_FILE_NAME};
+use crate::store::{load_json, state_file_path};
+use crate::teams::notifier::TeamsWebhookNotifier;
+use crate::webhook::notifier::WebhookNotifier;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::time::Duration;
+
+pub const SLACK_WEBHOOK_NOTIFIER: &str = "slack-webhook";
+pub const SLACK_API_NOTIFIER: &str = "slack-api";
+pub const DISCORD_NOTIFIER: &str = "discord";
+pub const TEAMS_NOTIFIER: &str = "teams";
+pub const EMAIL_NOTIFIER: &str = "email";
+pub const WEBHOOK_NOTIFIER: &str = "webhook";
+pub const MATRIX_NOTIFIER: &str = "matrix";
+pub const DASHBOARD_NOTIFIER: &str = "dashboard";
+
+// a channel which a digest is delivered to
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    // e.g. "slack-webhook", used in the reports
+    fn name(&self) -> &str;
+
+    async fn notify(&self, digest: &Digest) -> Result<(), NotifyError>;
+}
+
+#[derive(Debug)]
+pub struct NotifyReport {
+    pub name: String,
+    pub result: Result<(), NotifyError>,
+}
+
+// deliver the digest by every notifier in order
+// a failure doesn't stop the others, the result of each is in the reports
+pub async fn notify_all(notifiers: &[Box<dyn Notifier>], digest: &Digest) -> Vec<NotifyReport> {
+    let mut reports = Vec::new();
+    for notifier in notifiers {
+        let result = notifier.notify(digest).await;
+        match &result {
+            Ok(()) => println!("Notify by {} OK", notifier.name()),
+            Err(err) => println!("Notify by {} Error: {err}", notifier.name()),
+        }
+        reports.push(NotifyReport {
+            name: notifier.name().to_string(),
+            result,
+        });
+    }
+    reports
+}
+
+// e.g. "slack-api: channel_not_found", None when every notifier succeeded
+pub fn failure_message(reports: &[NotifyReport]) -> Option<String> {
+    let failures: Vec<String> = reports
+        .iter()
+        .filter_map(|report| match &report.result {
+            Ok(()) => None,
+            Err(err) => Some(format!("{}: {err}", report.name)),
+        })
+        .collect();
+    match failures.is_empty() {
+        true => None,
+        false => Some(failures.join("\n")),
+    }
+}
+
+// the notifiers of `names`, see env::get_notifier_names
+// a notifier whose settings are missing is left out with a message
+pub fn create_notifiers(names: &[String], format: &SlackDigestFormat) -> Vec<Box<dyn Notifier>> {
+    let mut notifiers: Vec<Box<dyn Notifier>> = Vec::new();
+    for name in names {
+        match name.as_str() {
+            SLACK_WEBHOOK_NOTIFIER => match get_slack_webhook_url() {
+                Some(webhook_url) => notifiers.push(Box::new(SlackWebhookNotifier {
+                    webhook_url,
+                    format: format.clone(),
+                })),
+                None => println!("Notifier {name} needs {SLACK_WEBHOOK_URL_KEY}"),
+            },
+            SLACK_API_NOTIFIER => match (get_slack_bot_token(), get_slack_channel_id()) {
+                (Some(token), Some(channel)) => notifiers.push(Box::new(SlackApiNotifier {
+                    slack_api_addr: "https://slack.com/api".to_string(),
+                    token,
+                    channel,
+                    history_path: state_file_path(
+                        &get_state_dir(),
+                        SLACK_MESSAGE_HISTORY_FILE_NAME,
+                    ),
+                    update_window_secs: get_slack_digest_update_window_secs(),
+                    layout: get_slack_digest_layout(),
+                    format: format.clone(),
+                })),
+                _ => println!(
+                    "Notifier {name} needs {SLACK_BOT_TOKEN_KEY} and {SLACK_CHANNEL_ID_KEY}"
+                ),
+            },
+            // Discord, Teams, email, Matrix and the dashboard have no locale of their own, so the default locale is used
+            DISCORD_NOTIFIER => match get_discord_webhook_url() {
+                Some(webhook_url) => notifiers.push(Box::new(DiscordWebhookNotifier {
+                    webhook_url,
+                    locale: get_locale_settings().default,
+                    style: format.template.style().clone(),
+                })),
+                None => println!("Notifier {name} needs {DISCORD_WEBHOOK_URL_KEY}"),
+            },
+            TEAMS_NOTIFIER => match get_teams_webhook_url() {
+                Some(webhook_url) => notifiers.push(Box::new(TeamsWebhookNotifier {
+                    webhook_url,
+                    locale: get_locale_settings().default,
+                    style: format.template.style().clone(),
+                })),
+                None => println!("Notifier {name} needs {TEAMS_WEBHOOK_URL_KEY}"),
+            },
+            EMAIL_NOTIFIER => match (get_smtp_settings(), get_email_from()) {
+                (Some(smtp), Some(from)) => notifiers.push(Box::new(EmailNotifier {
+                    smtp,
+                    from,
+                    to: get_email_to(),
+                    locale: get_locale_settings().default,
+                    style: format.template.style().clone(),
+                })),
+                _ => println!("Notifier {name} needs {SMTP_HOST_KEY} and {EMAIL_FROM_KEY}"),
+            },
+            WEBHOOK_NOTIFIER => match get_webhook_url_and_secret() {
+                Some((url, secret)) => notifiers.push(Box::new(WebhookNotifier {
+                    url,
+                    secret,
+                    timeout: Duration::from_secs(get_webhook_timeout_secs()),
+                    max_attempts: get_webhook_max_attempts(),
+                    retry_delay: Duration::from_secs(1),
+                })),
+                None => {
+                    println!("Notifier {name} needs {WEBHOOK_URL_KEY} and {WEBHOOK_SECRET_KEY}")
+                }
+            },
+            MATRIX_NOTIFIER => match get_matrix_settings() {
+                Some((homeserver_url, access_token, room_id)) => {
+                    notifiers.push(Box::new(MatrixNotifier {
+                        homeserver_url,
+                        access_token,
+                        room_id,
+                        history_path: state_file_path(
+                            &get_state_dir(),
+                            MATRIX_EVENT_HISTORY_FILE_NAME,
+                        ),
+                        update_window_secs: get_matrix_digest_update_window_secs(),
+                        locale: get_locale_settings().default,
+                        style: format.template.style().clone(),
+                    }))
+                }
+                None => println!(
+                    "Notifier {name} needs {MATRIX_HOMESERVER_URL_KEY}, {MATRIX_ACCESS_TOKEN_KEY} and {MATRIX_ROOM_ID_KEY}"
+                ),
+            },
+            DASHBOARD_NOTIFIER => match get_dashboard_target() {
+                Some(target) => notifiers.push(Box::new(DashboardNotifier {
+                    github_api_addr: "https://api.github.com".to_string(),
+                    token: get_github_personal_access_token(),
+                    target,
+                    pin: is_dashboard_pin_enabled(),
+                    state_path: state_file_path(&get_state_dir(), DASHBOARD_STATE_FILE_NAME),
+                    locale: get_locale_settings().default,
+                    style: format.template.style().clone(),
+                })),
+                None => println!("Notifier {name} needs {DASHBOARD_TARGET_KEY}"),
+            },
+            _ => println!("Unknown Notifier: {name}"),
+        }
+    }
+    notifiers
+}
+
+// the notifier which emails a personal digest, when email is one of the notifiers
+// it has no recipients of its own, so the digest goes only to the ones set on it, never to EMAIL_TO
+pub fn create_personal_email_notifier(
+    notifier_names: &[String],
+    format: &SlackDigestFormat,
+) -> Option<EmailNotifier> {
+    if !notifier_names.iter().any(|name| name == EMAIL_NOTIFIER) {
+        return None;
+    }
+    Some(EmailNotifier {
+        smtp: get_smtp_settings()?,
+        from: get_email_from()?,
+        to: vec![],
+        locale: format.locale,
+        style: format.template.style().clone(),
+    })
+}
+
+// the channel digest hides the issues snoozed in its channel
+// a webhook doesn't tell its channel, so the one of SLACK_CHANNEL_ID is used for it too
+pub fn team_snooze_scope() -> SnoozeScope {
+    SnoozeScope::Channel(get_slack_channel_id().unwrap_or_default())
+}
+
+// the digest of the issues of GITHUB_PERSONAL_ACCESS_TOKEN, which the notifiers deliver to the team
+// it is posted to a channel, so it is in the locale of the channel and mentions by SLACK_MENTION
+pub async fn create_team_digest(
+    github_api_addr: &str,
+    template: DigestTemplate,
+) -> (Digest, SlackDigestFormat) {
+    let token = get_github_personal_access_token();
+    let now = chrono::Utc::now();
+    let my_issues = get_my_issues(github_api_addr.to_string(), token.clone()).await;
+    let snoozed_issues: SnoozedIssues =
+        load_json(&state_file_path(&get_state_dir(), SNOOZED_ISSUES_FILE_NAME));
+    let my_issues = snoozed_issues.filter_issues(&team_snooze_scope(), my_issues, now.timestamp());
+    let sorted_issues = sort_issues(my_issues);
+    let locale = get_locale_settings().channel_locale(get_slack_channel_id().as_deref());
+    let mention_policy = get_slack_mention_policy();
+    let mention = match &sorted_issues {
+        Ok(issues) => {
+            // Slack users are looked up only when they are mentioned
+            let slack_users = if mention_policy.target == MentionTarget::Assignees {
+                resolve_slack_users(
+                    "https://slack.com/api",
+                    get_slack_bot_token().as_deref(),
+                    github_api_addr,
+                    &token,
+                    &issues.assignee_logins(),
+                    &get_slack_user_map(),
+                    &state_file_path(&get_state_dir(), SLACK_USER_MAP_FILE_NAME),
+                )
+                .await
+            } else {
+                HashMap::new()
+            };
+            create_mention_text(&mention_policy, issues, &slack_users, now)
+        }
+        Err(_) => None,
+    };
+    let format = SlackDigestFormat {
+        locale,
+        template,
+        mention,
+        snooze_days: is_slack_interactive_digest_enabled().then(get_slack_snooze_days),
+    };
+    (Digest::new(sorted_issues, now), format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    struct FakeNotifier {
+        name: &'static str,
+        error: Option<&'static str>,
+    }
+
+    #[async_trait]
+    impl Notifier for FakeNotifier {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn notify(&self, _digest: &Digest) -> Result<(), NotifyError> {
+            match self.error {
+                Some(message) => Err(NotifyError {
+                    message: message.to_string(),
+                }),
+                None => Ok(()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_notify_all() {
+        let notifiers: Vec<Box<dyn Notifier>> = vec![
+            Box::new(FakeNotifier {
+                name: "first",
+                error: Some("timeout"),
+            }),
+            Box::new(FakeNotifier {
+                name: "second",
+                error: None,
+            }),
+        ];
+        let digest = Digest::new(Ok(Default::default()), Utc::now());
+        let reports = notify_all(&notifiers, &digest).await;
+        // the failure of the first doesn't stop the second
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].name, "first");
+        assert!(reports[0].result.is_err());
+        assert!(reports[1].result.is_ok());
+        assert_eq!(
+            failure_message(&reports),
+            Some("first: timeout".to_string())
+        );
+        assert_eq!(failure_message(&reports[1..]), None);
+    }
+
+    #[test]
+    fn test_create_notifiers_skips_unknown() {
+        let notifiers = create_notifiers(&["unknown".to_string()], &SlackDigestFormat::default());
+        assert!(notifiers.is_empty());
+    }
+
+    #[test]
+    fn test_create_personal_email_notifier_without_email() {
+        let names = vec![SLACK_API_NOTIFIER.to_string()];
+        let notifier = create_personal_email_notifier(&names, &SlackDigestFormat::default());
+        assert!(notifier.is_none());
+    }
+}
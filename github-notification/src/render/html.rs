@@ -0,0 +1,114 @@
+use super::{DigestRenderer, DigestView, GroupView, IssueView};
+
+pub fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+pub fn link(url: &str, text: &str) -> String {
+    format!("<a href=\"{}\">{}</a>", escape_html(url), escape_html(text))
+}
+
+// the style of a standalone page, overdue dates in the color of the high priority
+const STYLE: &str =
+    "body{font-family:sans-serif}code{background:#eee;padding:0 4px}.overdue{color:#E74C3C}";
+
+// a standalone HTML page
+pub struct HtmlRenderer;
+
+impl DigestRenderer for HtmlRenderer {
+    fn render_issue(&self, issue: &IssueView) -> String {
+        let repository = &issue.issue.repository;
+        let mut html = format!(
+            "{} ({})",
+            link(&issue.issue.html_url, &issue.issue.title),
+            link(&repository.html_url, &repository.full_name)
+        );
+        for label in &issue.labels {
+            html.push_str(&format!(" <code>{}</code>", escape_html(&label.name)));
+        }
+        if let Some(due_label) = &issue.due_label {
+            html.push_str(&match issue.overdue {
+                true => format!(" <span class=\"overdue\">{}</span>", escape_html(due_label)),
+                false => format!(" {}", escape_html(due_label)),
+            });
+        }
+        html
+    }
+
+    fn render_group_header(&self, group: &GroupView) -> String {
+        format!("<h2>{}</h2>", escape_html(&group.title_with_emoji()))
+    }
+
+    fn render_group(&self, group: &GroupView) -> String {
+        let items: Vec<String> = group
+            .issues
+            .iter()
+            .map(|issue| format!("<li>{}</li>", self.render_issue(issue)))
+            .collect();
+        format!(
+            "<section>\n{}\n<ul>\n{}\n</ul>\n</section>",
+            self.render_group_header(group),
+            items.join("\n")
+        )
+    }
+
+    fn render_title(&self, title: &str) -> String {
+        format!("<h1>{}</h1>", escape_html(title))
+    }
+
+    fn render_text(&self, text: &str) -> String {
+        format!("<p>{}</p>", escape_html(text))
+    }
+
+    // a whole document, so that it can be opened or attached as it is
+    fn render_document(&self, digest: &DigestView, blocks: Vec<String>) -> String {
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n{}\n</body>\n</html>\n",
+            digest.locale.code(),
+            escape_html(&digest.title),
+            blocks.join("\n")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::digest;
+    use crate::i18n::Locale;
+    use crate::render::render;
+    use crate::slack::style::DigestStyle;
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html("<a href=\"x\">'&'</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&#39;&amp;&#39;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_html_renderer() {
+        let html = render(
+            &HtmlRenderer,
+            &digest("<script>"),
+            Locale::En,
+            &DigestStyle::default(),
+        );
+        assert!(html.starts_with("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>Tasks</title>\n"));
+        assert!(html.contains(
+            "<body>\n<h1>Tasks</h1>\n<section>\n<h2>🔴 Priority: High</h2>\n<ul>\n\
+             <li><a href=\"https://github.com/owner/name/issues/1\">&lt;script&gt;</a> (<a href=\"https://github.com/owner/name\">owner/name</a>) <code>bug</code></li>\n\
+             </ul>\n</section>\n"
+        ));
+        assert!(
+            html.contains("<code>bug</code> <span class=\"overdue\">(due yesterday)</span></li>")
+        );
+        assert!(html.ends_with("</section>\n</body>\n</html>\n"));
+    }
+}
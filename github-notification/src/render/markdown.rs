@@ -0,0 +1,141 @@
+use super::{DigestRenderer, GroupView, IssueView};
+
+// GitHub-flavored Markdown
+// https://github.github.com/gfm/
+
+const ZERO_WIDTH_SPACE: char = '\u{200B}';
+// any ASCII punctuation may be escaped, these are the ones which start markup in a line
+const SPECIAL_CHARACTERS: [char; 15] = [
+    '\\', '`', '*', '_', '{', '}', '[', ']', '(', ')', '<', '>', '#', '|', '~',
+];
+
+// user-controlled text shown as it is
+// "@" is followed by a zero width space so that nobody is mentioned on GitHub
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if SPECIAL_CHARACTERS.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+        if c == '@' {
+            escaped.push(ZERO_WIDTH_SPACE);
+        }
+    }
+    escaped
+}
+
+// parentheses and spaces would end the destination of a link
+fn escape_url(url: &str) -> String {
+    url.replace('(', "%28")
+        .replace(')', "%29")
+        .replace(' ', "%20")
+}
+
+pub fn link(url: &str, text: &str) -> String {
+    format!("[{}]({})", escape_markdown(text), escape_url(url))
+}
+
+// nothing is formatted in a code span, but a backtick would end it, so it is replaced with a look-alike
+pub fn code(text: &str) -> String {
+    format!("`{}`", text.replace('`', "\u{02CB}"))
+}
+
+pub struct MarkdownRenderer;
+
+impl DigestRenderer for MarkdownRenderer {
+    // e.g. "[title](url) ([owner/name](url)) `bug` (due tomorrow)"
+    fn render_issue(&self, issue: &IssueView) -> String {
+        let repository = &issue.issue.repository;
+        let mut parts = vec![
+            link(&issue.issue.html_url, &issue.issue.title),
+            format!("({})", link(&repository.html_url, &repository.full_name)),
+        ];
+        parts.extend(issue.labels.iter().map(|label| code(&label.name)));
+        if let Some(due_label) = &issue.due_label {
+            let due_label = escape_markdown(due_label);
+            parts.push(match issue.overdue {
+                true => format!("**{due_label}**"),
+                false => due_label,
+            });
+        }
+        parts.join(" ")
+    }
+
+    fn render_group_header(&self, group: &GroupView) -> String {
+        format!("## {}", escape_markdown(&group.title_with_emoji()))
+    }
+
+    // a blank line between the header and the list
+    fn render_group(&self, group: &GroupView) -> String {
+        let items: Vec<String> = group
+            .issues
+            .iter()
+            .map(|issue| format!("- {}", self.render_issue(issue)))
+            .collect();
+        format!(
+            "{}\n\n{}",
+            self.render_group_header(group),
+            items.join("\n")
+        )
+    }
+
+    fn render_title(&self, title: &str) -> String {
+        format!("# {}", escape_markdown(title))
+    }
+
+    fn render_text(&self, text: &str) -> String {
+        escape_markdown(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digest::Digest;
+    use crate::fixtures::{digest, now};
+    use crate::i18n::Locale;
+    use crate::models::SortedIssues;
+    use crate::render::render;
+    use crate::slack::style::DigestStyle;
+
+    #[test]
+    fn test_escape_markdown() {
+        assert_eq!(
+            escape_markdown("*a* [b](c) #1 @octocat"),
+            "\\*a\\* \\[b\\]\\(c\\) \\#1 @\u{200B}octocat"
+        );
+        assert_eq!(code("a`b"), "`a\u{02CB}b`");
+        assert_eq!(link("https://a/b c", "d"), "[d](https://a/b%20c)");
+    }
+
+    #[test]
+    fn test_markdown_renderer() {
+        let markdown = render(
+            &MarkdownRenderer,
+            &digest("fix *bold*"),
+            Locale::En,
+            &DigestStyle::default(),
+        );
+        assert_eq!(
+            markdown,
+            "# Tasks\n\n\
+             ## 🔴 Priority: High\n\n\
+             - [fix \\*bold\\*](https://github.com/owner/name/issues/1) ([owner/name](https://github.com/owner/name)) `bug`\n\n\
+             ## 🟡 Priority: Low\n\n\
+             - [overdue](https://github.com/owner/name/issues/2) ([owner/name](https://github.com/owner/name)) `bug` **\\(due yesterday\\)**\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_renderer_empty() {
+        let digest = Digest::new(Ok(SortedIssues::default()), now());
+        let markdown = render(
+            &MarkdownRenderer,
+            &digest,
+            Locale::Ja,
+            &DigestStyle::default(),
+        );
+        assert_eq!(markdown, "# タスク一覧\n\nタスクはありません\n");
+    }
+}
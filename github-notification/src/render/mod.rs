@@ -0,0 +1,209 @@
+// renderers of the digest into a markup
+// the grouping and what is shown of an issue are decided here once,
+// and a renderer only decides how it looks
+pub mod html;
+pub mod markdown;
+pub mod plain;
+
+use crate::digest::Digest;
+use crate::i18n::{relative_date, text, text_with, Locale};
+use crate::models::{Issue, Label, Priority, SortedIssues};
+use crate::slack::style::{group_title, DigestStyle};
+use chrono::{DateTime, Utc};
+
+// an issue as a renderer sees it
+#[derive(Debug)]
+pub struct IssueView<'a> {
+    pub issue: &'a Issue,
+    // without the labels hidden by the style
+    pub labels: Vec<&'a Label>,
+    // e.g. "(due tomorrow)", None without a due date
+    pub due_label: Option<String>,
+    pub overdue: bool,
+}
+
+impl<'a> IssueView<'a> {
+    pub fn new(
+        issue: &'a Issue,
+        priority: Priority,
+        locale: Locale,
+        style: &DigestStyle,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let due_label = issue
+            .milestone
+            .as_ref()
+            .and_then(|milestone| milestone.due_on)
+            .map(|due_on| {
+                let date = relative_date(locale, due_on, now);
+                text_with(locale, "digest.due", &[("date", &date)])
+            });
+        Self {
+            issue,
+            labels: style.visible_labels(issue, priority),
+            due_label,
+            overdue: issue.is_overdue(now),
+        }
+    }
+}
+
+// a priority and its issues
+#[derive(Debug)]
+pub struct GroupView<'a> {
+    pub priority: Priority,
+    // empty when the priority has no emoji
+    pub emoji: String,
+    // without markup, e.g. "Priority: High"
+    pub title: String,
+    pub issues: Vec<IssueView<'a>>,
+}
+
+impl GroupView<'_> {
+    // e.g. "🔴 Priority: High"
+    pub fn title_with_emoji(&self) -> String {
+        match self.emoji.as_str() {
+            "" => self.title.clone(),
+            emoji => format!("{emoji} {}", self.title),
+        }
+    }
+}
+
+// the priorities which have issues, from high to none
+pub fn group_views<'a>(
+    issues: &'a SortedIssues,
+    locale: Locale,
+    style: &DigestStyle,
+    now: DateTime<Utc>,
+) -> Vec<GroupView<'a>> {
+    issues
+        .groups()
+        .into_iter()
+        .filter(|(_, issues)| !issues.is_empty())
+        .map(|(priority, issues)| GroupView {
+            priority,
+            emoji: style.emoji(priority).to_string(),
+            title: group_title(priority, locale),
+            issues: issues
+                .iter()
+                .map(|issue| IssueView::new(issue, priority, locale, style, now))
+                .collect(),
+        })
+        .collect()
+}
+
+// the whole digest as a renderer sees it
+#[derive(Debug)]
+pub struct DigestView<'a> {
+    pub locale: Locale,
+    pub title: String,
+    // the groups, or a text instead of them, why the issues could not be fetched
+    // or that there is no issue
+    pub content: Result<Vec<GroupView<'a>>, String>,
+}
+
+impl<'a> DigestView<'a> {
+    pub fn new(digest: &'a Digest, locale: Locale, style: &DigestStyle) -> Self {
+        let content = match &digest.issues {
+            Err(e) => Err(e.message.clone()),
+            Ok(issues) => match group_views(issues, locale, style, digest.generated_at) {
+                groups if groups.is_empty() => Err(text(locale, "digest.summary.empty")),
+                groups => Ok(groups),
+            },
+        };
+        Self {
+            locale,
+            title: text(locale, "digest.title"),
+            content,
+        }
+    }
+}
+
+pub trait DigestRenderer {
+    // an issue with its repository, labels and due date, without a list marker
+    fn render_issue(&self, issue: &IssueView) -> String;
+
+    fn render_group_header(&self, group: &GroupView) -> String;
+
+    // the header and a line per issue
+    fn render_group(&self, group: &GroupView) -> String {
+        let mut lines = vec![self.render_group_header(group)];
+        lines.extend(
+            group
+                .issues
+                .iter()
+                .map(|issue| format!("- {}", self.render_issue(issue))),
+        );
+        lines.join("\n")
+    }
+
+    fn render_title(&self, title: &str) -> String;
+
+    // a text which is not markup, e.g. an error message
+    fn render_text(&self, text: &str) -> String;
+
+    // the title and the groups, or the text instead of them, put together
+    // the blocks separated by blank lines
+    fn render_document(&self, _digest: &DigestView, blocks: Vec<String>) -> String {
+        blocks.join("\n\n") + "\n"
+    }
+
+    fn render_digest(&self, digest: &DigestView) -> String {
+        let mut blocks = vec![self.render_title(&digest.title)];
+        match &digest.content {
+            Ok(groups) => blocks.extend(groups.iter().map(|group| self.render_group(group))),
+            Err(text) => blocks.push(self.render_text(text)),
+        }
+        self.render_document(digest, blocks)
+    }
+}
+
+// e.g. render(&MarkdownRenderer, &digest, locale, &style)
+pub fn render<R: DigestRenderer>(
+    renderer: &R,
+    digest: &Digest,
+    locale: Locale,
+    style: &DigestStyle,
+) -> String {
+    renderer.render_digest(&DigestView::new(digest, locale, style))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::GetIssueError;
+    use crate::fixtures::{digest, now};
+
+    #[test]
+    fn test_group_views() {
+        let digest = digest("title");
+        let mut style = DigestStyle::default();
+        style.priority_emoji.insert(Priority::Low, String::new());
+        let view = DigestView::new(&digest, Locale::Ja, &style);
+        let groups = view.content.unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].priority, Priority::High);
+        assert_eq!(groups[0].title_with_emoji(), "🔴 優先度: 高");
+        assert_eq!(groups[1].title_with_emoji(), "優先度: 低");
+        let issue = &groups[1].issues[0];
+        assert_eq!(issue.due_label, Some("(期限: 昨日)".to_string()));
+        assert!(issue.overdue);
+        assert_eq!(issue.labels.len(), 1);
+    }
+
+    #[test]
+    fn test_digest_view_without_groups() {
+        let digest = Digest::new(Ok(SortedIssues::default()), now());
+        let view = DigestView::new(&digest, Locale::En, &DigestStyle::default());
+        assert_eq!(view.content.unwrap_err(), "No tasks");
+
+        let digest = Digest::new(
+            Err(GetIssueError {
+                message: "Get Issue Error: 401".to_string(),
+            }),
+            now(),
+        );
+        let view = DigestView::new(&digest, Locale::En, &DigestStyle::default());
+        assert_eq!(view.title, "Tasks");
+        assert_eq!(view.content.unwrap_err(), "Get Issue Error: 401");
+    }
+}
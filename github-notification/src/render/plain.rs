@@ -0,0 +1,77 @@
+use super::{DigestRenderer, GroupView, IssueView};
+
+// plain text for terminals and logs, nothing is escaped
+pub struct PlainTextRenderer;
+
+impl DigestRenderer for PlainTextRenderer {
+    // e.g. "title (owner/name#1) [bug] (due tomorrow) https://github.com/owner/name/issues/1"
+    fn render_issue(&self, issue: &IssueView) -> String {
+        let repository = &issue.issue.repository;
+        let mut parts = vec![format!(
+            "{} ({}#{})",
+            issue.issue.title, repository.full_name, issue.issue.number
+        )];
+        parts.extend(issue.labels.iter().map(|label| format!("[{}]", label.name)));
+        parts.extend(issue.due_label.clone());
+        parts.push(issue.issue.html_url.clone());
+        parts.join(" ")
+    }
+
+    fn render_group_header(&self, group: &GroupView) -> String {
+        group.title_with_emoji()
+    }
+
+    fn render_title(&self, title: &str) -> String {
+        title.to_string()
+    }
+
+    fn render_text(&self, text: &str) -> String {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digest::Digest;
+    use crate::errors::GetIssueError;
+    use crate::fixtures::{digest, now};
+    use crate::i18n::Locale;
+    use crate::render::render;
+    use crate::slack::style::DigestStyle;
+
+    #[test]
+    fn test_plain_text_renderer() {
+        let text = render(
+            &PlainTextRenderer,
+            &digest("fix *bold*"),
+            Locale::En,
+            &DigestStyle::default(),
+        );
+        assert_eq!(
+            text,
+            "Tasks\n\n\
+             🔴 Priority: High\n\
+             - fix *bold* (owner/name#1) [bug] https://github.com/owner/name/issues/1\n\n\
+             🟡 Priority: Low\n\
+             - overdue (owner/name#2) [bug] (due yesterday) https://github.com/owner/name/issues/2\n"
+        );
+    }
+
+    #[test]
+    fn test_plain_text_renderer_error() {
+        let digest = Digest::new(
+            Err(GetIssueError {
+                message: "Get Issue Error: 401".to_string(),
+            }),
+            now(),
+        );
+        let text = render(
+            &PlainTextRenderer,
+            &digest,
+            Locale::Ja,
+            &DigestStyle::default(),
+        );
+        assert_eq!(text, "タスク一覧\n\nGet Issue Error: 401\n");
+    }
+}
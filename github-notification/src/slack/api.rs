@@ -0,0 +1,560 @@
+use super::message::{SlackMessageBlock, SlackMessageBlocks, SlackModalView};
+use crate::errors::SlackApiError;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize)]
+struct ChatPostMessageRequest<'a> {
+    channel: &'a str,
+    text: &'a str,
+    blocks: &'a [SlackMessageBlock],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thread_ts: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatUpdateRequest<'a> {
+    channel: &'a str,
+    ts: &'a str,
+    text: &'a str,
+    blocks: &'a [SlackMessageBlock],
+}
+
+#[derive(Debug, Serialize)]
+struct ChatDeleteRequest<'a> {
+    channel: &'a str,
+    ts: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SlackUnfurl {
+    pub blocks: Vec<SlackMessageBlock>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatUnfurlRequest<'a> {
+    channel: &'a str,
+    ts: &'a str,
+    unfurls: &'a HashMap<String, SlackUnfurl>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatPostEphemeralRequest<'a> {
+    channel: &'a str,
+    user: &'a str,
+    text: &'a str,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    blocks: &'a [SlackMessageBlock],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thread_ts: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+struct ViewsOpenRequest<'a> {
+    trigger_id: &'a str,
+    view: &'a SlackModalView,
+}
+
+#[derive(Debug, Serialize)]
+struct ViewsUpdateRequest<'a> {
+    view_id: &'a str,
+    hash: &'a str,
+    view: &'a SlackModalView,
+}
+
+#[derive(Debug, Serialize)]
+struct ViewsPublishRequest<'a> {
+    user_id: &'a str,
+    view: &'a SlackMessageBlocks,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct SlackPostedMessage {
+    pub channel: String,
+    pub ts: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SlackViewResponse {
+    view: SlackOpenedView,
+}
+
+// a view opened by views.open, to be updated by views.update
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct SlackOpenedView {
+    pub id: String,
+    // an update fails when the view has been changed since, e.g. by another update
+    pub hash: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SlackUserResponse {
+    user: SlackUser,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SlackUser {
+    pub id: String,
+    // e.g. "ja-JP", only with include_locale
+    #[serde(default)]
+    pub locale: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SlackPermalink {
+    permalink: String,
+}
+
+// every Web API response has "ok", and "error" when "ok" is false
+fn parse_slack_api_response<R: DeserializeOwned>(
+    method: &str,
+    body: serde_json::Value,
+) -> Result<R, SlackApiError> {
+    if body["ok"].as_bool() != Some(true) {
+        let error = body["error"].as_str().unwrap_or("unknown_error");
+        let error_message = format!("Slack API {method} Error: {error}");
+        println!("{error_message}");
+        return Err(SlackApiError {
+            message: error_message,
+        });
+    }
+    serde_json::from_value(body).map_err(|e| {
+        let error_message = format!("Parse Slack API {method} Response Error: {e}");
+        println!("{error_message}");
+        SlackApiError {
+            message: error_message,
+        }
+    })
+}
+
+async fn read_slack_api_response<R: DeserializeOwned>(
+    method: &str,
+    res: Result<reqwest::Response, reqwest::Error>,
+) -> Result<R, SlackApiError> {
+    let res = res.map_err(|err| {
+        let error_message = format!("Slack API {method} Error: {err}");
+        println!("{error_message}");
+        SlackApiError {
+            message: error_message,
+        }
+    })?;
+    if res.status() != 200 {
+        let error_message = format!("Slack API {method} Error: {}", res.status());
+        println!("{error_message}");
+        return Err(SlackApiError {
+            message: error_message,
+        });
+    }
+    let body = res.json::<serde_json::Value>().await.map_err(|e| {
+        let error_message = format!("Parse Slack API {method} Response Error: {e}");
+        println!("{error_message}");
+        SlackApiError {
+            message: error_message,
+        }
+    })?;
+    parse_slack_api_response(method, body)
+}
+
+// call a write method of the Web API with a JSON body
+pub async fn post_slack_api<T: Serialize + ?Sized, R: DeserializeOwned>(
+    slack_api_addr: &str,
+    token: &str,
+    method: &str,
+    body: &T,
+) -> Result<R, SlackApiError> {
+    let client = reqwest::Client::new();
+    let res = client
+        .post(format!("{slack_api_addr}/{method}"))
+        .header("Authorization", format!("Bearer {token}"))
+        .json(body)
+        .send()
+        .await;
+    read_slack_api_response(method, res).await
+}
+
+// call a read method of the Web API with query parameters
+pub async fn get_slack_api<R: DeserializeOwned>(
+    slack_api_addr: &str,
+    token: &str,
+    method: &str,
+    query: &[(&str, &str)],
+) -> Result<R, SlackApiError> {
+    let client = reqwest::Client::new();
+    let res = client
+        .get(format!("{slack_api_addr}/{method}"))
+        .header("Authorization", format!("Bearer {token}"))
+        .query(query)
+        .send()
+        .await;
+    read_slack_api_response(method, res).await
+}
+
+pub async fn post_message(
+    slack_api_addr: &str,
+    token: &str,
+    channel: &str,
+    text: &str,
+    message_blocks: &SlackMessageBlocks,
+) -> Result<SlackPostedMessage, SlackApiError> {
+    let body = ChatPostMessageRequest {
+        channel,
+        text,
+        blocks: &message_blocks.blocks,
+        thread_ts: None,
+    };
+    post_slack_api(slack_api_addr, token, "chat.postMessage", &body).await
+}
+
+pub async fn post_thread_reply(
+    slack_api_addr: &str,
+    token: &str,
+    channel: &str,
+    thread_ts: &str,
+    text: &str,
+    message_blocks: &SlackMessageBlocks,
+) -> Result<SlackPostedMessage, SlackApiError> {
+    let body = ChatPostMessageRequest {
+        channel,
+        text,
+        blocks: &message_blocks.blocks,
+        thread_ts: Some(thread_ts),
+    };
+    post_slack_api(slack_api_addr, token, "chat.postMessage", &body).await
+}
+
+pub async fn update_message(
+    slack_api_addr: &str,
+    token: &str,
+    channel: &str,
+    ts: &str,
+    text: &str,
+    message_blocks: &SlackMessageBlocks,
+) -> Result<SlackPostedMessage, SlackApiError> {
+    let body = ChatUpdateRequest {
+        channel,
+        ts,
+        text,
+        blocks: &message_blocks.blocks,
+    };
+    post_slack_api(slack_api_addr, token, "chat.update", &body).await
+}
+
+pub async fn delete_message(
+    slack_api_addr: &str,
+    token: &str,
+    channel: &str,
+    ts: &str,
+) -> Result<(), SlackApiError> {
+    let body = ChatDeleteRequest { channel, ts };
+    let _: serde_json::Value = post_slack_api(slack_api_addr, token, "chat.delete", &body).await?;
+    Ok(())
+}
+
+pub async fn get_permalink(
+    slack_api_addr: &str,
+    token: &str,
+    channel: &str,
+    ts: &str,
+) -> Result<String, SlackApiError> {
+    let query = [("channel", channel), ("message_ts", ts)];
+    let res: SlackPermalink =
+        get_slack_api(slack_api_addr, token, "chat.getPermalink", &query).await?;
+    Ok(res.permalink)
+}
+
+// needs the users:read.email scope
+pub async fn lookup_user_by_email(
+    slack_api_addr: &str,
+    token: &str,
+    email: &str,
+) -> Result<SlackUser, SlackApiError> {
+    let query = [("email", email)];
+    let res: SlackUserResponse =
+        get_slack_api(slack_api_addr, token, "users.lookupByEmail", &query).await?;
+    Ok(res.user)
+}
+
+// with the locale the user set in Slack
+pub async fn get_user_info(
+    slack_api_addr: &str,
+    token: &str,
+    user: &str,
+) -> Result<SlackUser, SlackApiError> {
+    let query = [("user", user), ("include_locale", "true")];
+    let res: SlackUserResponse = get_slack_api(slack_api_addr, token, "users.info", &query).await?;
+    Ok(res.user)
+}
+
+// post a message only the user can see
+pub async fn post_ephemeral(
+    slack_api_addr: &str,
+    token: &str,
+    channel: &str,
+    user: &str,
+    text: &str,
+) -> Result<(), SlackApiError> {
+    let body = ChatPostEphemeralRequest {
+        channel,
+        user,
+        text,
+        blocks: &[],
+        thread_ts: None,
+    };
+    let _: serde_json::Value =
+        post_slack_api(slack_api_addr, token, "chat.postEphemeral", &body).await?;
+    Ok(())
+}
+
+// post blocks only the user can see, in the thread when thread_ts is given
+// `text` is the fallback for notifications
+pub async fn post_ephemeral_blocks(
+    slack_api_addr: &str,
+    token: &str,
+    channel: &str,
+    user: &str,
+    thread_ts: Option<&str>,
+    text: &str,
+    message: &SlackMessageBlocks,
+) -> Result<(), SlackApiError> {
+    let body = ChatPostEphemeralRequest {
+        channel,
+        user,
+        text,
+        blocks: &message.blocks,
+        thread_ts,
+    };
+    let _: serde_json::Value =
+        post_slack_api(slack_api_addr, token, "chat.postEphemeral", &body).await?;
+    Ok(())
+}
+
+// open a modal, trigger_id expires 3 seconds after the interaction
+pub async fn open_view(
+    slack_api_addr: &str,
+    token: &str,
+    trigger_id: &str,
+    view: &SlackModalView,
+) -> Result<SlackOpenedView, SlackApiError> {
+    let body = ViewsOpenRequest { trigger_id, view };
+    let res: SlackViewResponse = post_slack_api(slack_api_addr, token, "views.open", &body).await?;
+    Ok(res.view)
+}
+
+// replace a modal which is open, e.g. once what it offers has been fetched
+pub async fn update_view(
+    slack_api_addr: &str,
+    token: &str,
+    opened_view: &SlackOpenedView,
+    view: &SlackModalView,
+) -> Result<SlackOpenedView, SlackApiError> {
+    let body = ViewsUpdateRequest {
+        view_id: &opened_view.id,
+        hash: &opened_view.hash,
+        view,
+    };
+    let res: SlackViewResponse =
+        post_slack_api(slack_api_addr, token, "views.update", &body).await?;
+    Ok(res.view)
+}
+
+// attach previews to links in a message, keyed by URL
+pub async fn unfurl(
+    slack_api_addr: &str,
+    token: &str,
+    channel: &str,
+    ts: &str,
+    unfurls: &HashMap<String, SlackUnfurl>,
+) -> Result<(), SlackApiError> {
+    let body = ChatUnfurlRequest {
+        channel,
+        ts,
+        unfurls,
+    };
+    let _: serde_json::Value = post_slack_api(slack_api_addr, token, "chat.unfurl", &body).await?;
+    Ok(())
+}
+
+// publish a view on the App Home tab of the user
+pub async fn publish_view(
+    slack_api_addr: &str,
+    token: &str,
+    user_id: &str,
+    view: &SlackMessageBlocks,
+) -> Result<(), SlackApiError> {
+    let body = ViewsPublishRequest { user_id, view };
+    let _: serde_json::Value =
+        post_slack_api(slack_api_addr, token, "views.publish", &body).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_slack_api_response_ok() {
+        let body = serde_json::json!({"ok": true, "channel": "C1", "ts": "1.0"});
+        let res: Result<SlackPostedMessage, _> = parse_slack_api_response("chat.update", body);
+        assert_eq!(
+            res.unwrap(),
+            SlackPostedMessage {
+                channel: "C1".to_string(),
+                ts: "1.0".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_slack_api_response_error() {
+        let body = serde_json::json!({"ok": false, "error": "message_not_found"});
+        let res: Result<SlackPostedMessage, _> = parse_slack_api_response("chat.update", body);
+        assert_eq!(
+            res.unwrap_err().message,
+            "Slack API chat.update Error: message_not_found"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post_message() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/chat.postMessage")
+                .header("Authorization", "Bearer token")
+                .json_body(serde_json::json!({
+                    "channel": "C1",
+                    "text": "text",
+                    "blocks": [],
+                }));
+            then.status(200)
+                .json_body(serde_json::json!({"ok": true, "channel": "C1", "ts": "1.0"}));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+
+        let message_blocks = SlackMessageBlocks::default();
+        let res = post_message(&mock_api_addr, "token", "C1", "text", &message_blocks).await;
+        mock.assert();
+        assert_eq!(res.unwrap().ts, "1.0");
+    }
+
+    #[tokio::test]
+    async fn test_get_permalink() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/chat.getPermalink")
+                .query_param("channel", "C1")
+                .query_param("message_ts", "1.0");
+            then.status(200).json_body(serde_json::json!({
+                "ok": true,
+                "channel": "C1",
+                "permalink": "https://example.slack.com/archives/C1/p10"
+            }));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+
+        let permalink = get_permalink(&mock_api_addr, "token", "C1", "1.0").await;
+        mock.assert();
+        assert_eq!(
+            permalink.unwrap(),
+            "https://example.slack.com/archives/C1/p10"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_publish_view() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/views.publish")
+                .header("Authorization", "Bearer token")
+                .json_body(serde_json::json!({
+                    "user_id": "U1",
+                    "view": {"type": "home", "blocks": []},
+                }));
+            then.status(200).json_body(serde_json::json!({"ok": true}));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+
+        let view = SlackMessageBlocks::default();
+        let res = publish_view(&mock_api_addr, "token", "U1", &view).await;
+        mock.assert();
+        assert!(res.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_post_thread_reply() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/chat.postMessage")
+                .json_body_partial(r#"{"channel": "C1", "thread_ts": "1.0"}"#);
+            then.status(200)
+                .json_body(serde_json::json!({"ok": true, "channel": "C1", "ts": "2.0"}));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+
+        let message_blocks = SlackMessageBlocks::default();
+        let res = post_thread_reply(
+            &mock_api_addr,
+            "token",
+            "C1",
+            "1.0",
+            "text",
+            &message_blocks,
+        )
+        .await;
+        mock.assert();
+        assert_eq!(res.unwrap().ts, "2.0");
+    }
+
+    #[tokio::test]
+    async fn test_lookup_user_by_email() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users.lookupByEmail")
+                .query_param("email", "octocat@example.com");
+            then.status(200).json_body(
+                serde_json::json!({"ok": true, "user": {"id": "U1", "name": "octocat"}}),
+            );
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+
+        let user = lookup_user_by_email(&mock_api_addr, "token", "octocat@example.com").await;
+        mock.assert();
+        assert_eq!(user.unwrap().id, "U1");
+    }
+
+    #[tokio::test]
+    async fn test_get_user_info() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users.info")
+                .query_param("user", "U1")
+                .query_param("include_locale", "true");
+            then.status(200).json_body(
+                serde_json::json!({"ok": true, "user": {"id": "U1", "locale": "en-US"}}),
+            );
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+
+        let user = get_user_info(&mock_api_addr, "token", "U1").await.unwrap();
+        mock.assert();
+        assert_eq!(user.id, "U1");
+        assert_eq!(user.locale.as_deref(), Some("en-US"));
+    }
+}
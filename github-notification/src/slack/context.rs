@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::template::DigestTemplate;
+use crate::i18n::LocaleSettings;
+use crate::store::state_file_path;
+
+// everything the Slack app handlers need to call Slack and GitHub
+#[derive(Debug, Clone)]
+pub struct SlackAppContext {
+    pub slack_api_addr: String,
+    pub slack_token: String,
+    pub github_api_addr: String,
+    pub github_token: String,
+    pub state_dir: String,
+    pub locales: LocaleSettings,
+    pub template: DigestTemplate,
+    // GitHub login -> Slack user id, see env::get_slack_user_map
+    pub static_users: HashMap<String, String>,
+    // Slack user id -> GitHub token, see env::get_slack_user_github_tokens
+    pub user_tokens: HashMap<String, String>,
+}
+
+impl SlackAppContext {
+    pub fn new(slack_token: String, github_token: String, state_dir: String) -> Self {
+        Self {
+            slack_api_addr: "https://slack.com/api".to_string(),
+            slack_token,
+            github_api_addr: "https://api.github.com".to_string(),
+            github_token,
+            state_dir,
+            locales: LocaleSettings::default(),
+            template: DigestTemplate::default(),
+            static_users: HashMap::new(),
+            user_tokens: HashMap::new(),
+        }
+    }
+
+    pub fn with_locales(mut self, locales: LocaleSettings) -> Self {
+        self.locales = locales;
+        self
+    }
+
+    pub fn with_template(mut self, template: DigestTemplate) -> Self {
+        self.template = template;
+        self
+    }
+
+    pub fn with_static_users(mut self, static_users: HashMap<String, String>) -> Self {
+        self.static_users = static_users;
+        self
+    }
+
+    pub fn with_user_tokens(mut self, user_tokens: HashMap<String, String>) -> Self {
+        self.user_tokens = user_tokens;
+        self
+    }
+
+    pub fn state_file_path(&self, file_name: &str) -> PathBuf {
+        state_file_path(&self.state_dir, file_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slack_app_context_new() {
+        let context = SlackAppContext::new(
+            "slack_token".to_string(),
+            "github_token".to_string(),
+            "state".to_string(),
+        );
+        assert_eq!(context.slack_api_addr, "https://slack.com/api");
+        assert_eq!(context.github_api_addr, "https://api.github.com");
+        assert_eq!(
+            context.state_file_path("test.json"),
+            state_file_path("state", "test.json")
+        );
+        assert_eq!(context.locales, LocaleSettings::default());
+        assert!(context.static_users.is_empty());
+        assert!(context.user_tokens.is_empty());
+    }
+}
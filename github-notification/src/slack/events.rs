@@ -0,0 +1,641 @@
+use super::api::{post_ephemeral_blocks, unfurl, SlackUnfurl};
+use super::context::SlackAppContext;
+use super::home::on_app_home_opened;
+use super::locale::resolve_user_locale;
+use super::message::SlackMessageBlocks;
+use super::mrkdwn;
+use super::personal::{
+    create_personal_digest, create_personal_digest_response, onboarding_text, resolve_digest_user,
+};
+use super::user_map::SLACK_USER_MAP_FILE_NAME;
+use crate::github::{get_issue, is_public_repository, parse_github_issue_url};
+use crate::i18n::text;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+pub const SLACK_RETRY_NUM_HEADER: &str = "X-Slack-Retry-Num";
+
+// how long an event_id is remembered for de-duplication
+const EVENT_ID_TTL_SECS: i64 = 60 * 60;
+
+// the body of a request to the Events API endpoint
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SlackEventsPayload {
+    UrlVerification { challenge: String },
+    EventCallback(SlackEventCallback),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SlackEventCallback {
+    pub team_id: String,
+    pub event_id: String,
+    pub event_time: i64,
+    pub event: SlackEvent,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SlackEvent {
+    AppHomeOpened(AppHomeOpenedEvent),
+    AppMention(AppMentionEvent),
+    LinkShared(LinkSharedEvent),
+    ReactionAdded(ReactionAddedEvent),
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SlackEventKind {
+    AppHomeOpened,
+    AppMention,
+    LinkShared,
+    ReactionAdded,
+    Unknown,
+}
+
+impl SlackEvent {
+    pub fn kind(&self) -> SlackEventKind {
+        match self {
+            SlackEvent::AppHomeOpened(_) => SlackEventKind::AppHomeOpened,
+            SlackEvent::AppMention(_) => SlackEventKind::AppMention,
+            SlackEvent::LinkShared(_) => SlackEventKind::LinkShared,
+            SlackEvent::ReactionAdded(_) => SlackEventKind::ReactionAdded,
+            SlackEvent::Unknown => SlackEventKind::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AppHomeOpenedEvent {
+    pub user: String,
+    pub channel: String,
+    pub tab: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AppMentionEvent {
+    pub user: String,
+    pub text: String,
+    pub channel: String,
+    pub ts: String,
+    pub thread_ts: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LinkSharedEvent {
+    pub channel: String,
+    pub user: String,
+    pub message_ts: String,
+    pub links: Vec<SharedLink>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SharedLink {
+    pub url: String,
+    pub domain: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReactionAddedEvent {
+    pub user: String,
+    pub reaction: String,
+    pub item: ReactionItem,
+    pub item_user: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReactionItem {
+    #[serde(rename = "type")]
+    pub item_type: String,
+    pub channel: Option<String>,
+    pub ts: Option<String>,
+}
+
+// Slack retries an event when the response is slow or fails,
+// so the event_id of every delivered event is remembered for a while
+#[derive(Debug, Default)]
+pub struct SlackEventDeduplicator {
+    seen: HashMap<String, i64>,
+}
+
+impl SlackEventDeduplicator {
+    // returns true if the event was already received
+    pub fn is_duplicate(&mut self, event_id: &str, retry_num: Option<u32>, now: i64) -> bool {
+        self.seen
+            .retain(|_, seen_at| now - *seen_at < EVENT_ID_TTL_SECS);
+        if self.seen.contains_key(event_id) {
+            println!("Duplicate Slack Event: {event_id} (retry: {retry_num:?})");
+            return true;
+        }
+        self.seen.insert(event_id.to_string(), now);
+        false
+    }
+}
+
+pub type SlackEventHandler =
+    Arc<dyn Fn(SlackEventCallback) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+#[derive(Default, Clone)]
+pub struct SlackEventDispatcher {
+    handlers: HashMap<SlackEventKind, Vec<SlackEventHandler>>,
+}
+
+impl SlackEventDispatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn on<F, Fut>(&mut self, kind: SlackEventKind, handler: F)
+    where
+        F: Fn(SlackEventCallback) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handler: SlackEventHandler = Arc::new(move |callback| Box::pin(handler(callback)));
+        self.handlers.entry(kind).or_default().push(handler);
+    }
+
+    // run every handler registered for the event, returns the number of handlers
+    pub async fn dispatch(&self, callback: SlackEventCallback) -> usize {
+        let handlers = match self.handlers.get(&callback.event.kind()) {
+            Some(handlers) => handlers,
+            None => {
+                println!("No Slack Event Handler: {:?}", callback.event.kind());
+                return 0;
+            }
+        };
+        for handler in handlers {
+            handler(callback.clone()).await;
+        }
+        handlers.len()
+    }
+}
+
+// a dispatcher with the handlers provided by this library
+pub fn default_event_dispatcher(context: SlackAppContext) -> SlackEventDispatcher {
+    let mut dispatcher = SlackEventDispatcher::new();
+
+    let ctx = context.clone();
+    dispatcher.on(SlackEventKind::AppHomeOpened, move |callback| {
+        let ctx = ctx.clone();
+        async move {
+            if let SlackEvent::AppHomeOpened(event) = callback.event {
+                handle_app_home_opened(&ctx, &event).await;
+            }
+        }
+    });
+
+    let ctx = context.clone();
+    dispatcher.on(SlackEventKind::AppMention, move |callback| {
+        let ctx = ctx.clone();
+        async move {
+            if let SlackEvent::AppMention(event) = callback.event {
+                handle_app_mention(&ctx, &event).await;
+            }
+        }
+    });
+
+    let ctx = context;
+    dispatcher.on(SlackEventKind::LinkShared, move |callback| {
+        let ctx = ctx.clone();
+        async move {
+            if let SlackEvent::LinkShared(event) = callback.event {
+                handle_link_shared(&ctx, &event).await;
+            }
+        }
+    });
+
+    dispatcher.on(SlackEventKind::ReactionAdded, |callback| async move {
+        if let SlackEvent::ReactionAdded(event) = callback.event {
+            println!(
+                "Reaction Added: {} by {} on {:?}",
+                event.reaction, event.user, event.item.ts
+            );
+        }
+    });
+
+    dispatcher
+}
+
+async fn handle_app_home_opened(context: &SlackAppContext, event: &AppHomeOpenedEvent) {
+    // the messages tab is not managed by this app
+    if event.tab != "home" {
+        return;
+    }
+    let _ = on_app_home_opened(context, &event.user).await;
+}
+
+// reply to a mention with the personal digest of the user who mentions the app
+// the reply is seen only by the user, so it is in the locale of the user
+async fn handle_app_mention(context: &SlackAppContext, event: &AppMentionEvent) {
+    let locale = resolve_user_locale(
+        &context.slack_api_addr,
+        Some(&context.slack_token),
+        &context.locales,
+        &event.user,
+        Some(&event.channel),
+    )
+    .await;
+    let digest_user = resolve_digest_user(
+        &event.user,
+        &context.user_tokens,
+        &context.static_users,
+        &context.state_file_path(SLACK_USER_MAP_FILE_NAME),
+    );
+    let mut message = SlackMessageBlocks::default();
+    match digest_user {
+        Some(digest_user) => {
+            let (digest, format) = create_personal_digest(
+                &context.github_api_addr,
+                &context.github_token,
+                &digest_user,
+                &event.user,
+                &context.state_dir,
+                locale,
+                context.template.clone(),
+            )
+            .await;
+            message.blocks = create_personal_digest_response(&digest, &format).blocks;
+        }
+        None => message.add_text_block(onboarding_text(locale)),
+    }
+    let thread_ts = event.thread_ts.as_deref().unwrap_or(&event.ts);
+    let _ = post_ephemeral_blocks(
+        &context.slack_api_addr,
+        &context.slack_token,
+        &event.channel,
+        &event.user,
+        Some(thread_ts),
+        &text(locale, "digest.title"),
+        &message,
+    )
+    .await;
+}
+
+// show title and state of shared GitHub issue links
+// the unfurl is seen by the channel, so an issue is fetched by the token of the one who posted it,
+// or by the shared token only when its repository is public
+async fn handle_link_shared(context: &SlackAppContext, event: &LinkSharedEvent) {
+    let user_token = context.user_tokens.get(&event.user);
+    let mut unfurls = HashMap::new();
+    for link in &event.links {
+        let issue_ref = match parse_github_issue_url(&link.url) {
+            Some(issue_ref) => issue_ref,
+            None => continue,
+        };
+        let token = match user_token {
+            Some(token) => token,
+            None => match is_public_repository(
+                &context.github_api_addr,
+                &context.github_token,
+                &issue_ref.owner,
+                &issue_ref.repo,
+            )
+            .await
+            {
+                Ok(true) => &context.github_token,
+                _ => continue,
+            },
+        };
+        let issue = match get_issue(&context.github_api_addr, token, &issue_ref).await {
+            Ok(issue) => issue,
+            Err(_) => continue,
+        };
+        let mut message_blocks = SlackMessageBlocks::default();
+        message_blocks.add_text_block(format!(
+            "{} #{} ({})",
+            mrkdwn::link(&issue.html_url, &issue.title),
+            issue.number,
+            issue.state
+        ));
+        unfurls.insert(
+            link.url.clone(),
+            SlackUnfurl {
+                blocks: message_blocks.blocks,
+            },
+        );
+    }
+    if unfurls.is_empty() {
+        return;
+    }
+    let _ = unfurl(
+        &context.slack_api_addr,
+        &context.slack_token,
+        &event.channel,
+        &event.message_ts,
+        &unfurls,
+    )
+    .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_parse_url_verification() {
+        let body = r#"{"token": "t", "challenge": "challenge_value", "type": "url_verification"}"#;
+        let payload: SlackEventsPayload = serde_json::from_str(body).unwrap();
+        match payload {
+            SlackEventsPayload::UrlVerification { challenge } => {
+                assert_eq!(challenge, "challenge_value")
+            }
+            _ => panic!("unexpected payload"),
+        }
+    }
+
+    #[test]
+    fn test_parse_event_callback() {
+        let body = r#"{
+            "type": "event_callback",
+            "team_id": "T1",
+            "event_id": "Ev1",
+            "event_time": 1234567890,
+            "event": {"type": "app_home_opened", "user": "U1", "channel": "D1", "tab": "home"}
+        }"#;
+        let payload: SlackEventsPayload = serde_json::from_str(body).unwrap();
+        match payload {
+            SlackEventsPayload::EventCallback(callback) => {
+                assert_eq!(callback.event_id, "Ev1");
+                assert_eq!(callback.event.kind(), SlackEventKind::AppHomeOpened);
+            }
+            _ => panic!("unexpected payload"),
+        }
+    }
+
+    #[test]
+    fn test_parse_unknown_event() {
+        let body = r#"{
+            "type": "event_callback",
+            "team_id": "T1",
+            "event_id": "Ev1",
+            "event_time": 1234567890,
+            "event": {"type": "member_joined_channel", "user": "U1"}
+        }"#;
+        let payload: SlackEventsPayload = serde_json::from_str(body).unwrap();
+        match payload {
+            SlackEventsPayload::EventCallback(callback) => {
+                assert_eq!(callback.event.kind(), SlackEventKind::Unknown);
+            }
+            _ => panic!("unexpected payload"),
+        }
+    }
+
+    #[test]
+    fn test_slack_event_deduplicator() {
+        let mut deduplicator = SlackEventDeduplicator::default();
+        assert!(!deduplicator.is_duplicate("Ev1", None, 0));
+        assert!(deduplicator.is_duplicate("Ev1", Some(1), 10));
+        assert!(!deduplicator.is_duplicate("Ev2", None, 10));
+        // forgotten after the TTL
+        assert!(!deduplicator.is_duplicate("Ev1", Some(2), EVENT_ID_TTL_SECS + 10));
+    }
+
+    #[tokio::test]
+    async fn test_slack_event_dispatcher() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let mut dispatcher = SlackEventDispatcher::new();
+        let counter = count.clone();
+        dispatcher.on(SlackEventKind::ReactionAdded, move |_| {
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        let callback = SlackEventCallback {
+            team_id: "T1".to_string(),
+            event_id: "Ev1".to_string(),
+            event_time: 0,
+            event: SlackEvent::ReactionAdded(ReactionAddedEvent {
+                user: "U1".to_string(),
+                reaction: "eyes".to_string(),
+                item: ReactionItem {
+                    item_type: "message".to_string(),
+                    channel: Some("C1".to_string()),
+                    ts: Some("1.0".to_string()),
+                },
+                item_user: None,
+            }),
+        };
+        assert_eq!(dispatcher.dispatch(callback.clone()).await, 1);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        let unknown = SlackEventCallback {
+            event: SlackEvent::Unknown,
+            ..callback
+        };
+        assert_eq!(dispatcher.dispatch(unknown).await, 0);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_handle_app_mention() {
+        use crate::fixtures::issue;
+        use crate::models::IssueRef;
+        use crate::snooze::{SnoozeScope, SnoozedIssues, SNOOZED_ISSUES_FILE_NAME};
+        use crate::store::save_json;
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let issues_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/issues")
+                .header("Authorization", "Bearer user_token");
+            then.status(200).json_body(serde_json::json!([
+                issue(1, "first", None),
+                issue(2, "second", None),
+            ]));
+        });
+        let ephemeral_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/chat.postEphemeral")
+                .json_body_partial(r#"{"channel": "C1", "user": "U1", "thread_ts": "1.0"}"#)
+                .body_contains("https://github.com/owner/name/issues/1");
+            then.status(200).json_body(serde_json::json!({"ok": true}));
+        });
+        let snoozed_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/chat.postEphemeral")
+                .body_contains("https://github.com/owner/name/issues/2");
+            then.status(200).json_body(serde_json::json!({"ok": true}));
+        });
+        let thread_mock = server.mock(|when, then| {
+            when.method(POST).path("/chat.postMessage");
+            then.status(200).json_body(serde_json::json!({"ok": true}));
+        });
+        let mock_addr = format!("http://{}", server.address());
+        let state_dir = std::env::temp_dir().join("github_notification_app_mention_test");
+        let mut snoozed_issues = SnoozedIssues::default();
+        snoozed_issues.snooze(
+            &SnoozeScope::User("U1".to_string()),
+            &IssueRef::parse("owner/name#2").unwrap(),
+            i64::MAX,
+        );
+        save_json(&state_dir.join(SNOOZED_ISSUES_FILE_NAME), &snoozed_issues).unwrap();
+        let context = SlackAppContext {
+            slack_api_addr: mock_addr.clone(),
+            slack_token: "slack_token".to_string(),
+            github_api_addr: mock_addr,
+            github_token: "github_token".to_string(),
+            state_dir: state_dir.display().to_string(),
+            locales: crate::i18n::LocaleSettings::default(),
+            template: crate::slack::template::DigestTemplate::default(),
+            static_users: HashMap::new(),
+            user_tokens: HashMap::from([("U1".to_string(), "user_token".to_string())]),
+        };
+        let event = AppMentionEvent {
+            user: "U1".to_string(),
+            text: "<@B1>".to_string(),
+            channel: "C1".to_string(),
+            ts: "1.0".to_string(),
+            thread_ts: None,
+        };
+
+        // only the user who mentions the app sees their digest, without the snoozed issue
+        handle_app_mention(&context, &event).await;
+        issues_mock.assert();
+        ephemeral_mock.assert();
+        snoozed_mock.assert_hits(0);
+        thread_mock.assert_hits(0);
+    }
+
+    #[tokio::test]
+    async fn test_handle_link_shared() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let repository_mock = server.mock(|when, then| {
+            when.method(GET).path("/repos/owner/repo");
+            then.status(200)
+                .json_body(serde_json::json!({"full_name": "owner/repo", "private": false}));
+        });
+        let issue_mock = server.mock(|when, then| {
+            when.method(GET).path("/repos/owner/repo/issues/1");
+            then.status(200).json_body(serde_json::json!({
+                "number": 1,
+                "title": "title",
+                "html_url": "https://github.com/owner/repo/issues/1",
+                "state": "open",
+                "labels": null,
+            }));
+        });
+        let unfurl_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/chat.unfurl")
+                .json_body_partial(r#"{"channel": "C1", "ts": "1.0"}"#)
+                .body_contains("<https://github.com/owner/repo/issues/1|title> #1 (open)");
+            then.status(200).json_body(serde_json::json!({"ok": true}));
+        });
+        let mock_addr = format!("http://{}", server.address());
+        let context = SlackAppContext {
+            slack_api_addr: mock_addr.clone(),
+            slack_token: "slack_token".to_string(),
+            github_api_addr: mock_addr,
+            github_token: "github_token".to_string(),
+            state_dir: std::env::temp_dir().display().to_string(),
+            locales: crate::i18n::LocaleSettings::default(),
+            template: crate::slack::template::DigestTemplate::default(),
+            static_users: HashMap::new(),
+            user_tokens: HashMap::new(),
+        };
+        let event = LinkSharedEvent {
+            channel: "C1".to_string(),
+            user: "U1".to_string(),
+            message_ts: "1.0".to_string(),
+            links: vec![
+                SharedLink {
+                    url: "https://github.com/owner/repo/issues/1".to_string(),
+                    domain: "github.com".to_string(),
+                },
+                SharedLink {
+                    url: "https://github.com/owner/repo".to_string(),
+                    domain: "github.com".to_string(),
+                },
+            ],
+        };
+
+        handle_link_shared(&context, &event).await;
+        repository_mock.assert();
+        issue_mock.assert();
+        unfurl_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_handle_link_shared_private() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let repository_mock = server.mock(|when, then| {
+            when.method(GET).path("/repos/owner/secret");
+            then.status(200)
+                .json_body(serde_json::json!({"full_name": "owner/secret", "private": true}));
+        });
+        let shared_issue_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/repos/owner/secret/issues/1")
+                .header("Authorization", "Bearer github_token");
+            then.status(200).json_body(serde_json::json!({
+                "number": 1,
+                "title": "secret",
+                "html_url": "https://github.com/owner/secret/issues/1",
+                "state": "open",
+                "labels": null,
+            }));
+        });
+        let user_issue_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/repos/owner/secret/issues/1")
+                .header("Authorization", "Bearer user_token");
+            then.status(200).json_body(serde_json::json!({
+                "number": 1,
+                "title": "secret",
+                "html_url": "https://github.com/owner/secret/issues/1",
+                "state": "open",
+                "labels": null,
+            }));
+        });
+        let unfurl_mock = server.mock(|when, then| {
+            when.method(POST).path("/chat.unfurl");
+            then.status(200).json_body(serde_json::json!({"ok": true}));
+        });
+        let mock_addr = format!("http://{}", server.address());
+        let mut context = SlackAppContext {
+            slack_api_addr: mock_addr.clone(),
+            slack_token: "slack_token".to_string(),
+            github_api_addr: mock_addr,
+            github_token: "github_token".to_string(),
+            state_dir: std::env::temp_dir().display().to_string(),
+            locales: crate::i18n::LocaleSettings::default(),
+            template: crate::slack::template::DigestTemplate::default(),
+            static_users: HashMap::new(),
+            user_tokens: HashMap::new(),
+        };
+        let event = LinkSharedEvent {
+            channel: "C1".to_string(),
+            user: "U1".to_string(),
+            message_ts: "1.0".to_string(),
+            links: vec![SharedLink {
+                url: "https://github.com/owner/secret/issues/1".to_string(),
+                domain: "github.com".to_string(),
+            }],
+        };
+
+        // the shared token doesn't show an issue of a private repository
+        handle_link_shared(&context, &event).await;
+        repository_mock.assert();
+        shared_issue_mock.assert_hits(0);
+        unfurl_mock.assert_hits(0);
+
+        // the one who posted it can see it by their own token
+        context.user_tokens = HashMap::from([("U1".to_string(), "user_token".to_string())]);
+        handle_link_shared(&context, &event).await;
+        repository_mock.assert_hits(1);
+        user_issue_mock.assert();
+        unfurl_mock.assert();
+    }
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+pub const SLACK_MESSAGE_HISTORY_FILE_NAME: &str = "slack_message_history.json";
+const MAX_RECORDS: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SlackMessageRecord {
+    pub channel: String,
+    pub ts: String,
+    // unix time when the message was first posted
+    pub posted_at: i64,
+    // continuation messages posted in the thread of the message
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replies: Vec<String>,
+}
+
+// messages sent by the bot, oldest first
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SlackMessageHistory {
+    pub records: Vec<SlackMessageRecord>,
+}
+
+impl SlackMessageHistory {
+    pub fn latest(&self, channel: &str) -> Option<&SlackMessageRecord> {
+        self.records.iter().rev().find(|r| r.channel == channel)
+    }
+
+    // the latest message in the channel, if it was posted within `max_age_secs`
+    pub fn latest_recent(
+        &self,
+        channel: &str,
+        now: i64,
+        max_age_secs: i64,
+    ) -> Option<&SlackMessageRecord> {
+        self.latest(channel)
+            .filter(|r| now - r.posted_at < max_age_secs)
+    }
+
+    pub fn find_mut(&mut self, channel: &str, ts: &str) -> Option<&mut SlackMessageRecord> {
+        self.records
+            .iter_mut()
+            .find(|r| r.channel == channel && r.ts == ts)
+    }
+
+    pub fn push(&mut self, record: SlackMessageRecord) {
+        self.records.push(record);
+        if self.records.len() > MAX_RECORDS {
+            let overflow = self.records.len() - MAX_RECORDS;
+            self.records.drain(..overflow);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(channel: &str, ts: &str, posted_at: i64) -> SlackMessageRecord {
+        SlackMessageRecord {
+            channel: channel.to_string(),
+            ts: ts.to_string(),
+            posted_at,
+            replies: vec![],
+        }
+    }
+
+    #[test]
+    fn test_latest() {
+        let mut history = SlackMessageHistory::default();
+        history.push(record("C1", "1.0", 10));
+        history.push(record("C2", "2.0", 20));
+        history.push(record("C1", "3.0", 30));
+        assert_eq!(history.latest("C1").unwrap().ts, "3.0");
+        assert_eq!(history.latest("C2").unwrap().ts, "2.0");
+        assert!(history.latest("C3").is_none());
+    }
+
+    #[test]
+    fn test_latest_recent() {
+        let mut history = SlackMessageHistory::default();
+        history.push(record("C1", "1.0", 100));
+        assert!(history.latest_recent("C1", 150, 100).is_some());
+        assert!(history.latest_recent("C1", 200, 100).is_none());
+    }
+
+    #[test]
+    fn test_find_mut() {
+        let mut history = SlackMessageHistory::default();
+        history.push(record("C1", "1.0", 10));
+        history.find_mut("C1", "1.0").unwrap().replies = vec!["1.1".to_string()];
+        assert_eq!(history.latest("C1").unwrap().replies, vec!["1.1"]);
+        assert!(history.find_mut("C2", "1.0").is_none());
+    }
+
+    #[test]
+    fn test_record_without_replies() {
+        // records saved before replies were recorded
+        let record: SlackMessageRecord =
+            serde_json::from_str(r#"{"channel": "C1", "ts": "1.0", "posted_at": 10}"#).unwrap();
+        assert!(record.replies.is_empty());
+    }
+
+    #[test]
+    fn test_push_keeps_max_records() {
+        let mut history = SlackMessageHistory::default();
+        for i in 0..(MAX_RECORDS + 5) {
+            history.push(record("C1", &i.to_string(), i as i64));
+        }
+        assert_eq!(history.records.len(), MAX_RECORDS);
+        assert_eq!(history.records[0].ts, "5");
+    }
+}
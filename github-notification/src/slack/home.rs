@@ -0,0 +1,359 @@
+use super::api::publish_view;
+use super::context::SlackAppContext;
+use super::layout::{truncate_blocks, MAX_BLOCKS_PER_VIEW};
+use super::locale::resolve_user_locale;
+use super::message::SlackMessageBlocks;
+use super::payload::add_sorted_issue_blocks;
+use super::personal::{get_digest_user_issues, onboarding_text, resolve_digest_user};
+use super::template::DigestTemplate;
+use super::user_map::SLACK_USER_MAP_FILE_NAME;
+use crate::errors::{GetIssueError, SlackApiError};
+use crate::github::sort_issues;
+use crate::i18n::{text, Locale};
+use crate::models::SortedIssues;
+use crate::snooze::{SnoozeScope, SnoozedIssues, SNOOZED_ISSUES_FILE_NAME};
+use crate::store::{load_json, save_json};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const SLACK_HOME_USERS_FILE_NAME: &str = "slack_home_users.json";
+
+// users who have opened the App Home tab, refreshed on schedule
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SlackHomeUsers {
+    pub user_ids: Vec<String>,
+}
+
+impl SlackHomeUsers {
+    // returns true if the user is newly added
+    pub fn add(&mut self, user_id: &str) -> bool {
+        if self.user_ids.iter().any(|id| id == user_id) {
+            return false;
+        }
+        self.user_ids.push(user_id.to_string());
+        true
+    }
+}
+
+// the App Home view uses the same grouping and rendering as the channel digest
+// a long list is cut to the blocks a view can have, with a "+N more" footer
+pub fn create_home_view(
+    issues: Result<SortedIssues, GetIssueError>,
+    locale: Locale,
+    template: &DigestTemplate,
+    now: DateTime<Utc>,
+) -> SlackMessageBlocks {
+    let mut view = SlackMessageBlocks::default();
+    view.add_header_block(text(locale, "digest.title"));
+
+    match issues {
+        Ok(issues) => add_sorted_issue_blocks(&mut view, &issues, locale, template, now),
+        Err(e) => view.add_text_block(e.message),
+    }
+    truncate_blocks(&mut view.blocks, MAX_BLOCKS_PER_VIEW, locale);
+
+    view
+}
+
+// for the users who are not linked yet, the tab tells how to link
+pub fn create_onboarding_home_view(locale: Locale) -> SlackMessageBlocks {
+    let mut view = SlackMessageBlocks::default();
+    view.add_header_block(text(locale, "digest.title"));
+    view.add_text_block(onboarding_text(locale));
+    view
+}
+
+// the tab shows the issues of the user who views it, as the personal digest does
+// without the snoozed issues
+pub async fn refresh_home(context: &SlackAppContext, user_id: &str) -> Result<(), SlackApiError> {
+    let locale = resolve_user_locale(
+        &context.slack_api_addr,
+        Some(&context.slack_token),
+        &context.locales,
+        user_id,
+        None,
+    )
+    .await;
+    let digest_user = resolve_digest_user(
+        user_id,
+        &context.user_tokens,
+        &context.static_users,
+        &context.state_file_path(SLACK_USER_MAP_FILE_NAME),
+    );
+    let view = match digest_user {
+        Some(digest_user) => {
+            let issues = get_digest_user_issues(
+                &context.github_api_addr,
+                &context.github_token,
+                &digest_user,
+            )
+            .await;
+            let now = Utc::now();
+            let snoozed_issues: SnoozedIssues =
+                load_json(&context.state_file_path(SNOOZED_ISSUES_FILE_NAME));
+            let scope = SnoozeScope::User(user_id.to_string());
+            let issues = snoozed_issues.filter_issues(&scope, issues, now.timestamp());
+            create_home_view(sort_issues(issues), locale, &context.template, now)
+        }
+        None => create_onboarding_home_view(locale),
+    };
+    publish_view(
+        &context.slack_api_addr,
+        &context.slack_token,
+        user_id,
+        &view,
+    )
+    .await?;
+    println!("Publish Home View OK: {user_id}");
+    Ok(())
+}
+
+// called on the app_home_opened event
+// the user is remembered so that the scheduled refresh covers the tab
+pub async fn on_app_home_opened(
+    context: &SlackAppContext,
+    user_id: &str,
+) -> Result<(), SlackApiError> {
+    let home_users_path = context.state_file_path(SLACK_HOME_USERS_FILE_NAME);
+    let mut home_users: SlackHomeUsers = load_json(&home_users_path);
+    if home_users.add(user_id) {
+        if let Err(err) = save_json(&home_users_path, &home_users) {
+            println!("{err}");
+        }
+    }
+    refresh_home(context, user_id).await
+}
+
+// refresh the App Home tab of every known user
+// returns the users whose tab could not be published
+pub async fn refresh_all_homes(context: &SlackAppContext) -> Vec<String> {
+    let home_users: SlackHomeUsers =
+        load_json(&context.state_file_path(SLACK_HOME_USERS_FILE_NAME));
+    let mut failed_user_ids = Vec::new();
+    for user_id in home_users.user_ids {
+        if refresh_home(context, &user_id).await.is_err() {
+            failed_user_ids.push(user_id);
+        }
+    }
+    failed_user_ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::LocaleSettings;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_slack_home_users_add() {
+        let mut home_users = SlackHomeUsers::default();
+        assert!(home_users.add("U1"));
+        assert!(!home_users.add("U1"));
+        assert!(home_users.add("U2"));
+        assert_eq!(home_users.user_ids, vec!["U1", "U2"]);
+    }
+
+    #[test]
+    fn test_create_home_view() {
+        use crate::fixtures::{issue, now};
+
+        let issues = SortedIssues {
+            priority_low_issues: vec![issue(1, "title", None)],
+            ..SortedIssues::default()
+        };
+        let view = create_home_view(Ok(issues), Locale::Ja, &DigestTemplate::default(), now());
+        assert_eq!(view.blocks_type, "home");
+        assert_eq!(view.blocks.len(), 2);
+        assert_eq!(view.blocks[0].block_type(), "header");
+        assert_eq!(
+            view.blocks[1].text().unwrap().text,
+            "🟡 *優先度: 低*\n- <https://github.com/owner/name/issues/1|title>(<https://github.com/owner/name|name>) `bug`\n"
+        );
+    }
+
+    #[test]
+    fn test_create_home_view_error() {
+        let view = create_home_view(
+            Err(GetIssueError {
+                message: "error".to_string(),
+            }),
+            Locale::Ja,
+            &DigestTemplate::default(),
+            Utc::now(),
+        );
+        assert_eq!(view.blocks.len(), 2);
+        assert_eq!(view.blocks[1].text().unwrap().text, "error");
+    }
+
+    #[test]
+    fn test_create_home_view_truncated() {
+        use crate::fixtures::{issue, now};
+
+        // a title this long takes a section of its own
+        let title = "a".repeat(2900);
+        let issues = SortedIssues {
+            priority_low_issues: (1..=150)
+                .map(|number| issue(number, &title, None))
+                .collect(),
+            ..SortedIssues::default()
+        };
+        let view = create_home_view(Ok(issues), Locale::En, &DigestTemplate::default(), now());
+        assert_eq!(view.blocks.len(), MAX_BLOCKS_PER_VIEW);
+        assert_eq!(view.blocks[99].block_type(), "context");
+        assert!(serde_json::to_string(&view.blocks[99])
+            .unwrap()
+            .contains("and 52 more issues"));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_home_without_snoozed_issues() {
+        use crate::fixtures::issue;
+        use crate::models::IssueRef;
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let issues_mock = server.mock(|when, then| {
+            when.method(GET).path("/issues");
+            then.status(200).json_body(serde_json::json!([
+                issue(1, "first", None),
+                issue(2, "second", None),
+            ]));
+        });
+        let publish_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/views.publish")
+                .body_contains("https://github.com/owner/name/issues/1");
+            then.status(200).json_body(serde_json::json!({"ok": true}));
+        });
+        let snoozed_publish_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/views.publish")
+                .body_contains("https://github.com/owner/name/issues/2");
+            then.status(200).json_body(serde_json::json!({"ok": true}));
+        });
+        let mock_addr = format!("http://{}", server.address());
+        let state_dir = std::env::temp_dir().join("github_notification_home_snooze_test");
+        let mut snoozed_issues = SnoozedIssues::default();
+        snoozed_issues.snooze(
+            &SnoozeScope::User("U1".to_string()),
+            &IssueRef::parse("owner/name#2").unwrap(),
+            i64::MAX,
+        );
+        save_json(&state_dir.join(SNOOZED_ISSUES_FILE_NAME), &snoozed_issues).unwrap();
+        let context = SlackAppContext {
+            slack_api_addr: mock_addr.clone(),
+            slack_token: "token".to_string(),
+            github_api_addr: mock_addr,
+            github_token: "shared_token".to_string(),
+            state_dir: state_dir.display().to_string(),
+            locales: LocaleSettings {
+                users: HashMap::from([("U1".to_string(), Locale::En)]),
+                ..LocaleSettings::default()
+            },
+            template: DigestTemplate::default(),
+            static_users: HashMap::new(),
+            user_tokens: HashMap::from([("U1".to_string(), "user_token".to_string())]),
+        };
+
+        refresh_home(&context, "U1").await.unwrap();
+        issues_mock.assert();
+        publish_mock.assert();
+        snoozed_publish_mock.assert_hits(0);
+    }
+
+    #[test]
+    fn test_create_onboarding_home_view() {
+        let view = create_onboarding_home_view(Locale::En);
+        assert_eq!(view.blocks.len(), 2);
+        assert!(view.blocks[1]
+            .text()
+            .unwrap()
+            .text
+            .contains("/mygithub link"));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_all_homes() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        // U1 has a token of their own
+        let issues_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/issues")
+                .header("Authorization", "Bearer user_token");
+            then.status(200).json_body(serde_json::json!([]));
+        });
+        // U2 is in the static map, the shared token searches the public issues
+        let search_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search/issues")
+                .query_param("q", "is:open is:issue is:public assignee:octocat")
+                .header("Authorization", "Bearer shared_token");
+            then.status(200)
+                .json_body(serde_json::json!({"total_count": 0, "items": []}));
+        });
+        let user_info_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users.info")
+                .query_param("user", "U1");
+            then.status(200).json_body(
+                serde_json::json!({"ok": true, "user": {"id": "U1", "locale": "en-US"}}),
+            );
+        });
+        let english_publish_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/views.publish")
+                .json_body_partial(r#"{"user_id": "U1"}"#)
+                .body_contains("Tasks");
+            then.status(200).json_body(serde_json::json!({"ok": true}));
+        });
+        let publish_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/views.publish")
+                .json_body_partial(r#"{"user_id": "U2"}"#)
+                .body_contains("タスク一覧");
+            then.status(200).json_body(serde_json::json!({"ok": true}));
+        });
+        // U3 is not linked
+        let onboarding_publish_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/views.publish")
+                .json_body_partial(r#"{"user_id": "U3"}"#)
+                .body_contains("/mygithub link");
+            then.status(200).json_body(serde_json::json!({"ok": true}));
+        });
+        let mock_addr = format!("http://{}", server.address());
+        let state_dir = std::env::temp_dir().join("github_notification_home_test");
+        let context = SlackAppContext {
+            slack_api_addr: mock_addr.clone(),
+            slack_token: "token".to_string(),
+            github_api_addr: mock_addr,
+            github_token: "shared_token".to_string(),
+            state_dir: state_dir.display().to_string(),
+            locales: LocaleSettings {
+                users: HashMap::from([
+                    ("U2".to_string(), Locale::Ja),
+                    ("U3".to_string(), Locale::Ja),
+                ]),
+                ..LocaleSettings::default()
+            },
+            template: DigestTemplate::default(),
+            static_users: HashMap::from([("octocat".to_string(), "U2".to_string())]),
+            user_tokens: HashMap::from([("U1".to_string(), "user_token".to_string())]),
+        };
+        let home_users = SlackHomeUsers {
+            user_ids: vec!["U1".to_string(), "U2".to_string(), "U3".to_string()],
+        };
+        save_json(&state_dir.join(SLACK_HOME_USERS_FILE_NAME), &home_users).unwrap();
+
+        let failed = refresh_all_homes(&context).await;
+        issues_mock.assert();
+        search_mock.assert();
+        user_info_mock.assert();
+        english_publish_mock.assert();
+        publish_mock.assert();
+        onboarding_publish_mock.assert();
+        assert!(failed.is_empty());
+    }
+}
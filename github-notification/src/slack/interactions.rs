@@ -0,0 +1,820 @@
+use super::context::SlackAppContext;
+use super::locale::resolve_user_locale;
+use super::modal::SubmittedView;
+use super::mrkdwn::escape_text;
+use super::payload::ISSUE_ACTION_ID;
+use super::user_map::{resolve_github_login, SLACK_USER_MAP_FILE_NAME};
+use crate::errors::GitHubApiError;
+use crate::github::{add_assignees, add_labels, close_issue, get_issue, remove_label};
+use crate::i18n::{plural_text, text, text_with, Locale};
+use crate::models::{IssueRef, Priority};
+use crate::snooze::{
+    snooze_until, SnoozeScope, SnoozedIssues, SNOOZED_ISSUES_FILE_NAME, SNOOZE_DAYS,
+};
+use crate::store::{load_json, save_json};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// the JSON payload is sent as the "payload" field of a form
+const SLACK_INTERACTION_PAYLOAD_KEY: &str = "payload";
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SlackInteractionPayload {
+    BlockActions(BlockActionsPayload),
+    // message shortcut
+    MessageAction(MessageActionPayload),
+    ViewSubmission(ViewSubmissionPayload),
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BlockActionsPayload {
+    pub user: InteractionUser,
+    pub actions: Vec<BlockAction>,
+    pub response_url: Option<String>,
+    pub message: Option<InteractionMessage>,
+    #[serde(default)]
+    pub channel: Option<InteractionChannel>,
+    #[serde(default)]
+    pub container: Option<InteractionContainer>,
+}
+
+impl BlockActionsPayload {
+    // a snooze in a channel message hides the issue in the digests of the channel
+    // a snooze in an ephemeral or a direct message, or on App Home, hides it for the user
+    pub fn snooze_scope(&self) -> SnoozeScope {
+        let is_ephemeral = matches!(&self.container, Some(container) if container.is_ephemeral);
+        match &self.channel {
+            Some(channel) if !is_ephemeral && !channel.id.starts_with('D') => {
+                SnoozeScope::Channel(channel.id.clone())
+            }
+            _ => SnoozeScope::User(self.user.id.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MessageActionPayload {
+    pub callback_id: String,
+    pub trigger_id: String,
+    pub user: InteractionUser,
+    pub channel: InteractionChannel,
+    pub message: ShortcutMessage,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShortcutMessage {
+    pub ts: String,
+    #[serde(default)]
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ViewSubmissionPayload {
+    pub user: InteractionUser,
+    pub view: SubmittedView,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InteractionUser {
+    pub id: String,
+    #[serde(default)]
+    pub username: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InteractionChannel {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InteractionContainer {
+    #[serde(default)]
+    pub is_ephemeral: bool,
+}
+
+// the message which has the element, blocks are kept as they were sent
+#[derive(Debug, Deserialize)]
+pub struct InteractionMessage {
+    pub ts: String,
+    pub blocks: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BlockAction {
+    pub action_id: String,
+    pub block_id: String,
+    pub value: Option<String>,
+    pub selected_option: Option<SelectedOption>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SelectedOption {
+    pub value: String,
+}
+
+impl BlockAction {
+    // buttons have "value", menus have "selected_option"
+    pub fn selected_value(&self) -> Option<&str> {
+        match &self.selected_option {
+            Some(option) => Some(&option.value),
+            None => self.value.as_deref(),
+        }
+    }
+}
+
+pub fn parse_interaction_payload(body: &str) -> Result<SlackInteractionPayload, String> {
+    let form: HashMap<String, String> =
+        serde_urlencoded::from_str(body).map_err(|e| format!("Invalid form: {e}"))?;
+    let payload = form
+        .get(SLACK_INTERACTION_PAYLOAD_KEY)
+        .ok_or_else(|| "No payload".to_string())?;
+    serde_json::from_str(payload).map_err(|e| format!("Invalid payload: {e}"))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum IssueAction {
+    Close,
+    Snooze { days: i64 },
+    AssignToMe,
+    PriorityUp,
+    PriorityDown,
+}
+
+impl IssueAction {
+    // parse the value of an option of the issue action menu
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "close" => Some(IssueAction::Close),
+            "assign_to_me" => Some(IssueAction::AssignToMe),
+            "priority_up" => Some(IssueAction::PriorityUp),
+            "priority_down" => Some(IssueAction::PriorityDown),
+            _ => {
+                let days = value.strip_prefix("snooze:")?.parse().ok()?;
+                SNOOZE_DAYS
+                    .contains(&days)
+                    .then_some(IssueAction::Snooze { days })
+            }
+        }
+    }
+
+    // closed and snoozed issues leave the digest, so their menu is removed
+    fn removes_issue(&self) -> bool {
+        matches!(self, IssueAction::Close | IssueAction::Snooze { .. })
+    }
+}
+
+async fn set_priority(
+    context: &SlackAppContext,
+    issue_ref: &IssueRef,
+    change: fn(&Priority) -> Priority,
+    locale: Locale,
+) -> Result<String, GitHubApiError> {
+    let issue = get_issue(&context.github_api_addr, &context.github_token, issue_ref)
+        .await
+        .map_err(|e| GitHubApiError { message: e.message })?;
+    let labels = issue.labels.unwrap_or_default();
+    let current = Priority::from_labels(&labels);
+    let new = change(&current);
+    if new == current {
+        return Ok(text(locale, "action.result.priority_unchanged"));
+    }
+
+    // swap the Priority label, the new one is added first
+    // so that a failure in between leaves the issue with a priority rather than none
+    let label_name = new.label_name();
+    if let Some(label_name) = label_name {
+        add_labels(
+            &context.github_api_addr,
+            &context.github_token,
+            issue_ref,
+            &[label_name.to_string()],
+        )
+        .await?;
+    }
+    for label in labels
+        .iter()
+        .filter(|l| Priority::is_priority_label(&l.name) && Some(l.name.as_str()) != label_name)
+    {
+        remove_label(
+            &context.github_api_addr,
+            &context.github_token,
+            issue_ref,
+            &label.name,
+        )
+        .await?;
+    }
+    match label_name {
+        Some(label_name) => Ok(text_with(
+            locale,
+            "action.result.priority_changed",
+            &[("label", label_name)],
+        )),
+        None => Ok(text(locale, "action.result.priority_removed")),
+    }
+}
+
+// run the action on GitHub and return the text describing the result
+// `github_login` is the one of the user who runs the action, which "Assign to me" needs
+// `snooze_scope` is where a snooze hides the issue, see BlockActionsPayload::snooze_scope
+pub async fn perform_issue_action(
+    context: &SlackAppContext,
+    issue_ref: &IssueRef,
+    action: &IssueAction,
+    snooze_scope: &SnoozeScope,
+    github_login: Option<&str>,
+    now: i64,
+    locale: Locale,
+) -> Result<String, GitHubApiError> {
+    match action {
+        IssueAction::Close => {
+            close_issue(&context.github_api_addr, &context.github_token, issue_ref).await?;
+            Ok(text(locale, "action.result.closed"))
+        }
+        IssueAction::Snooze { days } => {
+            let path = context.state_file_path(SNOOZED_ISSUES_FILE_NAME);
+            let mut snoozed_issues: SnoozedIssues = load_json(&path);
+            let until = snooze_until(now, *days).ok_or_else(|| GitHubApiError {
+                message: format!("Snooze Error: {days} days can't be snoozed"),
+            })?;
+            snoozed_issues.remove_expired(now);
+            snoozed_issues.snooze(snooze_scope, issue_ref, until);
+            save_json(&path, &snoozed_issues).map_err(|e| GitHubApiError { message: e.message })?;
+            Ok(plural_text(locale, "action.result.snoozed", *days))
+        }
+        IssueAction::AssignToMe => {
+            // the users who are not linked are refused before, see handle_block_actions
+            let login = github_login.ok_or_else(|| GitHubApiError {
+                message: "Assign Error: no GitHub login".to_string(),
+            })?;
+            add_assignees(
+                &context.github_api_addr,
+                &context.github_token,
+                issue_ref,
+                &[login.to_string()],
+            )
+            .await?;
+            Ok(text_with(
+                locale,
+                "action.result.assigned",
+                &[("login", login)],
+            ))
+        }
+        IssueAction::PriorityUp => set_priority(context, issue_ref, Priority::raise, locale).await,
+        IssueAction::PriorityDown => {
+            set_priority(context, issue_ref, Priority::lower, locale).await
+        }
+    }
+}
+
+// show the result under the issue line of the original message
+pub fn apply_action_result(
+    blocks: &mut [serde_json::Value],
+    block_id: &str,
+    result: &str,
+    remove_accessory: bool,
+) {
+    let block = blocks
+        .iter_mut()
+        .find(|block| block["block_id"].as_str() == Some(block_id));
+    let block = match block {
+        Some(block) => block,
+        None => return,
+    };
+    if let Some(text) = block["text"]["text"].as_str() {
+        let text = format!("{}\n_{result}_", text.trim_end());
+        block["text"]["text"] = serde_json::Value::String(text);
+    }
+    if remove_accessory {
+        if let Some(block) = block.as_object_mut() {
+            block.remove("accessory");
+        }
+    }
+}
+
+async fn replace_original_message(
+    response_url: &str,
+    blocks: &[serde_json::Value],
+    locale: Locale,
+) {
+    let client = reqwest::Client::new();
+    let body = serde_json::json!({
+        "replace_original": true,
+        "text": text(locale, "digest.title"),
+        "blocks": blocks,
+    });
+    match client.post(response_url).json(&body).send().await {
+        Ok(res) if res.status() == 200 => println!("Replace Original Message OK"),
+        Ok(res) => println!("Replace Original Message Error: {}", res.status()),
+        Err(err) => println!("Replace Original Message Error: {err}"),
+    }
+}
+
+// a reply which only the user who runs the action sees, the original message is kept
+async fn post_ephemeral_response(response_url: &str, text: &str) {
+    let client = reqwest::Client::new();
+    let body = serde_json::json!({
+        "response_type": "ephemeral",
+        "replace_original": false,
+        "text": text,
+    });
+    match client.post(response_url).json(&body).send().await {
+        Ok(res) if res.status() == 200 => println!("Ephemeral Response OK"),
+        Ok(res) => println!("Ephemeral Response Error: {}", res.status()),
+        Err(err) => println!("Ephemeral Response Error: {err}"),
+    }
+}
+
+pub async fn handle_block_actions(context: &SlackAppContext, payload: BlockActionsPayload) {
+    let mut blocks = match &payload.message {
+        Some(message) => message.blocks.clone(),
+        None => vec![],
+    };
+    let now = chrono::Utc::now().timestamp();
+    let snooze_scope = payload.snooze_scope();
+    // the results are in the language of the user who runs the action
+    let locale = resolve_user_locale(
+        &context.slack_api_addr,
+        Some(&context.slack_token),
+        &context.locales,
+        &payload.user.id,
+        None,
+    )
+    .await;
+    // the issues are assigned to the GitHub user of the one who clicks, not of the shared token
+    let github_login = resolve_github_login(
+        &payload.user.id,
+        &context.static_users,
+        &context.state_file_path(SLACK_USER_MAP_FILE_NAME),
+    );
+
+    for block_action in &payload.actions {
+        if block_action.action_id != ISSUE_ACTION_ID {
+            continue;
+        }
+        let issue_ref = IssueRef::parse(&block_action.block_id);
+        let action = block_action.selected_value().and_then(IssueAction::parse);
+        let (issue_ref, action) = match (issue_ref, action) {
+            (Some(issue_ref), Some(action)) => (issue_ref, action),
+            _ => {
+                println!("Unknown Issue Action: {:?}", block_action);
+                continue;
+            }
+        };
+        if action == IssueAction::AssignToMe && github_login.is_none() {
+            if let Some(response_url) = &payload.response_url {
+                post_ephemeral_response(response_url, &text(locale, "action.not_linked")).await;
+            }
+            continue;
+        }
+        let (result, remove_accessory) = match perform_issue_action(
+            context,
+            &issue_ref,
+            &action,
+            &snooze_scope,
+            github_login.as_deref(),
+            now,
+            locale,
+        )
+        .await
+        {
+            Ok(result) => (
+                format!("{result} (<@{}>)", payload.user.id),
+                action.removes_issue(),
+            ),
+            Err(e) => (
+                text_with(
+                    locale,
+                    "action.result.error",
+                    &[("message", &escape_text(&e.message))],
+                ),
+                false,
+            ),
+        };
+        apply_action_result(
+            &mut blocks,
+            &block_action.block_id,
+            &result,
+            remove_accessory,
+        );
+    }
+
+    if let (Some(response_url), Some(_)) = (&payload.response_url, &payload.message) {
+        replace_original_message(response_url, &blocks, locale).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(mock_addr: &str, state_dir: &str) -> SlackAppContext {
+        SlackAppContext {
+            slack_api_addr: mock_addr.to_string(),
+            slack_token: "slack_token".to_string(),
+            github_api_addr: mock_addr.to_string(),
+            github_token: "github_token".to_string(),
+            state_dir: state_dir.to_string(),
+            locales: crate::i18n::LocaleSettings::default(),
+            template: crate::slack::template::DigestTemplate::default(),
+            static_users: HashMap::new(),
+            user_tokens: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_issue_action_parse() {
+        assert_eq!(IssueAction::parse("close"), Some(IssueAction::Close));
+        assert_eq!(
+            IssueAction::parse("snooze:3"),
+            Some(IssueAction::Snooze { days: 3 })
+        );
+        assert_eq!(
+            IssueAction::parse("assign_to_me"),
+            Some(IssueAction::AssignToMe)
+        );
+        assert_eq!(IssueAction::parse("snooze:x"), None);
+        // only the days offered in the menu
+        assert_eq!(IssueAction::parse("snooze:2"), None);
+        assert_eq!(IssueAction::parse("snooze:9223372036854775807"), None);
+        assert_eq!(IssueAction::parse("unknown"), None);
+    }
+
+    #[test]
+    fn test_parse_interaction_payload() {
+        let payload = serde_json::json!({
+            "type": "block_actions",
+            "user": {"id": "U1"},
+            "response_url": "https://hooks.slack.com/actions/1",
+            "message": {"ts": "1.0", "blocks": []},
+            "actions": [{
+                "action_id": ISSUE_ACTION_ID,
+                "block_id": "owner/repo#1",
+                "selected_option": {"text": {"type": "plain_text", "text": "クローズ"}, "value": "close"},
+            }],
+        });
+        let body = serde_urlencoded::to_string([("payload", payload.to_string())]).unwrap();
+        match parse_interaction_payload(&body).unwrap() {
+            SlackInteractionPayload::BlockActions(payload) => {
+                assert_eq!(payload.user.id, "U1");
+                assert_eq!(payload.actions[0].selected_value(), Some("close"));
+            }
+            _ => panic!("unexpected payload"),
+        }
+    }
+
+    #[test]
+    fn test_parse_interaction_payload_message_action() {
+        let payload = serde_json::json!({
+            "type": "message_action",
+            "callback_id": "create_github_issue_from_message",
+            "trigger_id": "T1",
+            "user": {"id": "U1"},
+            "channel": {"id": "C1"},
+            "message": {"ts": "1.0", "text": "text"},
+        });
+        let body = serde_urlencoded::to_string([("payload", payload.to_string())]).unwrap();
+        match parse_interaction_payload(&body).unwrap() {
+            SlackInteractionPayload::MessageAction(payload) => {
+                assert_eq!(payload.trigger_id, "T1");
+                assert_eq!(payload.channel.id, "C1");
+                assert_eq!(payload.message.text, "text");
+            }
+            _ => panic!("unexpected payload"),
+        }
+    }
+
+    #[test]
+    fn test_parse_interaction_payload_view_submission() {
+        let payload = serde_json::json!({
+            "type": "view_submission",
+            "user": {"id": "U1"},
+            "view": {
+                "callback_id": "create_github_issue",
+                "private_metadata": "C1",
+                "state": {"values": {}},
+            },
+        });
+        let body = serde_urlencoded::to_string([("payload", payload.to_string())]).unwrap();
+        match parse_interaction_payload(&body).unwrap() {
+            SlackInteractionPayload::ViewSubmission(payload) => {
+                assert_eq!(payload.view.private_metadata, "C1");
+            }
+            _ => panic!("unexpected payload"),
+        }
+    }
+
+    #[test]
+    fn test_parse_interaction_payload_unknown() {
+        let payload = serde_json::json!({"type": "shortcut", "callback_id": "x"});
+        let body = serde_urlencoded::to_string([("payload", payload.to_string())]).unwrap();
+        assert!(matches!(
+            parse_interaction_payload(&body),
+            Ok(SlackInteractionPayload::Unknown)
+        ));
+        assert!(parse_interaction_payload("foo=bar").is_err());
+    }
+
+    #[test]
+    fn test_apply_action_result() {
+        let mut blocks = vec![serde_json::json!({
+            "type": "section",
+            "block_id": "owner/repo#1",
+            "text": {"type": "mrkdwn", "text": "- <url|title>\n"},
+            "accessory": {"type": "overflow"},
+        })];
+        apply_action_result(&mut blocks, "owner/repo#1", "クローズしました", true);
+        assert_eq!(
+            blocks[0]["text"]["text"],
+            "- <url|title>\n_クローズしました_"
+        );
+        assert!(blocks[0].get("accessory").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_perform_issue_action_priority_up() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let issue_mock = server.mock(|when, then| {
+            when.method(GET).path("/repos/owner/repo/issues/1");
+            then.status(200).json_body(serde_json::json!({
+                "number": 1,
+                "title": "title",
+                "html_url": "https://github.com/owner/repo/issues/1",
+                "state": "open",
+                "labels": [{"id": 1, "name": "Priority: Low"}, {"id": 2, "name": "bug"}],
+            }));
+        });
+        let remove_mock = server.mock(|when, then| {
+            when.method(DELETE)
+                .path("/repos/owner/repo/issues/1/labels/Priority:%20Low");
+            then.status(200).json_body(serde_json::json!([]));
+        });
+        let add_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/repos/owner/repo/issues/1/labels")
+                .json_body(serde_json::json!({"labels": ["Priority: Medium"]}));
+            then.status(200).json_body(serde_json::json!([]));
+        });
+        let mock_addr = format!("http://{}", server.address());
+        let context = context(&mock_addr, ".");
+        let issue_ref = IssueRef::parse("owner/repo#1").unwrap();
+
+        let res = perform_issue_action(
+            &context,
+            &issue_ref,
+            &IssueAction::PriorityUp,
+            &SnoozeScope::User("U1".to_string()),
+            None,
+            0,
+            Locale::Ja,
+        )
+        .await;
+        issue_mock.assert();
+        remove_mock.assert();
+        add_mock.assert();
+        assert_eq!(res.unwrap(), "優先度を「Priority: Medium」に変更しました");
+    }
+
+    #[tokio::test]
+    async fn test_perform_issue_action_priority_add_first() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/repos/owner/repo/issues/1");
+            then.status(200).json_body(serde_json::json!({
+                "number": 1,
+                "title": "title",
+                "html_url": "https://github.com/owner/repo/issues/1",
+                "state": "open",
+                "labels": [{"id": 1, "name": "Priority: Low"}],
+            }));
+        });
+        let add_mock = server.mock(|when, then| {
+            when.method(POST).path("/repos/owner/repo/issues/1/labels");
+            then.status(500);
+        });
+        let remove_mock = server.mock(|when, then| {
+            when.method(DELETE)
+                .path("/repos/owner/repo/issues/1/labels/Priority:%20Low");
+            then.status(200).json_body(serde_json::json!([]));
+        });
+        let mock_addr = format!("http://{}", server.address());
+        let context = context(&mock_addr, ".");
+        let issue_ref = IssueRef::parse("owner/repo#1").unwrap();
+
+        // the old label is kept when the new one can't be added
+        let res = perform_issue_action(
+            &context,
+            &issue_ref,
+            &IssueAction::PriorityUp,
+            &SnoozeScope::User("U1".to_string()),
+            None,
+            0,
+            Locale::En,
+        )
+        .await;
+        assert!(res.is_err());
+        add_mock.assert();
+        remove_mock.assert_hits(0);
+    }
+
+    #[tokio::test]
+    async fn test_perform_issue_action_snooze() {
+        let state_dir = std::env::temp_dir().join("github_notification_interactions_test");
+        let context = context("http://localhost", &state_dir.display().to_string());
+        let issue_ref = IssueRef::parse("owner/repo#1").unwrap();
+        let scope = SnoozeScope::Channel("C1".to_string());
+
+        let res = perform_issue_action(
+            &context,
+            &issue_ref,
+            &IssueAction::Snooze { days: 3 },
+            &scope,
+            None,
+            0,
+            Locale::Ja,
+        )
+        .await;
+        assert_eq!(res.unwrap(), "3日間スヌーズしました");
+        let snoozed_issues: SnoozedIssues =
+            load_json(&context.state_file_path(SNOOZED_ISSUES_FILE_NAME));
+        assert!(snoozed_issues.is_snoozed(&scope, &issue_ref, 3 * 24 * 60 * 60 - 1));
+        // the snooze is only for the channel
+        assert!(!snoozed_issues.is_snoozed(&SnoozeScope::User("U1".to_string()), &issue_ref, 0));
+
+        // the end of the snooze would overflow
+        let res = perform_issue_action(
+            &context,
+            &issue_ref,
+            &IssueAction::Snooze { days: 7 },
+            &scope,
+            None,
+            i64::MAX,
+            Locale::Ja,
+        )
+        .await;
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_block_actions_snooze_scope() {
+        let payload = |channel: Option<&str>, is_ephemeral: bool| BlockActionsPayload {
+            user: InteractionUser {
+                id: "U1".to_string(),
+                username: None,
+            },
+            actions: vec![],
+            response_url: None,
+            message: None,
+            channel: channel.map(|id| InteractionChannel { id: id.to_string() }),
+            container: Some(InteractionContainer { is_ephemeral }),
+        };
+
+        assert_eq!(
+            payload(Some("C1"), false).snooze_scope(),
+            SnoozeScope::Channel("C1".to_string())
+        );
+        assert_eq!(
+            payload(Some("C1"), true).snooze_scope(),
+            SnoozeScope::User("U1".to_string())
+        );
+        assert_eq!(
+            payload(Some("D1"), false).snooze_scope(),
+            SnoozeScope::User("U1".to_string())
+        );
+        assert_eq!(
+            payload(None, false).snooze_scope(),
+            SnoozeScope::User("U1".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handle_block_actions() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let close_mock = server.mock(|when, then| {
+            when.method("PATCH").path("/repos/owner/repo/issues/1");
+            then.status(200).json_body(serde_json::json!({}));
+        });
+        let response_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/response")
+                .json_body_partial(r#"{"replace_original": true}"#)
+                .body_contains("クローズしました (<@U1>)");
+            then.status(200);
+        });
+        let mock_addr = format!("http://{}", server.address());
+        let mut context = context(&mock_addr, ".");
+        context.locales.users.insert("U1".to_string(), Locale::Ja);
+        let payload = BlockActionsPayload {
+            user: InteractionUser {
+                id: "U1".to_string(),
+                username: None,
+            },
+            actions: vec![BlockAction {
+                action_id: ISSUE_ACTION_ID.to_string(),
+                block_id: "owner/repo#1".to_string(),
+                value: None,
+                selected_option: Some(SelectedOption {
+                    value: "close".to_string(),
+                }),
+            }],
+            response_url: Some(format!("{mock_addr}/response")),
+            message: Some(InteractionMessage {
+                ts: "1.0".to_string(),
+                blocks: vec![serde_json::json!({
+                    "type": "section",
+                    "block_id": "owner/repo#1",
+                    "text": {"type": "mrkdwn", "text": "- <url|title>\n"},
+                })],
+            }),
+            channel: None,
+            container: None,
+        };
+
+        handle_block_actions(&context, payload).await;
+        close_mock.assert();
+        response_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_perform_issue_action_assign_to_me() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let assign_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/repos/owner/repo/issues/1/assignees")
+                .json_body(serde_json::json!({"assignees": ["octocat"]}));
+            then.status(201).json_body(serde_json::json!({}));
+        });
+        let mock_addr = format!("http://{}", server.address());
+        let context = context(&mock_addr, ".");
+        let issue_ref = IssueRef::parse("owner/repo#1").unwrap();
+
+        let res = perform_issue_action(
+            &context,
+            &issue_ref,
+            &IssueAction::AssignToMe,
+            &SnoozeScope::User("U1".to_string()),
+            Some("octocat"),
+            0,
+            Locale::En,
+        )
+        .await;
+        assign_mock.assert();
+        assert_eq!(res.unwrap(), "Assigned to octocat");
+    }
+
+    #[tokio::test]
+    async fn test_handle_block_actions_assign_to_me_without_link() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let assign_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/repos/owner/repo/issues/1/assignees");
+            then.status(201).json_body(serde_json::json!({}));
+        });
+        let ephemeral_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/response")
+                .json_body_partial(r#"{"response_type": "ephemeral", "replace_original": false}"#)
+                .body_contains("/mygithub link");
+            then.status(200);
+        });
+        let mock_addr = format!("http://{}", server.address());
+        let state_dir = std::env::temp_dir().join("github_notification_interactions_unlinked");
+        let mut context = context(&mock_addr, &state_dir.display().to_string());
+        // the locale is set, so that Slack is not asked for it
+        context.locales.users.insert("U9".to_string(), Locale::En);
+        let payload = BlockActionsPayload {
+            user: InteractionUser {
+                id: "U9".to_string(),
+                username: None,
+            },
+            actions: vec![BlockAction {
+                action_id: ISSUE_ACTION_ID.to_string(),
+                block_id: "owner/repo#1".to_string(),
+                value: None,
+                selected_option: Some(SelectedOption {
+                    value: "assign_to_me".to_string(),
+                }),
+            }],
+            response_url: Some(format!("{mock_addr}/response")),
+            message: None,
+            channel: None,
+            container: None,
+        };
+
+        handle_block_actions(&context, payload).await;
+        ephemeral_mock.assert();
+        assign_mock.assert_hits(0);
+    }
+}
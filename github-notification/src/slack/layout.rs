@@ -0,0 +1,291 @@
+use super::message::{ContextBlock, SlackMessageBlock, SlackMessageBlocks};
+use crate::i18n::{plural_text, Locale};
+
+// limits of Slack
+// https://api.slack.com/reference/block-kit/blocks
+pub const MAX_SECTION_TEXT_LENGTH: usize = 3000;
+pub const MAX_BLOCKS_PER_MESSAGE: usize = 50;
+// of a view published to the App Home tab
+pub const MAX_BLOCKS_PER_VIEW: usize = 100;
+
+// a digest never spreads over more messages than this
+pub const MAX_MESSAGES_PER_DIGEST: usize = 5;
+
+// every issue in a digest section is a line starting with this
+pub const DIGEST_ITEM_PREFIX: &str = "- ";
+
+// how a digest is delivered by the Web API
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DigestLayout {
+    // the whole digest in a message, continued in its thread when too long
+    #[default]
+    Message,
+    // a summary at the top level and each group as a reply in its thread
+    Thread,
+}
+
+impl DigestLayout {
+    // "message" or "thread"
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "message" => Some(DigestLayout::Message),
+            "thread" => Some(DigestLayout::Thread),
+            _ => None,
+        }
+    }
+}
+
+// cut a text to at most `max_length` characters, with "…" at the end when cut
+pub fn truncate_text(text: &str, max_length: usize) -> String {
+    if text.chars().count() <= max_length {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_length - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+// pack lines under a header into texts of at most `max_length` characters
+// texts are split only between lines, and the following ones get `continued_header`
+// an empty header takes no line
+pub fn split_lines_with_header(
+    header: &str,
+    continued_header: &str,
+    lines: &[String],
+    max_length: usize,
+) -> Vec<String> {
+    let header_line = |header: &str| match header {
+        "" => String::new(),
+        header => format!("{header}\n"),
+    };
+    let mut texts = Vec::new();
+    let mut text = header_line(header);
+    let mut has_line = false;
+
+    for line in lines {
+        if has_line && text.chars().count() + line.chars().count() > max_length {
+            texts.push(text);
+            text = header_line(continued_header);
+        }
+        // a line which doesn't fit even alone is cut
+        let room = max_length.saturating_sub(text.chars().count());
+        text.push_str(&truncate_text(line, room.max(1)));
+        has_line = true;
+    }
+    texts.push(text);
+    texts
+}
+
+// the number of issues a block shows
+// an interactive digest has a section per issue, a plain one lists issues in a section
+pub fn count_digest_items(block: &SlackMessageBlock) -> usize {
+    match block {
+        SlackMessageBlock::Section(section) if section.accessory.is_some() => 1,
+        SlackMessageBlock::Section(section) => section
+            .text
+            .as_ref()
+            .map(|text| {
+                text.text
+                    .lines()
+                    .filter(|line| line.starts_with(DIGEST_ITEM_PREFIX))
+                    .count()
+            })
+            .unwrap_or(0),
+        _ => 0,
+    }
+}
+
+// keep at most `max_blocks` blocks, the last one is a "+N more" footer when issues are dropped
+// a limit of 0 is taken as 1, so that there is room for the footer
+pub fn truncate_blocks(blocks: &mut Vec<SlackMessageBlock>, max_blocks: usize, locale: Locale) {
+    let max_blocks = max_blocks.max(1);
+    if blocks.len() <= max_blocks {
+        return;
+    }
+    // leave room for the footer
+    let omitted = blocks.split_off(max_blocks - 1);
+    let omitted_count = omitted.iter().map(count_digest_items).sum::<usize>();
+    if omitted_count > 0 {
+        let footer =
+            ContextBlock::new().mrkdwn(plural_text(locale, "digest.more", omitted_count as i64));
+        blocks.push(footer.into());
+    }
+}
+
+// split blocks into messages of at most MAX_BLOCKS_PER_MESSAGE blocks
+// when more than `max_messages` would be needed, the rest is dropped
+// and the last message gets a "+N more" footer
+pub fn split_message_blocks(
+    message_blocks: SlackMessageBlocks,
+    max_messages: usize,
+    locale: Locale,
+) -> Vec<SlackMessageBlocks> {
+    let blocks_type = message_blocks.blocks_type;
+    let mut blocks = message_blocks.blocks;
+    truncate_blocks(&mut blocks, MAX_BLOCKS_PER_MESSAGE * max_messages, locale);
+
+    let mut messages: Vec<SlackMessageBlocks> = blocks
+        .chunks(MAX_BLOCKS_PER_MESSAGE)
+        .map(|chunk| SlackMessageBlocks {
+            blocks_type: blocks_type.clone(),
+            blocks: chunk.to_vec(),
+        })
+        .collect();
+    if messages.is_empty() {
+        messages.push(SlackMessageBlocks {
+            blocks_type,
+            blocks: vec![],
+        });
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(count: usize, length: usize) -> Vec<String> {
+        (0..count)
+            .map(|i| format!("{DIGEST_ITEM_PREFIX}{}\n", i.to_string().repeat(length)))
+            .collect()
+    }
+
+    #[test]
+    fn test_digest_layout_parse() {
+        assert_eq!(DigestLayout::parse("message"), Some(DigestLayout::Message));
+        assert_eq!(DigestLayout::parse("thread"), Some(DigestLayout::Thread));
+        assert_eq!(DigestLayout::parse("threads"), None);
+    }
+
+    #[test]
+    fn test_truncate_text() {
+        assert_eq!(truncate_text("abc", 3), "abc");
+        assert_eq!(truncate_text("abcd", 3), "ab…");
+        assert_eq!(truncate_text("あいうえ", 3), "あい…");
+    }
+
+    #[test]
+    fn test_split_lines_with_header_fits() {
+        let texts = split_lines_with_header("h", "h2", &lines(3, 1), 100);
+        assert_eq!(texts, vec!["h\n- 0\n- 1\n- 2\n"]);
+    }
+
+    #[test]
+    fn test_split_lines_with_header_splits_at_lines() {
+        // each line is 13 characters
+        let texts = split_lines_with_header("h", "h2", &lines(3, 10), 30);
+        assert_eq!(texts.len(), 2);
+        assert_eq!(
+            texts[0],
+            format!("h\n- {}\n- {}\n", "0".repeat(10), "1".repeat(10))
+        );
+        assert_eq!(texts[1], format!("h2\n- {}\n", "2".repeat(10)));
+        assert!(texts.iter().all(|text| text.chars().count() <= 30));
+    }
+
+    #[test]
+    fn test_split_lines_with_header_truncates_long_line() {
+        let texts = split_lines_with_header("h", "h2", &lines(1, 100), 30);
+        assert_eq!(texts.len(), 1);
+        assert_eq!(texts[0].chars().count(), 30);
+        assert!(texts[0].ends_with('…'));
+    }
+
+    #[test]
+    fn test_split_lines_with_empty_header() {
+        let texts = split_lines_with_header("", "", &lines(3, 10), 30);
+        assert_eq!(
+            texts,
+            vec![
+                format!("- {}\n- {}\n", "0".repeat(10), "1".repeat(10)),
+                format!("- {}\n", "2".repeat(10)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_lines_with_header_without_lines() {
+        let texts = split_lines_with_header("h", "h2", &[], 30);
+        assert_eq!(texts, vec!["h\n"]);
+    }
+
+    #[test]
+    fn test_count_digest_items() {
+        let mut message_blocks = SlackMessageBlocks::default();
+        message_blocks.add_header_block("header".to_string());
+        message_blocks.add_text_block("*優先度: 高*\n- a\n- b\n".to_string());
+        message_blocks.add_text_block_with_accessory(
+            "owner/repo#1".to_string(),
+            "- a\n".to_string(),
+            super::super::message::SlackBlockElement::overflow("action".to_string(), vec![]),
+        );
+        let counts: Vec<usize> = message_blocks
+            .blocks
+            .iter()
+            .map(count_digest_items)
+            .collect();
+        assert_eq!(counts, vec![0, 2, 1]);
+    }
+
+    fn message_with_sections(count: usize) -> SlackMessageBlocks {
+        let mut message_blocks = SlackMessageBlocks::default();
+        for i in 0..count {
+            message_blocks.add_text_block(format!("- {i}\n"));
+        }
+        message_blocks
+    }
+
+    #[test]
+    fn test_split_message_blocks_single_message() {
+        let messages = split_message_blocks(message_with_sections(50), 3, Locale::Ja);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].blocks.len(), 50);
+    }
+
+    #[test]
+    fn test_split_message_blocks_continuation() {
+        let messages = split_message_blocks(message_with_sections(120), 3, Locale::Ja);
+        let lengths: Vec<usize> = messages.iter().map(|m| m.blocks.len()).collect();
+        assert_eq!(lengths, vec![50, 50, 20]);
+    }
+
+    #[test]
+    fn test_split_message_blocks_hard_cap() {
+        let messages = split_message_blocks(message_with_sections(120), 2, Locale::Ja);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].blocks.len(), 50);
+        let footer = serde_json::to_value(&messages[1].blocks[49]).unwrap();
+        assert_eq!(
+            footer,
+            serde_json::json!({
+                "type": "context",
+                "elements": [{"type": "mrkdwn", "text": "ほか 21 件"}],
+            })
+        );
+
+        let messages = split_message_blocks(message_with_sections(120), 2, Locale::En);
+        let footer = serde_json::to_value(&messages[1].blocks[49]).unwrap();
+        assert_eq!(footer["elements"][0]["text"], "and 21 more issues");
+    }
+
+    #[test]
+    fn test_split_message_blocks_no_messages() {
+        // no room is taken as room for the footer alone
+        let messages = split_message_blocks(message_with_sections(3), 0, Locale::En);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].blocks.len(), 1);
+        let footer = serde_json::to_value(&messages[0].blocks[0]).unwrap();
+        assert_eq!(footer["elements"][0]["text"], "and 3 more issues");
+
+        let mut blocks = message_with_sections(3).blocks;
+        truncate_blocks(&mut blocks, 0, Locale::En);
+        assert_eq!(blocks.len(), 1);
+    }
+
+    #[test]
+    fn test_split_message_blocks_empty() {
+        let messages = split_message_blocks(SlackMessageBlocks::default(), 3, Locale::Ja);
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].blocks.is_empty());
+    }
+}
@@ -0,0 +1,60 @@
+use super::api::get_user_info;
+use crate::i18n::{Locale, LocaleSettings};
+
+// the locale for the texts shown to a Slack user
+// Slack is asked for the locale of the user only when none is configured,
+// which needs the bot token
+pub async fn resolve_user_locale(
+    slack_api_addr: &str,
+    slack_token: Option<&str>,
+    locales: &LocaleSettings,
+    user_id: &str,
+    channel_id: Option<&str>,
+) -> Locale {
+    let mut slack_locale = None;
+    if let (None, Some(slack_token)) = (locales.users.get(user_id), slack_token) {
+        match get_user_info(slack_api_addr, slack_token, user_id).await {
+            Ok(user) => slack_locale = user.locale,
+            Err(err) => println!("{err}"),
+        }
+    }
+    locales.user_locale(user_id, slack_locale.as_deref(), channel_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_resolve_user_locale() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users.info")
+                .query_param("user", "U2");
+            then.status(200).json_body(
+                serde_json::json!({"ok": true, "user": {"id": "U2", "locale": "en-US"}}),
+            );
+        });
+        let mock_addr = format!("http://{}", server.address());
+        let locales = LocaleSettings {
+            default: Locale::Ja,
+            channels: HashMap::new(),
+            users: HashMap::from([("U1".to_string(), Locale::Ja)]),
+        };
+
+        // a configured user is not asked to Slack
+        let locale = resolve_user_locale(&mock_addr, Some("token"), &locales, "U1", None).await;
+        assert_eq!(locale, Locale::Ja);
+        let locale = resolve_user_locale(&mock_addr, Some("token"), &locales, "U2", None).await;
+        assert_eq!(locale, Locale::En);
+        mock.assert_hits(1);
+
+        // without the bot token, the default
+        let locale = resolve_user_locale(&mock_addr, None, &locales, "U2", None).await;
+        assert_eq!(locale, Locale::Ja);
+    }
+}
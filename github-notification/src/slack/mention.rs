@@ -0,0 +1,293 @@
+use crate::models::{Issue, SortedIssues};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+// who is mentioned at the top of a digest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MentionTarget {
+    None,
+    Here,
+    Channel,
+    // id of a user group, e.g. "S0123ABCD"
+    UserGroup(String),
+    // the Slack user of each assignee
+    Assignees,
+}
+
+impl MentionTarget {
+    // "none", "here", "channel", "subteam:<id>" or "assignees"
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "none" => Some(MentionTarget::None),
+            "here" => Some(MentionTarget::Here),
+            "channel" => Some(MentionTarget::Channel),
+            "assignees" => Some(MentionTarget::Assignees),
+            _ => value
+                .strip_prefix("subteam:")
+                .filter(|id| !id.is_empty())
+                .map(|id| MentionTarget::UserGroup(id.to_string())),
+        }
+    }
+}
+
+// when the mention is made
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MentionCondition {
+    Always,
+    // only when there are high priority or overdue issues
+    HighPriorityOrOverdue,
+}
+
+impl MentionCondition {
+    // "always" or "high_or_overdue"
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "always" => Some(MentionCondition::Always),
+            "high_or_overdue" => Some(MentionCondition::HighPriorityOrOverdue),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MentionPolicy {
+    pub target: MentionTarget,
+    pub condition: MentionCondition,
+}
+
+impl Default for MentionPolicy {
+    // the whole channel on every digest, as before the policy was configurable
+    fn default() -> Self {
+        Self {
+            target: MentionTarget::Channel,
+            condition: MentionCondition::Always,
+        }
+    }
+}
+
+// the issues which call for a mention
+fn issues_to_mention(
+    condition: MentionCondition,
+    issues: &SortedIssues,
+    now: DateTime<Utc>,
+) -> Vec<&Issue> {
+    match condition {
+        MentionCondition::Always => issues.iter().collect(),
+        MentionCondition::HighPriorityOrOverdue => issues
+            .priority_high_issues
+            .iter()
+            .chain(issues.iter().filter(|issue| issue.is_overdue(now)))
+            .collect(),
+    }
+}
+
+// the mention text for a digest, None when nobody is to be mentioned
+// `slack_users` maps GitHub logins to Slack user ids
+pub fn create_mention_text(
+    policy: &MentionPolicy,
+    issues: &SortedIssues,
+    slack_users: &HashMap<String, String>,
+    now: DateTime<Utc>,
+) -> Option<String> {
+    let issues = issues_to_mention(policy.condition, issues, now);
+    // "always" mentions even an empty digest, as it did before
+    if issues.is_empty() && policy.condition != MentionCondition::Always {
+        return None;
+    }
+
+    match &policy.target {
+        MentionTarget::None => None,
+        MentionTarget::Here => Some("<!here>".to_string()),
+        MentionTarget::Channel => Some("<!channel>".to_string()),
+        MentionTarget::UserGroup(id) => Some(format!("<!subteam^{id}>")),
+        MentionTarget::Assignees => {
+            let mut user_ids: Vec<&str> = Vec::new();
+            for login in issues.iter().flat_map(|issue| issue.assignee_logins()) {
+                // assignees without a Slack user are left out
+                if let Some(user_id) = slack_users.get(login) {
+                    if !user_ids.contains(&user_id.as_str()) {
+                        user_ids.push(user_id);
+                    }
+                }
+            }
+            if user_ids.is_empty() {
+                return None;
+            }
+            let mentions: Vec<String> = user_ids.iter().map(|id| format!("<@{id}>")).collect();
+            Some(mentions.join(" "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{self, now};
+    use crate::models::User;
+
+    // the shared fixture assigned to `assignees`
+    fn issue(assignees: &[&str], due_on: Option<DateTime<Utc>>) -> Issue {
+        let mut issue = fixtures::issue(1, "title", due_on);
+        issue.assignees = Some(
+            assignees
+                .iter()
+                .map(|login| User {
+                    login: login.to_string(),
+                })
+                .collect(),
+        );
+        issue
+    }
+
+    fn policy(target: MentionTarget, condition: MentionCondition) -> MentionPolicy {
+        MentionPolicy { target, condition }
+    }
+
+    #[test]
+    fn test_mention_target_parse() {
+        assert_eq!(MentionTarget::parse("none"), Some(MentionTarget::None));
+        assert_eq!(MentionTarget::parse("here"), Some(MentionTarget::Here));
+        assert_eq!(
+            MentionTarget::parse("channel"),
+            Some(MentionTarget::Channel)
+        );
+        assert_eq!(
+            MentionTarget::parse("subteam:S1"),
+            Some(MentionTarget::UserGroup("S1".to_string()))
+        );
+        assert_eq!(
+            MentionTarget::parse("assignees"),
+            Some(MentionTarget::Assignees)
+        );
+        assert_eq!(MentionTarget::parse("subteam:"), None);
+        assert_eq!(MentionTarget::parse("everyone"), None);
+    }
+
+    #[test]
+    fn test_mention_condition_parse() {
+        assert_eq!(
+            MentionCondition::parse("always"),
+            Some(MentionCondition::Always)
+        );
+        assert_eq!(
+            MentionCondition::parse("high_or_overdue"),
+            Some(MentionCondition::HighPriorityOrOverdue)
+        );
+        assert_eq!(MentionCondition::parse("sometimes"), None);
+    }
+
+    #[test]
+    fn test_create_mention_text_targets() {
+        let mut issues = SortedIssues::default();
+        issues.priority_low_issues.push(issue(&[], None));
+        let users = HashMap::new();
+        let text = |target| {
+            create_mention_text(
+                &policy(target, MentionCondition::Always),
+                &issues,
+                &users,
+                now(),
+            )
+        };
+        assert_eq!(text(MentionTarget::None), None);
+        assert_eq!(text(MentionTarget::Here).as_deref(), Some("<!here>"));
+        assert_eq!(text(MentionTarget::Channel).as_deref(), Some("<!channel>"));
+        assert_eq!(
+            text(MentionTarget::UserGroup("S1".to_string())).as_deref(),
+            Some("<!subteam^S1>")
+        );
+    }
+
+    #[test]
+    fn test_create_mention_text_without_issues() {
+        let text = create_mention_text(
+            &MentionPolicy::default(),
+            &SortedIssues::default(),
+            &HashMap::new(),
+            now(),
+        );
+        assert_eq!(text.as_deref(), Some("<!channel>"));
+    }
+
+    #[test]
+    fn test_create_mention_text_assignees() {
+        let mut issues = SortedIssues::default();
+        issues
+            .priority_high_issues
+            .push(issue(&["alice", "bob"], None));
+        issues
+            .priority_low_issues
+            .push(issue(&["alice", "carol"], None));
+        let users = HashMap::from([
+            ("alice".to_string(), "U1".to_string()),
+            ("carol".to_string(), "U3".to_string()),
+        ]);
+        let text = create_mention_text(
+            &policy(MentionTarget::Assignees, MentionCondition::Always),
+            &issues,
+            &users,
+            now(),
+        );
+        assert_eq!(text.as_deref(), Some("<@U1> <@U3>"));
+
+        // only the assignees of high priority issues
+        let text = create_mention_text(
+            &policy(
+                MentionTarget::Assignees,
+                MentionCondition::HighPriorityOrOverdue,
+            ),
+            &issues,
+            &users,
+            now(),
+        );
+        assert_eq!(text.as_deref(), Some("<@U1>"));
+
+        // nobody is mapped
+        let text = create_mention_text(
+            &policy(MentionTarget::Assignees, MentionCondition::Always),
+            &issues,
+            &HashMap::new(),
+            now(),
+        );
+        assert_eq!(text, None);
+    }
+
+    #[test]
+    fn test_create_mention_text_high_or_overdue() {
+        let condition = MentionCondition::HighPriorityOrOverdue;
+        let users = HashMap::new();
+
+        let mut issues = SortedIssues::default();
+        issues
+            .priority_low_issues
+            .push(issue(&[], Some(now() + chrono::Duration::days(1))));
+        let text = create_mention_text(
+            &policy(MentionTarget::Here, condition),
+            &issues,
+            &users,
+            now(),
+        );
+        assert_eq!(text, None);
+
+        issues
+            .priority_low_issues
+            .push(issue(&[], Some(now() - chrono::Duration::days(1))));
+        let text = create_mention_text(
+            &policy(MentionTarget::Here, condition),
+            &issues,
+            &users,
+            now(),
+        );
+        assert_eq!(text.as_deref(), Some("<!here>"));
+
+        let mut issues = SortedIssues::default();
+        issues.priority_high_issues.push(issue(&[], None));
+        let text = create_mention_text(
+            &policy(MentionTarget::Here, condition),
+            &issues,
+            &users,
+            now(),
+        );
+        assert_eq!(text.as_deref(), Some("<!here>"));
+    }
+}
@@ -1,20 +1,699 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SlackMessageBlock {
-    #[serde(rename = "type")]
-    pub(crate) block_type: String,
-    pub(crate) text: Option<SlackMessageBlockText>,
-}
+// Block Kit
+// https://api.slack.com/reference/block-kit/blocks
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+// text object, "plain_text" or "mrkdwn"
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct SlackMessageBlockText {
     #[serde(rename = "type")]
-    pub(crate) text_type: String,
-    pub(crate) text: String,
+    pub text_type: String,
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl SlackMessageBlockText {
+    pub fn plain_text(text: String) -> Self {
+        Self {
+            text_type: "plain_text".to_string(),
+            text,
+            emoji: None,
+        }
+    }
+
+    pub fn mrkdwn(text: String) -> Self {
+        Self {
+            text_type: "mrkdwn".to_string(),
+            text,
+            emoji: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SlackOptionObject {
+    pub text: SlackMessageBlockText,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<SlackMessageBlockText>,
+}
+
+impl SlackOptionObject {
+    pub fn new(text: String, value: String) -> Self {
+        Self {
+            text: SlackMessageBlockText::plain_text(text),
+            value,
+            description: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SlackConfirmationDialog {
+    pub title: SlackMessageBlockText,
+    pub text: SlackMessageBlockText,
+    pub confirm: SlackMessageBlockText,
+    pub deny: SlackMessageBlockText,
+}
+
+impl SlackConfirmationDialog {
+    pub fn new(title: String, text: String, confirm: String, deny: String) -> Self {
+        Self {
+            title: SlackMessageBlockText::plain_text(title),
+            text: SlackMessageBlockText::plain_text(text),
+            confirm: SlackMessageBlockText::plain_text(confirm),
+            deny: SlackMessageBlockText::plain_text(deny),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ButtonStyle {
+    Primary,
+    Danger,
+}
+
+// block elements
+// https://api.slack.com/reference/block-kit/block-elements
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SlackBlockElement {
+    Button(ButtonElement),
+    Overflow(OverflowElement),
+    StaticSelect(StaticSelectElement),
+    PlainTextInput(PlainTextInputElement),
+    Image(ImageElement),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ButtonElement {
+    pub action_id: String,
+    pub text: SlackMessageBlockText,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<ButtonStyle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirm: Option<SlackConfirmationDialog>,
+}
+
+impl ButtonElement {
+    pub fn new(action_id: String, text: String) -> Self {
+        Self {
+            action_id,
+            text: SlackMessageBlockText::plain_text(text),
+            value: None,
+            url: None,
+            style: None,
+            confirm: None,
+        }
+    }
+
+    pub fn value(mut self, value: String) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    pub fn url(mut self, url: String) -> Self {
+        self.url = Some(url);
+        self
+    }
+
+    pub fn style(mut self, style: ButtonStyle) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    pub fn confirm(mut self, confirm: SlackConfirmationDialog) -> Self {
+        self.confirm = Some(confirm);
+        self
+    }
+}
+
+// an overflow menu can have 2 to 5 options
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OverflowElement {
+    pub action_id: String,
+    pub options: Vec<SlackOptionObject>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirm: Option<SlackConfirmationDialog>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StaticSelectElement {
+    pub action_id: String,
+    pub options: Vec<SlackOptionObject>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<SlackMessageBlockText>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_option: Option<SlackOptionObject>,
+}
+
+impl StaticSelectElement {
+    pub fn new(action_id: String, options: Vec<SlackOptionObject>) -> Self {
+        Self {
+            action_id,
+            options,
+            placeholder: None,
+            initial_option: None,
+        }
+    }
+
+    pub fn placeholder(mut self, placeholder: String) -> Self {
+        self.placeholder = Some(SlackMessageBlockText::plain_text(placeholder));
+        self
+    }
+
+    pub fn initial_option(mut self, option: SlackOptionObject) -> Self {
+        self.initial_option = Some(option);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PlainTextInputElement {
+    pub action_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<SlackMessageBlockText>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiline: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<u32>,
+}
+
+impl PlainTextInputElement {
+    pub fn new(action_id: String) -> Self {
+        Self {
+            action_id,
+            placeholder: None,
+            initial_value: None,
+            multiline: None,
+            max_length: None,
+        }
+    }
+
+    pub fn placeholder(mut self, placeholder: String) -> Self {
+        self.placeholder = Some(SlackMessageBlockText::plain_text(placeholder));
+        self
+    }
+
+    pub fn initial_value(mut self, initial_value: String) -> Self {
+        self.initial_value = Some(initial_value);
+        self
+    }
+
+    pub fn multiline(mut self, multiline: bool) -> Self {
+        self.multiline = Some(multiline);
+        self
+    }
+
+    pub fn max_length(mut self, max_length: u32) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ImageElement {
+    pub image_url: String,
+    pub alt_text: String,
+}
+
+impl ImageElement {
+    pub fn new(image_url: String, alt_text: String) -> Self {
+        Self {
+            image_url,
+            alt_text,
+        }
+    }
+}
+
+impl SlackBlockElement {
+    pub fn overflow(action_id: String, options: Vec<SlackOptionObject>) -> Self {
+        SlackBlockElement::Overflow(OverflowElement {
+            action_id,
+            options,
+            confirm: None,
+        })
+    }
+
+    pub fn static_select(
+        action_id: String,
+        placeholder: String,
+        options: Vec<SlackOptionObject>,
+    ) -> Self {
+        SlackBlockElement::StaticSelect(
+            StaticSelectElement::new(action_id, options).placeholder(placeholder),
+        )
+    }
+
+    pub fn plain_text_input(
+        action_id: String,
+        multiline: bool,
+        initial_value: Option<String>,
+    ) -> Self {
+        let mut element = PlainTextInputElement::new(action_id).multiline(multiline);
+        element.initial_value = initial_value;
+        SlackBlockElement::PlainTextInput(element)
+    }
+
+    pub fn action_id(&self) -> Option<&str> {
+        match self {
+            SlackBlockElement::Button(e) => Some(&e.action_id),
+            SlackBlockElement::Overflow(e) => Some(&e.action_id),
+            SlackBlockElement::StaticSelect(e) => Some(&e.action_id),
+            SlackBlockElement::PlainTextInput(e) => Some(&e.action_id),
+            SlackBlockElement::Image(_) => None,
+        }
+    }
+}
+
+impl From<ButtonElement> for SlackBlockElement {
+    fn from(element: ButtonElement) -> Self {
+        SlackBlockElement::Button(element)
+    }
+}
+
+impl From<StaticSelectElement> for SlackBlockElement {
+    fn from(element: StaticSelectElement) -> Self {
+        SlackBlockElement::StaticSelect(element)
+    }
+}
+
+impl From<PlainTextInputElement> for SlackBlockElement {
+    fn from(element: PlainTextInputElement) -> Self {
+        SlackBlockElement::PlainTextInput(element)
+    }
+}
+
+impl From<ImageElement> for SlackBlockElement {
+    fn from(element: ImageElement) -> Self {
+        SlackBlockElement::Image(element)
+    }
+}
+
+// elements of a context block: text or images
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SlackContextElement {
+    PlainText { text: String },
+    Mrkdwn { text: String },
+    Image(ImageElement),
+}
+
+// rich text, as sent by the message composer
+// https://api.slack.com/reference/block-kit/blocks#rich_text
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SlackRichTextElement {
+    RichTextSection {
+        elements: Vec<SlackRichTextInline>,
+    },
+    RichTextList {
+        style: String,
+        elements: Vec<SlackRichTextElement>,
+    },
+    RichTextQuote {
+        elements: Vec<SlackRichTextInline>,
+    },
+    RichTextPreformatted {
+        elements: Vec<SlackRichTextInline>,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SlackRichTextStyle {
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub bold: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub italic: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub strike: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub code: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SlackRichTextInline {
+    Text {
+        text: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        style: Option<SlackRichTextStyle>,
+    },
+    Link {
+        url: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        text: Option<String>,
+    },
+    Emoji {
+        name: String,
+    },
+    User {
+        user_id: String,
+    },
+    Usergroup {
+        usergroup_id: String,
+    },
+    Channel {
+        channel_id: String,
+    },
+}
+
+// blocks
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SlackMessageBlock {
+    Header(HeaderBlock),
+    Section(SectionBlock),
+    Divider(DividerBlock),
+    Context(ContextBlock),
+    Actions(ActionsBlock),
+    Image(ImageBlock),
+    Input(InputBlock),
+    RichText(RichTextBlock),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HeaderBlock {
+    // plain_text only, up to 150 characters
+    pub text: SlackMessageBlockText,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+}
+
+impl HeaderBlock {
+    pub fn new(text: String) -> Self {
+        Self {
+            text: SlackMessageBlockText::plain_text(text),
+            block_id: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SectionBlock {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<SlackMessageBlockText>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+    // up to 10 fields, shown in two columns
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<SlackMessageBlockText>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accessory: Option<SlackBlockElement>,
+}
+
+impl SectionBlock {
+    pub fn new() -> Self {
+        Self {
+            text: None,
+            block_id: None,
+            fields: vec![],
+            accessory: None,
+        }
+    }
+
+    pub fn mrkdwn(text: String) -> Self {
+        Self::new().text(SlackMessageBlockText::mrkdwn(text))
+    }
+
+    pub fn text(mut self, text: SlackMessageBlockText) -> Self {
+        self.text = Some(text);
+        self
+    }
+
+    pub fn block_id(mut self, block_id: String) -> Self {
+        self.block_id = Some(block_id);
+        self
+    }
+
+    pub fn field(mut self, field: SlackMessageBlockText) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    pub fn accessory(mut self, accessory: SlackBlockElement) -> Self {
+        self.accessory = Some(accessory);
+        self
+    }
+}
+
+impl Default for SectionBlock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DividerBlock {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContextBlock {
+    // up to 10 elements
+    pub elements: Vec<SlackContextElement>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+}
+
+impl ContextBlock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn mrkdwn(mut self, text: String) -> Self {
+        self.elements.push(SlackContextElement::Mrkdwn { text });
+        self
+    }
+
+    pub fn plain_text(mut self, text: String) -> Self {
+        self.elements.push(SlackContextElement::PlainText { text });
+        self
+    }
+
+    pub fn image(mut self, image: ImageElement) -> Self {
+        self.elements.push(SlackContextElement::Image(image));
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ActionsBlock {
+    // up to 25 elements
+    pub elements: Vec<SlackBlockElement>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+}
+
+impl ActionsBlock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn element(mut self, element: impl Into<SlackBlockElement>) -> Self {
+        self.elements.push(element.into());
+        self
+    }
+
+    pub fn block_id(mut self, block_id: String) -> Self {
+        self.block_id = Some(block_id);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ImageBlock {
+    pub image_url: String,
+    pub alt_text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<SlackMessageBlockText>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+}
+
+impl ImageBlock {
+    pub fn new(image_url: String, alt_text: String) -> Self {
+        Self {
+            image_url,
+            alt_text,
+            title: None,
+            block_id: None,
+        }
+    }
+
+    pub fn title(mut self, title: String) -> Self {
+        self.title = Some(SlackMessageBlockText::plain_text(title));
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InputBlock {
+    pub label: SlackMessageBlockText,
+    pub element: SlackBlockElement,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<SlackMessageBlockText>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub optional: Option<bool>,
+}
+
+impl InputBlock {
+    pub fn new(label: String, element: impl Into<SlackBlockElement>) -> Self {
+        Self {
+            label: SlackMessageBlockText::plain_text(label),
+            element: element.into(),
+            block_id: None,
+            hint: None,
+            optional: None,
+        }
+    }
+
+    pub fn block_id(mut self, block_id: String) -> Self {
+        self.block_id = Some(block_id);
+        self
+    }
+
+    pub fn hint(mut self, hint: String) -> Self {
+        self.hint = Some(SlackMessageBlockText::plain_text(hint));
+        self
+    }
+
+    pub fn optional(mut self, optional: bool) -> Self {
+        self.optional = Some(optional);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RichTextBlock {
+    pub elements: Vec<SlackRichTextElement>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+}
+
+impl RichTextBlock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn element(mut self, element: SlackRichTextElement) -> Self {
+        self.elements.push(element);
+        self
+    }
+}
+
+impl SlackMessageBlock {
+    // the "type" of the block in Block Kit
+    pub fn block_type(&self) -> &'static str {
+        match self {
+            SlackMessageBlock::Header(_) => "header",
+            SlackMessageBlock::Section(_) => "section",
+            SlackMessageBlock::Divider(_) => "divider",
+            SlackMessageBlock::Context(_) => "context",
+            SlackMessageBlock::Actions(_) => "actions",
+            SlackMessageBlock::Image(_) => "image",
+            SlackMessageBlock::Input(_) => "input",
+            SlackMessageBlock::RichText(_) => "rich_text",
+        }
+    }
+
+    // the main text of header and section blocks
+    pub fn text(&self) -> Option<&SlackMessageBlockText> {
+        match self {
+            SlackMessageBlock::Header(block) => Some(&block.text),
+            SlackMessageBlock::Section(block) => block.text.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn accessory(&self) -> Option<&SlackBlockElement> {
+        match self {
+            SlackMessageBlock::Section(block) => block.accessory.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn element(&self) -> Option<&SlackBlockElement> {
+        match self {
+            SlackMessageBlock::Input(block) => Some(&block.element),
+            _ => None,
+        }
+    }
+
+    pub fn block_id(&self) -> Option<&str> {
+        match self {
+            SlackMessageBlock::Header(block) => block.block_id.as_deref(),
+            SlackMessageBlock::Section(block) => block.block_id.as_deref(),
+            SlackMessageBlock::Divider(block) => block.block_id.as_deref(),
+            SlackMessageBlock::Context(block) => block.block_id.as_deref(),
+            SlackMessageBlock::Actions(block) => block.block_id.as_deref(),
+            SlackMessageBlock::Image(block) => block.block_id.as_deref(),
+            SlackMessageBlock::Input(block) => block.block_id.as_deref(),
+            SlackMessageBlock::RichText(block) => block.block_id.as_deref(),
+        }
+    }
+}
+
+impl From<HeaderBlock> for SlackMessageBlock {
+    fn from(block: HeaderBlock) -> Self {
+        SlackMessageBlock::Header(block)
+    }
+}
+
+impl From<SectionBlock> for SlackMessageBlock {
+    fn from(block: SectionBlock) -> Self {
+        SlackMessageBlock::Section(block)
+    }
+}
+
+impl From<DividerBlock> for SlackMessageBlock {
+    fn from(block: DividerBlock) -> Self {
+        SlackMessageBlock::Divider(block)
+    }
+}
+
+impl From<ContextBlock> for SlackMessageBlock {
+    fn from(block: ContextBlock) -> Self {
+        SlackMessageBlock::Context(block)
+    }
+}
+
+impl From<ActionsBlock> for SlackMessageBlock {
+    fn from(block: ActionsBlock) -> Self {
+        SlackMessageBlock::Actions(block)
+    }
+}
+
+impl From<ImageBlock> for SlackMessageBlock {
+    fn from(block: ImageBlock) -> Self {
+        SlackMessageBlock::Image(block)
+    }
+}
+
+impl From<InputBlock> for SlackMessageBlock {
+    fn from(block: InputBlock) -> Self {
+        SlackMessageBlock::Input(block)
+    }
+}
+
+impl From<RichTextBlock> for SlackMessageBlock {
+    fn from(block: RichTextBlock) -> Self {
+        SlackMessageBlock::RichText(block)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SlackMessageBlocks {
     #[serde(rename = "type")]
     pub blocks_type: String,
@@ -35,45 +714,103 @@ impl SlackMessageBlocks {
         }
     }
 
-    fn add_block(&mut self, block: SlackMessageBlock) {
-        self.blocks.push(block);
+    pub fn add_block(&mut self, block: impl Into<SlackMessageBlock>) -> &mut Self {
+        self.blocks.push(block.into());
+        self
     }
 
-    pub fn add_header_block(&mut self, text: String) {
-        let block = SlackMessageBlock {
-            block_type: "header".to_string(),
-            text: Some(SlackMessageBlockText {
-                text_type: "plain_text".to_string(),
-                text,
-            }),
-        };
+    // builder style, e.g. SlackMessageBlocks::default().with_block(DividerBlock::default())
+    pub fn with_block(mut self, block: impl Into<SlackMessageBlock>) -> Self {
         self.add_block(block);
+        self
+    }
+
+    pub fn add_header_block(&mut self, text: String) {
+        self.add_block(HeaderBlock::new(text));
     }
 
     pub fn add_text_block(&mut self, text: String) {
-        let block = SlackMessageBlock {
-            block_type: "section".to_string(),
-            text: Some(SlackMessageBlockText {
-                text_type: "mrkdwn".to_string(),
-                text,
-            }),
-        };
-        self.add_block(block);
+        self.add_block(SectionBlock::mrkdwn(text));
+    }
+
+    pub fn add_text_block_with_accessory(
+        &mut self,
+        block_id: String,
+        text: String,
+        accessory: SlackBlockElement,
+    ) {
+        self.add_block(
+            SectionBlock::mrkdwn(text)
+                .block_id(block_id)
+                .accessory(accessory),
+        );
+    }
+
+    pub fn add_divider_block(&mut self) {
+        self.add_block(DividerBlock::default());
+    }
+
+    pub fn add_context_block(&mut self, text: String) {
+        self.add_block(ContextBlock::new().mrkdwn(text));
+    }
+}
+
+// a modal opened by views.open
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SlackModalView {
+    #[serde(rename = "type")]
+    pub view_type: String,
+    pub callback_id: String,
+    pub title: SlackMessageBlockText,
+    pub submit: SlackMessageBlockText,
+    pub close: SlackMessageBlockText,
+    pub private_metadata: String,
+    pub blocks: Vec<SlackMessageBlock>,
+}
+
+impl SlackModalView {
+    // `submit` and `close` are the labels of the buttons
+    pub fn new(
+        callback_id: String,
+        title: String,
+        submit: String,
+        close: String,
+        private_metadata: String,
+    ) -> Self {
+        Self {
+            view_type: "modal".to_string(),
+            callback_id,
+            title: SlackMessageBlockText::plain_text(title),
+            submit: SlackMessageBlockText::plain_text(submit),
+            close: SlackMessageBlockText::plain_text(close),
+            private_metadata,
+            blocks: vec![],
+        }
+    }
+
+    pub fn add_input_block(
+        &mut self,
+        block_id: String,
+        label: String,
+        element: SlackBlockElement,
+        optional: bool,
+    ) {
+        let block = InputBlock::new(label, element)
+            .block_id(block_id)
+            .optional(optional);
+        self.blocks.push(block.into());
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::slack::message::{SlackMessageBlock, SlackMessageBlockText};
+    use crate::slack::message::*;
 
     #[test]
     fn test_slack_message_block_1() {
-        let slack_message_block = SlackMessageBlock {
-            block_type: "test".to_string(),
-            text: None,
-        };
-        assert_eq!(slack_message_block.block_type, "test");
-        assert_eq!(slack_message_block.text, None);
+        let slack_message_block = SlackMessageBlock::Divider(DividerBlock::default());
+        assert_eq!(slack_message_block.block_type(), "divider");
+        assert_eq!(slack_message_block.text(), None);
     }
 
     #[test]
@@ -81,8 +818,192 @@ mod tests {
         let slack_message_block_text = SlackMessageBlockText {
             text_type: "test".to_string(),
             text: "test".to_string(),
+            emoji: None,
         };
         assert_eq!(slack_message_block_text.text_type, "test");
         assert_eq!(slack_message_block_text.text, "test");
     }
+
+    #[test]
+    fn test_add_header_and_text_block_1() {
+        let mut slack_message_blocks = SlackMessageBlocks::default();
+        slack_message_blocks.add_header_block("header".to_string());
+        slack_message_blocks.add_text_block("text".to_string());
+        let json = serde_json::to_value(&slack_message_blocks).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "home",
+                "blocks": [
+                    {"type": "header", "text": {"type": "plain_text", "text": "header"}},
+                    {"type": "section", "text": {"type": "mrkdwn", "text": "text"}},
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_add_text_block_with_accessory_1() {
+        let mut slack_message_blocks = SlackMessageBlocks::default();
+        let overflow = SlackBlockElement::overflow(
+            "action".to_string(),
+            vec![
+                SlackOptionObject::new("a".to_string(), "a".to_string()),
+                SlackOptionObject::new("b".to_string(), "b".to_string()),
+            ],
+        );
+        slack_message_blocks.add_text_block_with_accessory(
+            "block".to_string(),
+            "text".to_string(),
+            overflow,
+        );
+        let json = serde_json::to_value(&slack_message_blocks.blocks[0]).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "section",
+                "text": {"type": "mrkdwn", "text": "text"},
+                "block_id": "block",
+                "accessory": {
+                    "type": "overflow",
+                    "action_id": "action",
+                    "options": [
+                        {"text": {"type": "plain_text", "text": "a"}, "value": "a"},
+                        {"text": {"type": "plain_text", "text": "b"}, "value": "b"},
+                    ],
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_section_block_with_fields_1() {
+        let block: SlackMessageBlock = SectionBlock::new()
+            .field(SlackMessageBlockText::mrkdwn("*a*".to_string()))
+            .field(SlackMessageBlockText::plain_text("b".to_string()))
+            .accessory(
+                ImageElement::new("https://example.com/a.png".to_string(), "a".to_string()).into(),
+            )
+            .into();
+        let json = serde_json::to_value(&block).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "section",
+                "fields": [
+                    {"type": "mrkdwn", "text": "*a*"},
+                    {"type": "plain_text", "text": "b"},
+                ],
+                "accessory": {
+                    "type": "image",
+                    "image_url": "https://example.com/a.png",
+                    "alt_text": "a",
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_builder_blocks_1() {
+        let blocks = SlackMessageBlocks::default()
+            .with_block(DividerBlock::default())
+            .with_block(
+                ContextBlock::new()
+                    .mrkdwn("*a*".to_string())
+                    .image(ImageElement::new("u".to_string(), "alt".to_string())),
+            )
+            .with_block(
+                ActionsBlock::new().block_id("actions".to_string()).element(
+                    ButtonElement::new("open".to_string(), "Open".to_string())
+                        .url("https://github.com".to_string())
+                        .style(ButtonStyle::Primary),
+                ),
+            )
+            .with_block(ImageBlock::new("u".to_string(), "alt".to_string()).title("t".to_string()));
+        let json = serde_json::to_value(&blocks.blocks).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {"type": "divider"},
+                {"type": "context", "elements": [
+                    {"type": "mrkdwn", "text": "*a*"},
+                    {"type": "image", "image_url": "u", "alt_text": "alt"},
+                ]},
+                {"type": "actions", "block_id": "actions", "elements": [{
+                    "type": "button",
+                    "action_id": "open",
+                    "text": {"type": "plain_text", "text": "Open"},
+                    "url": "https://github.com",
+                    "style": "primary",
+                }]},
+                {"type": "image", "image_url": "u", "alt_text": "alt", "title": {"type": "plain_text", "text": "t"}},
+            ])
+        );
+    }
+
+    #[test]
+    fn test_rich_text_block_1() {
+        let block: SlackMessageBlock = RichTextBlock::new()
+            .element(SlackRichTextElement::RichTextSection {
+                elements: vec![
+                    SlackRichTextInline::Text {
+                        text: "bold".to_string(),
+                        style: Some(SlackRichTextStyle {
+                            bold: true,
+                            ..Default::default()
+                        }),
+                    },
+                    SlackRichTextInline::Link {
+                        url: "https://github.com".to_string(),
+                        text: None,
+                    },
+                ],
+            })
+            .into();
+        let json = serde_json::to_value(&block).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "rich_text",
+                "elements": [{
+                    "type": "rich_text_section",
+                    "elements": [
+                        {"type": "text", "text": "bold", "style": {"bold": true}},
+                        {"type": "link", "url": "https://github.com"},
+                    ],
+                }],
+            })
+        );
+        // and back
+        let parsed: SlackMessageBlock = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, block);
+    }
+
+    #[test]
+    fn test_slack_modal_view_add_input_block_1() {
+        let mut view = SlackModalView::new(
+            "callback".to_string(),
+            "title".to_string(),
+            "submit".to_string(),
+            "close".to_string(),
+            "metadata".to_string(),
+        );
+        view.add_input_block(
+            "block".to_string(),
+            "label".to_string(),
+            SlackBlockElement::plain_text_input("action".to_string(), true, None),
+            false,
+        );
+        let json = serde_json::to_value(&view.blocks[0]).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "input",
+                "block_id": "block",
+                "label": {"type": "plain_text", "text": "label"},
+                "element": {"type": "plain_text_input", "action_id": "action", "multiline": true},
+                "optional": false,
+            })
+        );
+    }
 }
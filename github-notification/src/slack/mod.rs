@@ -1,4 +1,21 @@
+pub mod api;
+pub mod context;
+pub mod events;
+pub mod history;
+pub mod home;
+pub mod interactions;
+pub mod layout;
+pub mod locale;
+pub mod mention;
 pub mod message;
+pub mod modal;
+pub mod mrkdwn;
 pub mod notification;
+pub mod notifier;
 pub mod payload;
+pub mod personal;
+pub mod signature;
 pub mod slash;
+pub mod style;
+pub mod template;
+pub mod user_map;
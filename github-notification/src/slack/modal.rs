@@ -0,0 +1,607 @@
+use super::api::{open_view, post_ephemeral, post_message, update_view};
+use super::context::SlackAppContext;
+use super::interactions::InteractionUser;
+use super::locale::resolve_user_locale;
+use super::message::{SlackBlockElement, SlackMessageBlocks, SlackModalView, SlackOptionObject};
+use super::mrkdwn::{escape_text, link};
+use crate::errors::SlackApiError;
+use crate::github::{collect_repository_names, create_issue, get_my_issues};
+use crate::i18n::{text, text_with, Locale};
+use crate::models::{NewIssue, Priority};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const CREATE_ISSUE_MODAL_CALLBACK_ID: &str = "create_github_issue";
+// callback_id of the message shortcut "Create GitHub issue from this message"
+pub const CREATE_ISSUE_SHORTCUT_CALLBACK_ID: &str = "create_github_issue_from_message";
+
+const REPOSITORY_BLOCK_ID: &str = "repository";
+const TITLE_BLOCK_ID: &str = "title";
+const BODY_BLOCK_ID: &str = "body";
+const PRIORITY_BLOCK_ID: &str = "priority";
+const ASSIGNEE_BLOCK_ID: &str = "assignee";
+// one element per input block, so the action_id is the same for every block
+const INPUT_ACTION_ID: &str = "input";
+
+// a title has to fit in a plain_text_input initial_value
+const MAX_INITIAL_TITLE_CHARS: usize = 100;
+// the longest initial_value of a multiline plain_text_input
+const MAX_INITIAL_BODY_CHARS: usize = 3000;
+
+// state.values of a submitted view: block_id -> action_id -> value
+#[derive(Debug, Deserialize)]
+pub struct SubmittedView {
+    pub callback_id: String,
+    pub private_metadata: String,
+    pub state: SubmittedViewState,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubmittedViewState {
+    pub values: HashMap<String, HashMap<String, SubmittedValue>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubmittedValue {
+    pub value: Option<String>,
+    pub selected_option: Option<SubmittedOption>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubmittedOption {
+    pub value: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CreateIssueSubmission {
+    pub owner: String,
+    pub repo: String,
+    pub new_issue: NewIssue,
+    // where the result is told, empty for a direct message
+    pub channel_id: String,
+    // of the modal, so that the result is in the same language
+    pub locale: Locale,
+}
+
+// private_metadata of the modal
+// the locale is kept so that a submission is answered without asking Slack for it again
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CreateIssueMetadata {
+    channel_id: String,
+    locale: String,
+}
+
+fn priority_option(priority: Priority) -> Option<SlackOptionObject> {
+    let label_name = priority.label_name()?;
+    Some(SlackOptionObject::new(
+        label_name.to_string(),
+        label_name.to_string(),
+    ))
+}
+
+// the first line of the message is used as the title, the whole message as the body
+pub fn create_issue_modal(
+    recent_repositories: &[String],
+    channel_id: &str,
+    initial_text: Option<&str>,
+    locale: Locale,
+) -> SlackModalView {
+    let metadata = CreateIssueMetadata {
+        channel_id: channel_id.to_string(),
+        locale: locale.code().to_string(),
+    };
+    let mut view = SlackModalView::new(
+        CREATE_ISSUE_MODAL_CALLBACK_ID.to_string(),
+        text(locale, "modal.create_issue.title"),
+        text(locale, "modal.submit"),
+        text(locale, "modal.cancel"),
+        serde_json::to_string(&metadata).unwrap_or_default(),
+    );
+
+    let repository_element = if recent_repositories.is_empty() {
+        SlackBlockElement::plain_text_input(INPUT_ACTION_ID.to_string(), false, None)
+    } else {
+        let options = recent_repositories
+            .iter()
+            .take(100)
+            .map(|name| SlackOptionObject::new(name.clone(), name.clone()))
+            .collect();
+        SlackBlockElement::static_select(
+            INPUT_ACTION_ID.to_string(),
+            text(locale, "modal.repository.placeholder"),
+            options,
+        )
+    };
+    view.add_input_block(
+        REPOSITORY_BLOCK_ID.to_string(),
+        text(locale, "modal.repository.label"),
+        repository_element,
+        false,
+    );
+
+    let initial_title = initial_text
+        .and_then(|text| text.lines().next())
+        .map(|line| {
+            line.chars()
+                .take(MAX_INITIAL_TITLE_CHARS)
+                .collect::<String>()
+        });
+    view.add_input_block(
+        TITLE_BLOCK_ID.to_string(),
+        text(locale, "modal.title.label"),
+        SlackBlockElement::plain_text_input(INPUT_ACTION_ID.to_string(), false, initial_title),
+        false,
+    );
+    view.add_input_block(
+        BODY_BLOCK_ID.to_string(),
+        text(locale, "modal.body.label"),
+        SlackBlockElement::plain_text_input(
+            INPUT_ACTION_ID.to_string(),
+            true,
+            initial_text.map(|text| text.chars().take(MAX_INITIAL_BODY_CHARS).collect()),
+        ),
+        true,
+    );
+
+    let priority_options = [Priority::High, Priority::Medium, Priority::Low]
+        .into_iter()
+        .filter_map(priority_option)
+        .collect();
+    view.add_input_block(
+        PRIORITY_BLOCK_ID.to_string(),
+        text(locale, "modal.priority.label"),
+        SlackBlockElement::static_select(
+            INPUT_ACTION_ID.to_string(),
+            text(locale, "modal.priority.placeholder"),
+            priority_options,
+        ),
+        true,
+    );
+    view.add_input_block(
+        ASSIGNEE_BLOCK_ID.to_string(),
+        text(locale, "modal.assignee.label"),
+        SlackBlockElement::plain_text_input(INPUT_ACTION_ID.to_string(), false, None),
+        true,
+    );
+
+    view
+}
+
+// the modal is opened at once, as trigger_id expires 3 seconds after the interaction
+// then it is updated with the repositories of the assigned issues, in the locale of the user
+pub async fn open_create_issue_modal(
+    context: &SlackAppContext,
+    trigger_id: &str,
+    channel_id: &str,
+    user_id: &str,
+    initial_text: Option<&str>,
+) -> Result<(), SlackApiError> {
+    let configured_locale = context.locales.user_locale(user_id, None, Some(channel_id));
+    let view = create_issue_modal(&[], channel_id, initial_text, configured_locale);
+    let opened_view = open_view(
+        &context.slack_api_addr,
+        &context.slack_token,
+        trigger_id,
+        &view,
+    )
+    .await?;
+
+    let locale = resolve_user_locale(
+        &context.slack_api_addr,
+        Some(&context.slack_token),
+        &context.locales,
+        user_id,
+        Some(channel_id),
+    )
+    .await;
+    let my_issues = get_my_issues(
+        context.github_api_addr.clone(),
+        context.github_token.clone(),
+    )
+    .await;
+    let recent_repositories = match my_issues {
+        Ok(issues) => collect_repository_names(&issues),
+        Err(_) => vec![],
+    };
+    if recent_repositories.is_empty() && locale == configured_locale {
+        return Ok(());
+    }
+    let view = create_issue_modal(&recent_repositories, channel_id, initial_text, locale);
+    update_view(
+        &context.slack_api_addr,
+        &context.slack_token,
+        &opened_view,
+        &view,
+    )
+    .await
+    .map(|_| ())
+}
+
+fn get_submitted_value(view: &SubmittedView, block_id: &str) -> Option<String> {
+    let value = view.state.values.get(block_id)?.get(INPUT_ACTION_ID)?;
+    let value = match &value.selected_option {
+        Some(option) => option.value.clone(),
+        None => value.value.clone()?,
+    };
+    let value = value.trim().to_string();
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+// validation errors are keyed by block_id, as view_submission responses expect
+// they are in the locale of the modal
+pub fn parse_create_issue_submission(
+    view: &SubmittedView,
+) -> Result<CreateIssueSubmission, HashMap<String, String>> {
+    let metadata: CreateIssueMetadata =
+        serde_json::from_str(&view.private_metadata).unwrap_or_default();
+    let locale = Locale::parse(&metadata.locale).unwrap_or_default();
+    let mut errors = HashMap::new();
+
+    let repository = get_submitted_value(view, REPOSITORY_BLOCK_ID).unwrap_or_default();
+    let owner_and_repo = repository
+        .split_once('/')
+        .filter(|(owner, repo)| !owner.is_empty() && !repo.is_empty() && !repo.contains('/'));
+    if owner_and_repo.is_none() {
+        errors.insert(
+            REPOSITORY_BLOCK_ID.to_string(),
+            text(locale, "modal.repository.invalid"),
+        );
+    }
+    let title = get_submitted_value(view, TITLE_BLOCK_ID);
+    if title.is_none() {
+        errors.insert(
+            TITLE_BLOCK_ID.to_string(),
+            text(locale, "modal.title.required"),
+        );
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let (owner, repo) = owner_and_repo.unwrap_or_default();
+    Ok(CreateIssueSubmission {
+        owner: owner.to_string(),
+        repo: repo.to_string(),
+        new_issue: NewIssue {
+            title: title.unwrap_or_default(),
+            body: get_submitted_value(view, BODY_BLOCK_ID),
+            labels: get_submitted_value(view, PRIORITY_BLOCK_ID)
+                .into_iter()
+                .collect(),
+            assignees: get_submitted_value(view, ASSIGNEE_BLOCK_ID)
+                .into_iter()
+                .collect(),
+        },
+        channel_id: metadata.channel_id,
+        locale,
+    })
+}
+
+// the body ends with who asked for the issue in Slack, as the issue is created by the bot
+fn requested_issue_body(body: Option<&str>, user: &InteractionUser, locale: Locale) -> String {
+    let requester = match &user.username {
+        Some(username) => format!("@{username} ({})", user.id),
+        None => user.id.clone(),
+    };
+    let requested_by = text_with(locale, "modal.requested_by", &[("user", &requester)]);
+    match body {
+        Some(body) => format!("{body}\n\n---\n{requested_by}"),
+        None => requested_by,
+    }
+}
+
+// create the issue and tell the user the link
+// the reply is ephemeral in the channel, or a direct message if the bot is not in it
+pub async fn submit_create_issue(
+    context: &SlackAppContext,
+    user: &InteractionUser,
+    submission: &CreateIssueSubmission,
+) {
+    let user_id = user.id.as_str();
+    let channel_id = submission.channel_id.as_str();
+    let locale = submission.locale;
+    let new_issue = NewIssue {
+        title: submission.new_issue.title.clone(),
+        body: Some(requested_issue_body(
+            submission.new_issue.body.as_deref(),
+            user,
+            locale,
+        )),
+        labels: submission.new_issue.labels.clone(),
+        assignees: submission.new_issue.assignees.clone(),
+    };
+    let text = match create_issue(
+        &context.github_api_addr,
+        &context.github_token,
+        &submission.owner,
+        &submission.repo,
+        &new_issue,
+    )
+    .await
+    {
+        Ok(issue) => {
+            let issue_link = link(
+                &issue.html_url,
+                &format!(
+                    "{}/{}#{} {}",
+                    submission.owner, submission.repo, issue.number, issue.title
+                ),
+            );
+            text_with(locale, "modal.issue_created", &[("link", &issue_link)])
+        }
+        Err(e) => text_with(
+            locale,
+            "modal.issue_failed",
+            &[("error", &escape_text(&e.message))],
+        ),
+    };
+
+    if !channel_id.is_empty() {
+        let res = post_ephemeral(
+            &context.slack_api_addr,
+            &context.slack_token,
+            channel_id,
+            user_id,
+            &text,
+        )
+        .await;
+        if res.is_ok() {
+            return;
+        }
+    }
+    let mut message_blocks = SlackMessageBlocks::default();
+    message_blocks.add_text_block(text.clone());
+    let _ = post_message(
+        &context.slack_api_addr,
+        &context.slack_token,
+        user_id,
+        &text,
+        &message_blocks,
+    )
+    .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slack::message::{PlainTextInputElement, StaticSelectElement};
+
+    fn submitted_view(values: serde_json::Value) -> SubmittedView {
+        serde_json::from_value(serde_json::json!({
+            "callback_id": CREATE_ISSUE_MODAL_CALLBACK_ID,
+            "private_metadata": r#"{"channel_id": "C1", "locale": "en"}"#,
+            "state": {"values": values},
+        }))
+        .unwrap()
+    }
+
+    fn static_select(view: &SlackModalView, index: usize) -> &StaticSelectElement {
+        match view.blocks[index].element() {
+            Some(SlackBlockElement::StaticSelect(element)) => element,
+            other => panic!("unexpected element: {other:?}"),
+        }
+    }
+
+    fn plain_text_input(view: &SlackModalView, index: usize) -> &PlainTextInputElement {
+        match view.blocks[index].element() {
+            Some(SlackBlockElement::PlainTextInput(element)) => element,
+            other => panic!("unexpected element: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_create_issue_modal() {
+        let repositories = vec!["owner/repo".to_string()];
+        let view = create_issue_modal(
+            &repositories,
+            "C1",
+            Some("first line\nsecond line"),
+            Locale::Ja,
+        );
+        assert_eq!(view.callback_id, CREATE_ISSUE_MODAL_CALLBACK_ID);
+        assert_eq!(
+            view.private_metadata,
+            r#"{"channel_id":"C1","locale":"ja"}"#
+        );
+        assert_eq!(view.blocks.len(), 5);
+        assert_eq!(view.title.text, "GitHub Issue を作成");
+        assert_eq!(view.submit.text, "送信");
+
+        let repository = static_select(&view, 0);
+        assert_eq!(repository.options[0].value, "owner/repo");
+        let title = plain_text_input(&view, 1);
+        assert_eq!(title.initial_value.as_deref(), Some("first line"));
+        let body = plain_text_input(&view, 2);
+        assert_eq!(
+            body.initial_value.as_deref(),
+            Some("first line\nsecond line")
+        );
+        let priority = static_select(&view, 3);
+        assert_eq!(priority.options.len(), 3);
+    }
+
+    #[test]
+    fn test_create_issue_modal_without_repositories() {
+        let view = create_issue_modal(&[], "C1", None, Locale::En);
+        assert_eq!(view.title.text, "Create a GitHub issue");
+        assert_eq!(view.close.text, "Cancel");
+        plain_text_input(&view, 0);
+        let title = plain_text_input(&view, 1);
+        assert_eq!(title.initial_value, None);
+    }
+
+    #[test]
+    fn test_create_issue_modal_long_text() {
+        let initial_text = "a".repeat(MAX_INITIAL_BODY_CHARS + 1);
+        let view = create_issue_modal(&[], "C1", Some(&initial_text), Locale::En);
+        let title = plain_text_input(&view, 1);
+        assert_eq!(
+            title
+                .initial_value
+                .as_ref()
+                .map(|title| title.chars().count()),
+            Some(MAX_INITIAL_TITLE_CHARS)
+        );
+        let body = plain_text_input(&view, 2);
+        assert_eq!(
+            body.initial_value.as_ref().map(|body| body.chars().count()),
+            Some(MAX_INITIAL_BODY_CHARS)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_open_create_issue_modal() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let open_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/views.open")
+                .json_body_partial(r#"{"trigger_id": "T1"}"#)
+                .body_contains("plain_text_input");
+            then.status(200)
+                .json_body(serde_json::json!({"ok": true, "view": {"id": "V1", "hash": "h1"}}));
+        });
+        let issues_mock = server.mock(|when, then| {
+            when.method(GET).path("/issues");
+            then.status(200)
+                .json_body(serde_json::json!([crate::fixtures::issue(
+                    1, "title", None
+                )]));
+        });
+        let update_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/views.update")
+                .json_body_partial(r#"{"view_id": "V1", "hash": "h1"}"#)
+                .body_contains("owner/name");
+            then.status(200)
+                .json_body(serde_json::json!({"ok": true, "view": {"id": "V1", "hash": "h2"}}));
+        });
+        let mock_addr = format!("http://{}", server.address());
+        let context = SlackAppContext {
+            slack_api_addr: mock_addr.clone(),
+            slack_token: "slack_token".to_string(),
+            github_api_addr: mock_addr,
+            github_token: "github_token".to_string(),
+            state_dir: ".".to_string(),
+            locales: crate::i18n::LocaleSettings {
+                users: HashMap::from([("U1".to_string(), Locale::En)]),
+                ..Default::default()
+            },
+            template: crate::slack::template::DigestTemplate::default(),
+            static_users: HashMap::new(),
+            user_tokens: HashMap::new(),
+        };
+
+        open_create_issue_modal(&context, "T1", "C1", "U1", None)
+            .await
+            .unwrap();
+        open_mock.assert();
+        issues_mock.assert();
+        update_mock.assert();
+    }
+
+    #[test]
+    fn test_parse_create_issue_submission() {
+        let view = submitted_view(serde_json::json!({
+            "repository": {"input": {"type": "static_select", "selected_option": {"value": "owner/repo"}}},
+            "title": {"input": {"type": "plain_text_input", "value": " title "}},
+            "body": {"input": {"type": "plain_text_input", "value": null}},
+            "priority": {"input": {"type": "static_select", "selected_option": {"value": "Priority: High"}}},
+            "assignee": {"input": {"type": "plain_text_input", "value": "octocat"}},
+        }));
+        let submission = parse_create_issue_submission(&view).unwrap();
+        assert_eq!(
+            submission,
+            CreateIssueSubmission {
+                owner: "owner".to_string(),
+                repo: "repo".to_string(),
+                new_issue: NewIssue {
+                    title: "title".to_string(),
+                    body: None,
+                    labels: vec!["Priority: High".to_string()],
+                    assignees: vec!["octocat".to_string()],
+                },
+                channel_id: "C1".to_string(),
+                locale: Locale::En,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_create_issue_submission_errors() {
+        let view = submitted_view(serde_json::json!({
+            "repository": {"input": {"type": "plain_text_input", "value": "invalid"}},
+            "title": {"input": {"type": "plain_text_input", "value": ""}},
+        }));
+        let errors = parse_create_issue_submission(&view).unwrap_err();
+        assert_eq!(
+            errors[REPOSITORY_BLOCK_ID],
+            "Enter the repository as owner/repo"
+        );
+        assert_eq!(errors[TITLE_BLOCK_ID], "Enter a title");
+    }
+
+    #[tokio::test]
+    async fn test_submit_create_issue() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let create_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/repos/owner/repo/issues")
+                .json_body_partial(
+                    r#"{"body": "body\n\n---\nRequested by @octocat (U1) on Slack"}"#,
+                );
+            then.status(201).json_body(serde_json::json!({
+                "number": 7,
+                "title": "title",
+                "html_url": "https://github.com/owner/repo/issues/7",
+                "state": "open",
+                "labels": [],
+            }));
+        });
+        let ephemeral_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/chat.postEphemeral")
+                .json_body_partial(r#"{"channel": "C1", "user": "U1"}"#)
+                .body_contains("Created the issue: <https://github.com/owner/repo/issues/7|");
+            then.status(200).json_body(serde_json::json!({"ok": true}));
+        });
+        let mock_addr = format!("http://{}", server.address());
+        let context = SlackAppContext {
+            slack_api_addr: mock_addr.clone(),
+            slack_token: "slack_token".to_string(),
+            github_api_addr: mock_addr,
+            github_token: "github_token".to_string(),
+            state_dir: ".".to_string(),
+            locales: crate::i18n::LocaleSettings::default(),
+            template: crate::slack::template::DigestTemplate::default(),
+            static_users: HashMap::new(),
+            user_tokens: HashMap::new(),
+        };
+        let submission = CreateIssueSubmission {
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            new_issue: NewIssue {
+                title: "title".to_string(),
+                body: Some("body".to_string()),
+                ..Default::default()
+            },
+            channel_id: "C1".to_string(),
+            locale: Locale::En,
+        };
+        let user = InteractionUser {
+            id: "U1".to_string(),
+            username: Some("octocat".to_string()),
+        };
+
+        submit_create_issue(&context, &user, &submission).await;
+        create_mock.assert();
+        ephemeral_mock.assert();
+    }
+}
@@ -0,0 +1,160 @@
+// mrkdwn text of Slack
+// https://api.slack.com/reference/surfaces/formatting
+
+use crate::render::{DigestRenderer, GroupView, IssueView};
+
+// Slack has no escape for the formatting markers, so a zero width space
+// is put right after them, which keeps them from opening bold, italic and so on
+const ZERO_WIDTH_SPACE: char = '\u{200B}';
+const FORMATTING_MARKERS: [char; 4] = ['*', '_', '~', '`'];
+
+// mentions which ping many people even without the <!...> syntax
+const BROADCAST_MENTIONS: [&str; 4] = ["@channel", "@here", "@everyone", "@group"];
+
+// escape the control characters, the only escapes Slack understands
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+// make broadcast mentions like "@here" plain text
+// "<!channel>" is already harmless after `escape`
+pub fn neutralize_mentions(text: &str) -> String {
+    let mut text = text.to_string();
+    for mention in BROADCAST_MENTIONS {
+        let (at, name) = mention.split_at(1);
+        text = text.replace(mention, &format!("{at}{ZERO_WIDTH_SPACE}{name}"));
+    }
+    text
+}
+
+fn neutralize_formatting(text: &str) -> String {
+    let mut neutralized = String::with_capacity(text.len());
+    for c in text.chars() {
+        neutralized.push(c);
+        if FORMATTING_MARKERS.contains(&c) {
+            neutralized.push(ZERO_WIDTH_SPACE);
+        }
+    }
+    neutralized
+}
+
+// user-controlled text, e.g. issue titles and label names, shown as it is
+pub fn escape_text(text: &str) -> String {
+    neutralize_mentions(&neutralize_formatting(&escape(text)))
+}
+
+// characters which end the URL part of a link
+fn escape_url(url: &str) -> String {
+    url.replace('&', "&amp;")
+        .replace('<', "%3C")
+        .replace('>', "%3E")
+        .replace('|', "%7C")
+        .replace(' ', "%20")
+}
+
+// `text`, as a chip
+// nothing is formatted inside, but a backtick would end it, so it is replaced with a look-alike
+pub fn code(text: &str) -> String {
+    let text = neutralize_mentions(&escape(text)).replace('`', "\u{02CB}");
+    format!("`{text}`")
+}
+
+// <url|label>
+pub fn link(url: &str, label: &str) -> String {
+    // a "|" in the label would be taken as the separator by some clients
+    let label = escape_text(label).replace('|', "｜");
+    format!("<{}|{}>", escape_url(url), label)
+}
+
+// the lines of the Slack digest
+pub struct MrkdwnRenderer;
+
+impl DigestRenderer for MrkdwnRenderer {
+    // the same line as the default template renders, e.g. "<url|title>(<url|name>) `bug` (期限: 明日)"
+    fn render_issue(&self, issue: &IssueView) -> String {
+        let repository = &issue.issue.repository;
+        let mut parts = vec![format!(
+            "{}({})",
+            link(&issue.issue.html_url, &issue.issue.title),
+            link(&repository.html_url, &repository.name)
+        )];
+        parts.extend(issue.labels.iter().map(|label| code(&label.name)));
+        parts.extend(issue.due_label.clone());
+        parts.join(" ")
+    }
+
+    // e.g. "🔴 *優先度: 高*"
+    fn render_group_header(&self, group: &GroupView) -> String {
+        match group.emoji.as_str() {
+            "" => format!("*{}*", group.title),
+            emoji => format!("{emoji} *{}*", group.title),
+        }
+    }
+
+    fn render_title(&self, title: &str) -> String {
+        format!("*{}*", escape(title))
+    }
+
+    fn render_text(&self, text: &str) -> String {
+        escape(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a & b <c> d"), "a &amp; b &lt;c&gt; d");
+        // & is escaped first so entities are not escaped twice
+        assert_eq!(escape("&lt;"), "&amp;lt;");
+    }
+
+    #[test]
+    fn test_neutralize_mentions() {
+        assert_eq!(neutralize_mentions("hi @here"), "hi @\u{200B}here");
+        assert_eq!(neutralize_mentions("@channel!"), "@\u{200B}channel!");
+        assert_eq!(neutralize_mentions("user@example.com"), "user@example.com");
+    }
+
+    #[test]
+    fn test_escape_text() {
+        assert_eq!(escape_text("<!channel>"), "&lt;!channel&gt;");
+        assert_eq!(escape_text("*bold*"), "*\u{200B}bold*\u{200B}");
+        assert_eq!(
+            escape_text("fix `a` @here"),
+            "fix `\u{200B}a`\u{200B} @\u{200B}here"
+        );
+        assert_eq!(escape_text("タイトル"), "タイトル");
+    }
+
+    #[test]
+    fn test_code() {
+        assert_eq!(code("bug"), "`bug`");
+        assert_eq!(
+            code("a`b <c> @here"),
+            "`a\u{02CB}b &lt;c&gt; @\u{200B}here`"
+        );
+        // formatting markers are harmless inside
+        assert_eq!(code("*wip*"), "`*wip*`");
+    }
+
+    #[test]
+    fn test_link() {
+        assert_eq!(
+            link("https://github.com/o/r/issues/1", "title"),
+            "<https://github.com/o/r/issues/1|title>"
+        );
+        assert_eq!(
+            link("https://example.com/?a=1&b=2", "a > b | c"),
+            "<https://example.com/?a=1&amp;b=2|a &gt; b ｜ c>"
+        );
+        assert_eq!(
+            link("https://example.com/a|b>c", "x"),
+            "<https://example.com/a%7Cb%3Ec|x>"
+        );
+    }
+}
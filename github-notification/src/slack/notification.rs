@@ -1,21 +1,310 @@
+use super::api::{
+    delete_message, get_permalink, post_message, post_thread_reply, update_message,
+    SlackPostedMessage,
+};
+use super::history::{SlackMessageHistory, SlackMessageRecord};
+use super::layout::{split_message_blocks, MAX_BLOCKS_PER_MESSAGE, MAX_MESSAGES_PER_DIGEST};
 use super::message::SlackMessageBlocks;
+use super::mrkdwn::link;
+use super::payload::SlackThreadDigest;
+use crate::errors::SlackApiError;
+use crate::i18n::{plural_text, text, Locale};
+use crate::store::{load_json, save_json};
+use std::path::Path;
 
-pub async fn notify_by_slack(webhook_url: String, message_blocks: SlackMessageBlocks) {
+// a digest longer than a message is sent as several messages
+// sending stops at the first failure, so the messages stay in order
+pub async fn notify_by_slack(
+    webhook_url: String,
+    message_blocks: SlackMessageBlocks,
+    locale: Locale,
+) -> Result<(), SlackApiError> {
     let client = reqwest::Client::new();
-    let res = client.post(webhook_url).json(&message_blocks).send().await;
-    match res {
-        Ok(res) => {
+    for message in split_message_blocks(message_blocks, MAX_MESSAGES_PER_DIGEST, locale) {
+        let res = client.post(&webhook_url).json(&message).send().await;
+        let error_message = match res {
             // if status code is 200, it means success
-            if res.status() == 200 {
+            Ok(res) if res.status() == 200 => {
                 println!("Notify by Slack OK");
-            } else {
-                println!("Notify by Slack Error: {}", res.status());
+                continue;
             }
+            Ok(res) => format!("Notify by Slack Error: {}", res.status().as_u16()),
+            Err(err) => format!("Notify by Slack Error: {err}"),
+        };
+        println!("{error_message}");
+        return Err(SlackApiError {
+            message: error_message,
+        });
+    }
+    Ok(())
+}
+
+// the link to the previous digest goes at the end, unless the digest is already at its cap
+fn add_previous_digest_link(
+    messages: &mut Vec<SlackMessageBlocks>,
+    permalink: &str,
+    locale: Locale,
+) {
+    let text = link(permalink, &text(locale, "digest.previous"));
+    let has_room = messages
+        .last()
+        .is_some_and(|last| last.blocks.len() < MAX_BLOCKS_PER_MESSAGE);
+    if has_room {
+        messages.last_mut().unwrap().add_text_block(text);
+    } else if messages.len() < MAX_MESSAGES_PER_DIGEST {
+        let mut message = SlackMessageBlocks::default();
+        message.add_text_block(text);
+        messages.push(message);
+    }
+}
+
+// the messages of a digest, the first at the top level and the rest in its thread
+struct DigestThread {
+    messages: Vec<SlackMessageBlocks>,
+    // in the thread layout the link to the previous digest goes in the summary
+    link_in_parent: bool,
+}
+
+impl DigestThread {
+    fn add_previous_digest_link(&mut self, permalink: &str, locale: Locale) {
+        if self.link_in_parent {
+            let text = link(permalink, &text(locale, "digest.previous"));
+            self.messages[0].add_text_block(text);
+        } else {
+            add_previous_digest_link(&mut self.messages, permalink, locale);
         }
-        Err(err) => {
-            println!("Notify by Slack Error: {err}");
+    }
+}
+
+// continuation messages of a digest are posted in the thread of its first message, in order
+// existing replies are updated in place and the ones no longer needed are deleted
+// once a reply has to be posted anew, the rest follow it at the end of the thread,
+// and posting stops at the first failure so that no message gets ahead of a missing one
+// returns the ts of the replies which are in the thread now
+async fn sync_thread_replies(
+    slack_api_addr: &str,
+    token: &str,
+    channel: &str,
+    thread_ts: &str,
+    existing_replies: &[String],
+    messages: &[SlackMessageBlocks],
+    fallback_text: &str,
+) -> Vec<String> {
+    let mut replies = Vec::new();
+    let mut appending = false;
+    for (i, message) in messages.iter().enumerate() {
+        if !appending {
+            if let Some(ts) = existing_replies.get(i) {
+                let res =
+                    update_message(slack_api_addr, token, channel, ts, fallback_text, message)
+                        .await;
+                if let Ok(updated) = res {
+                    replies.push(updated.ts);
+                    continue;
+                }
+            }
+            appending = true;
+        }
+        let res = post_thread_reply(
+            slack_api_addr,
+            token,
+            channel,
+            thread_ts,
+            fallback_text,
+            message,
+        )
+        .await;
+        match res {
+            Ok(posted) => replies.push(posted.ts),
+            Err(err) => {
+                println!("Post Slack Thread Reply Error: {err}");
+                break;
+            }
+        }
+    }
+    for ts in existing_replies.iter().filter(|ts| !replies.contains(ts)) {
+        if let Err(err) = delete_message(slack_api_addr, token, channel, ts).await {
+            println!("Delete Slack Message Error: {err}");
         }
     }
+    replies
+}
+
+// if the previous digest in the channel is recent enough, it is updated in place,
+// otherwise a new message is posted with a link to the previous one
+// when some replies could not be posted, the first message says so and an error is returned,
+// the missing replies are posted by the next update of the digest
+async fn notify_digest_thread(
+    slack_api_addr: &str,
+    token: &str,
+    channel: &str,
+    mut thread: DigestThread,
+    history_path: &Path,
+    update_window_secs: i64,
+    locale: Locale,
+) -> Result<SlackPostedMessage, SlackApiError> {
+    let mut history: SlackMessageHistory = load_json(history_path);
+    let now = chrono::Utc::now().timestamp();
+    // fallback text for notifications of clients which can't show blocks
+    let fallback_text = text(locale, "digest.title");
+
+    let mut updated = None;
+    if let Some(record) = history
+        .latest_recent(channel, now, update_window_secs)
+        .cloned()
+    {
+        let res = update_message(
+            slack_api_addr,
+            token,
+            &record.channel,
+            &record.ts,
+            &fallback_text,
+            &thread.messages[0],
+        )
+        .await;
+        match res {
+            Ok(message) => {
+                println!("Update Slack Message OK");
+                updated = Some((message, record.replies));
+            }
+            // the previous message may have been deleted, so post a new one
+            Err(err) => println!("Update Slack Message Error: {err}"),
+        }
+    }
+
+    let (posted, existing_replies) = match updated {
+        Some(updated) => updated,
+        None => {
+            if let Some(record) = history.latest(channel) {
+                if let Ok(permalink) =
+                    get_permalink(slack_api_addr, token, &record.channel, &record.ts).await
+                {
+                    thread.add_previous_digest_link(&permalink, locale);
+                }
+            }
+            let posted = post_message(
+                slack_api_addr,
+                token,
+                channel,
+                &fallback_text,
+                &thread.messages[0],
+            )
+            .await?;
+            println!("Post Slack Message OK");
+            history.push(SlackMessageRecord {
+                channel: posted.channel.clone(),
+                ts: posted.ts.clone(),
+                posted_at: now,
+                replies: vec![],
+            });
+            (posted, vec![])
+        }
+    };
+
+    let replies = sync_thread_replies(
+        slack_api_addr,
+        token,
+        &posted.channel,
+        &posted.ts,
+        &existing_replies,
+        &thread.messages[1..],
+        &fallback_text,
+    )
+    .await;
+    let reply_count = thread.messages.len() - 1;
+    let unposted_count = reply_count - replies.len();
+    if let Some(record) = history.find_mut(&posted.channel, &posted.ts) {
+        record.replies = replies;
+    }
+    if let Err(err) = save_json(history_path, &history) {
+        println!("{err}");
+    }
+
+    if unposted_count > 0 {
+        let parent = &mut thread.messages[0];
+        if parent.blocks.len() < MAX_BLOCKS_PER_MESSAGE {
+            parent.add_context_block(plural_text(
+                locale,
+                "digest.thread.failed",
+                unposted_count as i64,
+            ));
+            let _ = update_message(
+                slack_api_addr,
+                token,
+                &posted.channel,
+                &posted.ts,
+                &fallback_text,
+                parent,
+            )
+            .await;
+        }
+        let error_message = format!(
+            "Post Slack Thread Reply Error: {unposted_count} of {reply_count} replies were not posted"
+        );
+        println!("{error_message}");
+        return Err(SlackApiError {
+            message: error_message,
+        });
+    }
+    Ok(posted)
+}
+
+// notify by Slack Web API
+// a digest over the limits of a message continues in the thread
+pub async fn notify_by_slack_api(
+    slack_api_addr: &str,
+    token: &str,
+    channel: &str,
+    message_blocks: SlackMessageBlocks,
+    history_path: &Path,
+    update_window_secs: i64,
+    locale: Locale,
+) -> Result<SlackPostedMessage, SlackApiError> {
+    let thread = DigestThread {
+        messages: split_message_blocks(message_blocks, MAX_MESSAGES_PER_DIGEST, locale),
+        link_in_parent: false,
+    };
+    notify_digest_thread(
+        slack_api_addr,
+        token,
+        channel,
+        thread,
+        history_path,
+        update_window_secs,
+        locale,
+    )
+    .await
+}
+
+// notify by Slack Web API in the thread layout
+// the summary is the top level message and each group is a reply, split when too long
+pub async fn notify_by_slack_api_in_thread(
+    slack_api_addr: &str,
+    token: &str,
+    channel: &str,
+    digest: SlackThreadDigest,
+    history_path: &Path,
+    update_window_secs: i64,
+    locale: Locale,
+) -> Result<SlackPostedMessage, SlackApiError> {
+    let mut messages = vec![digest.summary];
+    for group in digest.groups {
+        messages.extend(split_message_blocks(group, MAX_MESSAGES_PER_DIGEST, locale));
+    }
+    let thread = DigestThread {
+        messages,
+        link_in_parent: true,
+    };
+    notify_digest_thread(
+        slack_api_addr,
+        token,
+        channel,
+        thread,
+        history_path,
+        update_window_secs,
+        locale,
+    )
+    .await
 }
 
 #[cfg(test)]
@@ -37,7 +326,412 @@ mod tests {
         let mock_webhook_url = format!("http://{}", server.address());
         let mock_message_blocks = SlackMessageBlocks::default();
 
-        notify_by_slack(mock_webhook_url, mock_message_blocks).await;
+        let res = notify_by_slack(mock_webhook_url, mock_message_blocks, Locale::Ja).await;
         mock.assert();
+        assert!(res.is_ok());
+    }
+
+    fn history_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir()
+            .join("github_notification_notification_test")
+            .join(name);
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[tokio::test]
+    async fn test_notify_by_slack_api_posts_new_message() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let post_mock = server.mock(|when, then| {
+            when.method(POST).path("/chat.postMessage");
+            then.status(200)
+                .json_body(serde_json::json!({"ok": true, "channel": "C1", "ts": "1.0"}));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+        let path = history_path("post_new.json");
+
+        let posted = notify_by_slack_api(
+            &mock_api_addr,
+            "token",
+            "C1",
+            SlackMessageBlocks::default(),
+            &path,
+            3600,
+            Locale::Ja,
+        )
+        .await;
+        post_mock.assert();
+        assert_eq!(posted.unwrap().ts, "1.0");
+        let history: SlackMessageHistory = load_json(&path);
+        assert_eq!(history.latest("C1").unwrap().ts, "1.0");
+    }
+
+    #[tokio::test]
+    async fn test_notify_by_slack_api_updates_recent_message() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let update_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/chat.update")
+                .json_body_partial(r#"{"channel": "C1", "ts": "1.0"}"#);
+            then.status(200)
+                .json_body(serde_json::json!({"ok": true, "channel": "C1", "ts": "1.0"}));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+        let path = history_path("update_recent.json");
+        let mut history = SlackMessageHistory::default();
+        history.push(SlackMessageRecord {
+            channel: "C1".to_string(),
+            ts: "1.0".to_string(),
+            posted_at: chrono::Utc::now().timestamp(),
+            replies: vec![],
+        });
+        save_json(&path, &history).unwrap();
+
+        let updated = notify_by_slack_api(
+            &mock_api_addr,
+            "token",
+            "C1",
+            SlackMessageBlocks::default(),
+            &path,
+            3600,
+            Locale::Ja,
+        )
+        .await;
+        update_mock.assert();
+        assert_eq!(updated.unwrap().ts, "1.0");
+    }
+
+    #[tokio::test]
+    async fn test_notify_by_slack_api_links_old_message() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let permalink_mock = server.mock(|when, then| {
+            when.method(GET).path("/chat.getPermalink");
+            then.status(200).json_body(serde_json::json!({
+                "ok": true,
+                "permalink": "https://example.slack.com/p1"
+            }));
+        });
+        let post_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/chat.postMessage")
+                .body_contains("<https://example.slack.com/p1|前回のタスク一覧>");
+            then.status(200)
+                .json_body(serde_json::json!({"ok": true, "channel": "C1", "ts": "2.0"}));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+        let path = history_path("link_old.json");
+        let mut history = SlackMessageHistory::default();
+        history.push(SlackMessageRecord {
+            channel: "C1".to_string(),
+            ts: "1.0".to_string(),
+            posted_at: 0,
+            replies: vec![],
+        });
+        save_json(&path, &history).unwrap();
+
+        let posted = notify_by_slack_api(
+            &mock_api_addr,
+            "token",
+            "C1",
+            SlackMessageBlocks::default(),
+            &path,
+            3600,
+            Locale::Ja,
+        )
+        .await;
+        permalink_mock.assert();
+        post_mock.assert();
+        assert_eq!(posted.unwrap().ts, "2.0");
+        let history: SlackMessageHistory = load_json(&path);
+        assert_eq!(history.records.len(), 2);
+    }
+
+    fn long_message_blocks(count: usize) -> SlackMessageBlocks {
+        let mut message_blocks = SlackMessageBlocks::default();
+        for i in 0..count {
+            message_blocks.add_text_block(format!("- {i}\n"));
+        }
+        message_blocks
+    }
+
+    #[tokio::test]
+    async fn test_notify_by_slack_splits_long_digest() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method("POST").path("/");
+            then.status(200);
+        });
+
+        let mock_webhook_url = format!("http://{}", server.address());
+        notify_by_slack(mock_webhook_url, long_message_blocks(60), Locale::Ja)
+            .await
+            .unwrap();
+        mock.assert_hits(2);
+    }
+
+    #[tokio::test]
+    async fn test_notify_by_slack_api_posts_thread_replies() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let reply_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/chat.postMessage")
+                .json_body_partial(r#"{"thread_ts": "1.0"}"#);
+            then.status(200)
+                .json_body(serde_json::json!({"ok": true, "channel": "C1", "ts": "1.1"}));
+        });
+        let post_mock = server.mock(|when, then| {
+            when.method(POST).path("/chat.postMessage");
+            then.status(200)
+                .json_body(serde_json::json!({"ok": true, "channel": "C1", "ts": "1.0"}));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+        let path = history_path("thread_replies.json");
+
+        let posted = notify_by_slack_api(
+            &mock_api_addr,
+            "token",
+            "C1",
+            long_message_blocks(60),
+            &path,
+            3600,
+            Locale::Ja,
+        )
+        .await;
+        post_mock.assert();
+        reply_mock.assert();
+        assert_eq!(posted.unwrap().ts, "1.0");
+        let history: SlackMessageHistory = load_json(&path);
+        assert_eq!(history.latest("C1").unwrap().replies, vec!["1.1"]);
+    }
+
+    #[tokio::test]
+    async fn test_notify_by_slack_api_deletes_unneeded_replies() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let update_mock = server.mock(|when, then| {
+            when.method(POST).path("/chat.update");
+            then.status(200)
+                .json_body(serde_json::json!({"ok": true, "channel": "C1", "ts": "1.0"}));
+        });
+        let delete_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/chat.delete")
+                .json_body(serde_json::json!({"channel": "C1", "ts": "1.1"}));
+            then.status(200).json_body(serde_json::json!({"ok": true}));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+        let path = history_path("delete_replies.json");
+        let mut history = SlackMessageHistory::default();
+        history.push(SlackMessageRecord {
+            channel: "C1".to_string(),
+            ts: "1.0".to_string(),
+            posted_at: chrono::Utc::now().timestamp(),
+            replies: vec!["1.1".to_string()],
+        });
+        save_json(&path, &history).unwrap();
+
+        let updated = notify_by_slack_api(
+            &mock_api_addr,
+            "token",
+            "C1",
+            long_message_blocks(10),
+            &path,
+            3600,
+            Locale::Ja,
+        )
+        .await;
+        update_mock.assert_hits(1);
+        delete_mock.assert();
+        assert_eq!(updated.unwrap().ts, "1.0");
+        let history: SlackMessageHistory = load_json(&path);
+        assert!(history.latest("C1").unwrap().replies.is_empty());
+    }
+
+    #[test]
+    fn test_add_previous_digest_link() {
+        let mut messages = vec![long_message_blocks(50)];
+        add_previous_digest_link(&mut messages, "https://example.slack.com/p1", Locale::Ja);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[1].blocks[0].text().unwrap().text,
+            "<https://example.slack.com/p1|前回のタスク一覧>"
+        );
+
+        let mut messages = vec![long_message_blocks(10)];
+        add_previous_digest_link(&mut messages, "https://example.slack.com/p1", Locale::Ja);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].blocks.len(), 11);
+    }
+
+    fn thread_digest(group_count: usize) -> SlackThreadDigest {
+        let mut digest = SlackThreadDigest::default();
+        digest.summary.add_text_block("summary".to_string());
+        for i in 0..group_count {
+            let mut group = SlackMessageBlocks::default();
+            group.add_text_block(format!("group {i}"));
+            digest.groups.push(group);
+        }
+        digest
+    }
+
+    #[tokio::test]
+    async fn test_notify_by_slack_api_in_thread() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let first_reply_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/chat.postMessage")
+                .json_body_partial(r#"{"thread_ts": "1.0"}"#)
+                .body_contains("group 0");
+            then.status(200)
+                .json_body(serde_json::json!({"ok": true, "channel": "C1", "ts": "1.1"}));
+        });
+        let second_reply_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/chat.postMessage")
+                .json_body_partial(r#"{"thread_ts": "1.0"}"#)
+                .body_contains("group 1");
+            then.status(200)
+                .json_body(serde_json::json!({"ok": true, "channel": "C1", "ts": "1.2"}));
+        });
+        let summary_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/chat.postMessage")
+                .body_contains("summary");
+            then.status(200)
+                .json_body(serde_json::json!({"ok": true, "channel": "C1", "ts": "1.0"}));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+        let path = history_path("in_thread.json");
+
+        let posted = notify_by_slack_api_in_thread(
+            &mock_api_addr,
+            "token",
+            "C1",
+            thread_digest(2),
+            &path,
+            3600,
+            Locale::Ja,
+        )
+        .await;
+        summary_mock.assert();
+        first_reply_mock.assert();
+        second_reply_mock.assert();
+        assert_eq!(posted.unwrap().ts, "1.0");
+        // the replies are in the order of the groups
+        let history: SlackMessageHistory = load_json(&path);
+        assert_eq!(history.latest("C1").unwrap().replies, vec!["1.1", "1.2"]);
+    }
+
+    #[tokio::test]
+    async fn test_notify_by_slack_api_in_thread_partial_failure() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let reply_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/chat.postMessage")
+                .json_body_partial(r#"{"thread_ts": "1.0"}"#);
+            then.status(200)
+                .json_body(serde_json::json!({"ok": false, "error": "ratelimited"}));
+        });
+        let summary_mock = server.mock(|when, then| {
+            when.method(POST).path("/chat.postMessage");
+            then.status(200)
+                .json_body(serde_json::json!({"ok": true, "channel": "C1", "ts": "1.0"}));
+        });
+        let note_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/chat.update")
+                .json_body_partial(r#"{"channel": "C1", "ts": "1.0"}"#)
+                .body_contains("2 messages could not be posted in the thread");
+            then.status(200)
+                .json_body(serde_json::json!({"ok": true, "channel": "C1", "ts": "1.0"}));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+        let path = history_path("in_thread_partial_failure.json");
+
+        let res = notify_by_slack_api_in_thread(
+            &mock_api_addr,
+            "token",
+            "C1",
+            thread_digest(2),
+            &path,
+            3600,
+            Locale::En,
+        )
+        .await;
+        summary_mock.assert();
+        // the second group isn't posted ahead of the first
+        reply_mock.assert_hits(1);
+        note_mock.assert();
+        assert!(res.unwrap_err().message.contains("2 of 2"));
+        // the summary is kept, so the next run updates it and posts the groups
+        let history: SlackMessageHistory = load_json(&path);
+        let record = history.latest("C1").unwrap();
+        assert_eq!(record.ts, "1.0");
+        assert!(record.replies.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sync_thread_replies_keeps_order() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let first_update_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/chat.update")
+                .json_body_partial(r#"{"ts": "1.1"}"#);
+            then.status(200)
+                .json_body(serde_json::json!({"ok": true, "channel": "C1", "ts": "1.1"}));
+        });
+        // the second reply has been deleted
+        let second_update_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/chat.update")
+                .json_body_partial(r#"{"ts": "1.2"}"#);
+            then.status(200)
+                .json_body(serde_json::json!({"ok": false, "error": "message_not_found"}));
+        });
+        let reply_mock = server.mock(|when, then| {
+            when.method(POST).path("/chat.postMessage");
+            then.status(200)
+                .json_body(serde_json::json!({"ok": true, "channel": "C1", "ts": "1.4"}));
+        });
+        let delete_mock = server.mock(|when, then| {
+            when.method(POST).path("/chat.delete");
+            then.status(200).json_body(serde_json::json!({"ok": true}));
+        });
+        let mock_api_addr = format!("http://{}", server.address());
+        let existing_replies = vec!["1.1".to_string(), "1.2".to_string(), "1.3".to_string()];
+
+        let replies = sync_thread_replies(
+            &mock_api_addr,
+            "token",
+            "C1",
+            "1.0",
+            &existing_replies,
+            &thread_digest(3).groups,
+            "text",
+        )
+        .await;
+        first_update_mock.assert();
+        second_update_mock.assert();
+        // the third reply would come before the second one, so it is posted anew too
+        reply_mock.assert_hits(2);
+        delete_mock.assert_hits(2);
+        assert_eq!(replies, vec!["1.1", "1.4", "1.4"]);
     }
 }
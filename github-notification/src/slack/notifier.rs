@@ -0,0 +1,245 @@
+use super::layout::DigestLayout;
+use super::message::SlackMessageBlocks;
+use super::notification::{notify_by_slack, notify_by_slack_api, notify_by_slack_api_in_thread};
+use super::payload::{
+    create_interactive_payload_for_slack, create_payload_for_slack,
+    create_thread_payload_for_slack, SlackThreadDigest,
+};
+use super::template::DigestTemplate;
+use crate::digest::Digest;
+use crate::errors::NotifyError;
+use crate::i18n::Locale;
+use crate::notifier::{Notifier, SLACK_API_NOTIFIER, SLACK_WEBHOOK_NOTIFIER};
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+// how a digest looks in Slack, the same by the webhook and by the Web API
+#[derive(Debug, Clone, Default)]
+pub struct SlackDigestFormat {
+    pub locale: Locale,
+    pub template: DigestTemplate,
+    // put at the top, see mention::create_mention_text
+    pub mention: Option<String>,
+    // an overflow menu on every issue when set
+    pub snooze_days: Option<i64>,
+}
+
+impl SlackDigestFormat {
+    pub fn create_payload(&self, digest: &Digest) -> SlackMessageBlocks {
+        let issues = digest.issues.clone();
+        match self.snooze_days {
+            Some(snooze_days) => create_interactive_payload_for_slack(
+                issues,
+                self.mention.as_deref(),
+                snooze_days,
+                self.locale,
+                self.template.style(),
+                digest.generated_at,
+            ),
+            None => create_payload_for_slack(
+                issues,
+                self.mention.as_deref(),
+                self.locale,
+                &self.template,
+                digest.generated_at,
+            ),
+        }
+    }
+
+    pub fn create_thread_payload(&self, digest: &Digest) -> SlackThreadDigest {
+        create_thread_payload_for_slack(
+            digest.issues.clone(),
+            self.mention.as_deref(),
+            self.locale,
+            &self.template,
+            self.snooze_days,
+            digest.generated_at,
+        )
+    }
+}
+
+// a webhook posts the whole digest, it can't make a thread
+pub struct SlackWebhookNotifier {
+    pub webhook_url: String,
+    pub format: SlackDigestFormat,
+}
+
+#[async_trait]
+impl Notifier for SlackWebhookNotifier {
+    fn name(&self) -> &str {
+        SLACK_WEBHOOK_NOTIFIER
+    }
+
+    async fn notify(&self, digest: &Digest) -> Result<(), NotifyError> {
+        let payload = self.format.create_payload(digest);
+        notify_by_slack(self.webhook_url.clone(), payload, self.format.locale).await?;
+        Ok(())
+    }
+}
+
+pub struct SlackApiNotifier {
+    pub slack_api_addr: String,
+    pub token: String,
+    pub channel: String,
+    pub history_path: PathBuf,
+    pub update_window_secs: i64,
+    pub layout: DigestLayout,
+    pub format: SlackDigestFormat,
+}
+
+#[async_trait]
+impl Notifier for SlackApiNotifier {
+    fn name(&self) -> &str {
+        SLACK_API_NOTIFIER
+    }
+
+    async fn notify(&self, digest: &Digest) -> Result<(), NotifyError> {
+        match self.layout {
+            DigestLayout::Message => {
+                notify_by_slack_api(
+                    &self.slack_api_addr,
+                    &self.token,
+                    &self.channel,
+                    self.format.create_payload(digest),
+                    &self.history_path,
+                    self.update_window_secs,
+                    self.format.locale,
+                )
+                .await?;
+            }
+            DigestLayout::Thread => {
+                notify_by_slack_api_in_thread(
+                    &self.slack_api_addr,
+                    &self.token,
+                    &self.channel,
+                    self.format.create_thread_payload(digest),
+                    &self.history_path,
+                    self.update_window_secs,
+                    self.format.locale,
+                )
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::issue;
+    use crate::models::SortedIssues;
+    use chrono::Utc;
+
+    fn digest() -> Digest {
+        Digest::new(
+            Ok(SortedIssues {
+                priority_high_issues: vec![issue(5, "title", None)],
+                ..SortedIssues::default()
+            }),
+            Utc::now(),
+        )
+    }
+
+    #[test]
+    fn test_create_payload() {
+        let mut format = SlackDigestFormat {
+            mention: Some("<!here>".to_string()),
+            ..SlackDigestFormat::default()
+        };
+        let payload = format.create_payload(&digest());
+        assert_eq!(payload.blocks.len(), 3);
+        assert_eq!(payload.blocks[0].text().unwrap().text, "<!here>\n");
+
+        format.snooze_days = Some(3);
+        let payload = format.create_payload(&digest());
+        assert_eq!(payload.blocks[3].block_id(), Some("owner/name#5"));
+    }
+
+    #[test]
+    fn test_create_thread_payload_at_generated_at() {
+        use crate::fixtures::{issue, now};
+        use chrono::TimeZone;
+
+        let issues = SortedIssues {
+            priority_high_issues: vec![issue(
+                1,
+                "title",
+                Utc.with_ymd_and_hms(2023, 1, 9, 0, 0, 0).single(),
+            )],
+            ..SortedIssues::default()
+        };
+        let format = SlackDigestFormat {
+            locale: Locale::En,
+            ..SlackDigestFormat::default()
+        };
+        let summary_text = |generated_at| {
+            let digest = Digest::new(Ok(issues.clone()), generated_at);
+            let payload = format.create_thread_payload(&digest);
+            payload.summary.blocks[1].text().unwrap().text.clone()
+        };
+
+        // overdue is told as of the time the digest was generated
+        assert!(summary_text(now()).contains("1 overdue issue"));
+        assert!(
+            !summary_text(Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap()).contains("overdue")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_slack_webhook_notifier() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .body_contains("https://github.com/owner/name/issues/5");
+            then.status(500);
+        });
+        let notifier = SlackWebhookNotifier {
+            webhook_url: format!("http://{}", server.address()),
+            format: SlackDigestFormat::default(),
+        };
+        let res = notifier.notify(&digest()).await;
+        mock.assert();
+        assert_eq!(res.unwrap_err().message, "Notify by Slack Error: 500");
+    }
+
+    #[tokio::test]
+    async fn test_slack_api_notifier_in_thread() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let reply_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/chat.postMessage")
+                .json_body_partial(r#"{"thread_ts": "1.0"}"#)
+                .body_contains("https://github.com/owner/name/issues/5");
+            then.status(200)
+                .json_body(serde_json::json!({"ok": true, "channel": "C1", "ts": "1.1"}));
+        });
+        let summary_mock = server.mock(|when, then| {
+            when.method(POST).path("/chat.postMessage");
+            then.status(200)
+                .json_body(serde_json::json!({"ok": true, "channel": "C1", "ts": "1.0"}));
+        });
+        let history_path = std::env::temp_dir()
+            .join("github_notification_slack_notifier_test")
+            .join("history.json");
+        let _ = std::fs::remove_file(&history_path);
+        let notifier = SlackApiNotifier {
+            slack_api_addr: format!("http://{}", server.address()),
+            token: "token".to_string(),
+            channel: "C1".to_string(),
+            history_path,
+            update_window_secs: 3600,
+            layout: DigestLayout::Thread,
+            format: SlackDigestFormat::default(),
+        };
+        assert_eq!(notifier.name(), "slack-api");
+        notifier.notify(&digest()).await.unwrap();
+        summary_mock.assert();
+        reply_mock.assert();
+    }
+}
@@ -1,74 +1,155 @@
-use super::message::SlackMessageBlocks;
+use super::layout::{split_lines_with_header, DIGEST_ITEM_PREFIX, MAX_SECTION_TEXT_LENGTH};
+use super::message::{SlackBlockElement, SlackMessageBlocks, SlackOptionObject};
+use super::mrkdwn::MrkdwnRenderer;
+use super::style::DigestStyle;
+use super::template::{DigestTemplate, TemplateSection};
 use crate::errors::GetIssueError;
-use crate::models::{Issue, SortedIssues};
+use crate::i18n::{plural_text, text, text_with, Locale};
+use crate::models::{IssueRef, SortedIssues};
+use crate::render::{group_views, DigestRenderer, GroupView, IssueView};
+use chrono::{DateTime, Utc};
 
-fn generate_text_with_header(header: &str, issues: &Vec<Issue>) -> String {
-    let mut text = String::new();
-    text.push_str(format!("{header}\n").as_str());
+pub const ISSUE_ACTION_ID: &str = "issue_action";
 
-    for issue in issues {
-        let text_for_issue = generate_text_for_issue(issue);
-        text.push_str(&text_for_issue);
+// a section can't be longer than MAX_SECTION_TEXT_LENGTH,
+// so a long list is split into several texts at issue boundaries
+fn generate_texts_with_header(header: &str, lines: &[String], locale: Locale) -> Vec<String> {
+    let continued_header = match header {
+        "" => String::new(),
+        header => text_with(locale, "digest.continued", &[("header", header)]),
+    };
+    split_lines_with_header(header, &continued_header, lines, MAX_SECTION_TEXT_LENGTH)
+}
+
+// the same line as the default template renders
+fn generate_item_text(issue: &IssueView) -> String {
+    format!(
+        "{}{}\n",
+        DIGEST_ITEM_PREFIX,
+        MrkdwnRenderer.render_issue(issue)
+    )
+}
+
+fn add_section_blocks(
+    message_block: &mut SlackMessageBlocks,
+    section: &TemplateSection,
+    locale: Locale,
+) {
+    for text in generate_texts_with_header(&section.header, &section.items, locale) {
+        message_block.add_text_block(text);
     }
-    text
 }
 
-fn generate_text_for_issue(issue: &Issue) -> String {
-    let issue_url = &issue.html_url;
-    let issue_title = &issue.title;
-    let issue_labels = match &issue.labels {
-        Some(labels) => {
-            let mut label_names = String::new();
-            for label in labels {
-                label_names.push_str(&label.name);
-                label_names.push(' ');
+// add the sections rendered by the template, by default one for each priority which has issues
+pub(crate) fn add_sorted_issue_blocks(
+    message_block: &mut SlackMessageBlocks,
+    issues: &SortedIssues,
+    locale: Locale,
+    template: &DigestTemplate,
+    now: DateTime<Utc>,
+) {
+    match template.render_sections(issues, locale, now) {
+        Ok(sections) => {
+            for section in sections {
+                add_section_blocks(message_block, &section, locale);
             }
-            label_names
         }
-        None => String::from(""),
-    };
-    let issue_repository = &issue.repository;
-    format!(
-        "- <{}|{}>(<{}|{}>): {}\n",
-        issue_url, issue_title, issue_repository.html_url, issue_repository.name, issue_labels
-    )
+        Err(e) => message_block.add_text_block(e.message),
+    }
 }
 
-pub fn create_payload_for_slack(issues: Result<SortedIssues, GetIssueError>) -> SlackMessageBlocks {
+// `mention` is put at the top, see mention::create_mention_text
+pub fn create_payload_for_slack(
+    issues: Result<SortedIssues, GetIssueError>,
+    mention: Option<&str>,
+    locale: Locale,
+    template: &DigestTemplate,
+    now: DateTime<Utc>,
+) -> SlackMessageBlocks {
     let mut message_block = SlackMessageBlocks::default();
 
     match issues {
         Ok(issues) => {
-            // add mention to the channel
-            message_block.add_text_block("<!channel>\n".to_string());
-            message_block.add_header_block("タスク一覧".to_string());
-
-            // add priority high issues
-            let priority_high_issues = issues.priority_high_issues;
-            if !priority_high_issues.is_empty() {
-                let text = generate_text_with_header("*優先度: 高*", &priority_high_issues);
-                message_block.add_text_block(text);
+            if let Some(mention) = mention {
+                message_block.add_text_block(format!("{mention}\n"));
             }
+            message_block.add_header_block(text(locale, "digest.title"));
+            add_sorted_issue_blocks(&mut message_block, &issues, locale, template, now);
+        }
+        Err(e) => {
+            message_block.add_text_block(e.message);
+        }
+    }
 
-            // add priority medium issues
-            let priority_medium_issues = issues.priority_medium_issues;
-            if !priority_medium_issues.is_empty() {
-                let text = generate_text_with_header("*優先度: 中*", &priority_medium_issues);
-                message_block.add_text_block(text);
-            }
+    message_block
+}
 
-            // add priority low issues
-            let priority_low_issues = issues.priority_low_issues;
-            if !priority_low_issues.is_empty() {
-                let text = generate_text_with_header("*優先度: 低*", &priority_low_issues);
-                message_block.add_text_block(text);
-            }
+// overflow menu of the actions on an issue
+fn create_issue_action_menu(snooze_days: i64, locale: Locale) -> SlackBlockElement {
+    let options = vec![
+        SlackOptionObject::new(text(locale, "action.close"), "close".to_string()),
+        SlackOptionObject::new(
+            plural_text(locale, "action.snooze", snooze_days),
+            format!("snooze:{snooze_days}"),
+        ),
+        SlackOptionObject::new(
+            text(locale, "action.assign_to_me"),
+            "assign_to_me".to_string(),
+        ),
+        SlackOptionObject::new(
+            text(locale, "action.priority_up"),
+            "priority_up".to_string(),
+        ),
+        SlackOptionObject::new(
+            text(locale, "action.priority_down"),
+            "priority_down".to_string(),
+        ),
+    ];
+    SlackBlockElement::overflow(ISSUE_ACTION_ID.to_string(), options)
+}
+
+fn add_interactive_issue_blocks(
+    message_block: &mut SlackMessageBlocks,
+    group: &GroupView,
+    snooze_days: i64,
+    locale: Locale,
+) {
+    message_block.add_text_block(MrkdwnRenderer.render_group_header(group));
+    for issue in &group.issues {
+        let text = generate_item_text(issue);
+        match IssueRef::from_issue(issue.issue) {
+            // the block_id tells which issue the action is for
+            Some(issue_ref) => message_block.add_text_block_with_accessory(
+                issue_ref.to_string(),
+                text,
+                create_issue_action_menu(snooze_days, locale),
+            ),
+            None => message_block.add_text_block(text),
+        }
+    }
+}
 
-            // add priority none issues
-            let priority_none_issues = issues.priority_none_issues;
-            if !priority_none_issues.is_empty() {
-                let text = generate_text_with_header("*優先度: なし*", &priority_none_issues);
-                message_block.add_text_block(text);
+// a digest with an overflow menu on every issue
+// each issue has its own section, so this is meant for short lists
+// and doesn't go through the template
+pub fn create_interactive_payload_for_slack(
+    issues: Result<SortedIssues, GetIssueError>,
+    mention: Option<&str>,
+    snooze_days: i64,
+    locale: Locale,
+    style: &DigestStyle,
+    now: DateTime<Utc>,
+) -> SlackMessageBlocks {
+    let mut message_block = SlackMessageBlocks::default();
+
+    match issues {
+        Ok(issues) => {
+            if let Some(mention) = mention {
+                message_block.add_text_block(format!("{mention}\n"));
+            }
+            message_block.add_header_block(text(locale, "digest.title"));
+            for group in group_views(&issues, locale, style, now) {
+                add_interactive_issue_blocks(&mut message_block, &group, snooze_days, locale);
             }
         }
         Err(e) => {
@@ -79,9 +160,119 @@ pub fn create_payload_for_slack(issues: Result<SortedIssues, GetIssueError>) ->
     message_block
 }
 
+// the issue counts per priority and the overdue count, e.g. "🔴 *優先度: 高* 3件"
+fn generate_summary_text(
+    issues: &SortedIssues,
+    locale: Locale,
+    style: &DigestStyle,
+    now: DateTime<Utc>,
+) -> String {
+    let mut lines: Vec<String> = group_views(issues, locale, style, now)
+        .iter()
+        .map(|group| {
+            format!(
+                "{} {}",
+                MrkdwnRenderer.render_group_header(group),
+                plural_text(locale, "digest.summary.count", group.issues.len() as i64)
+            )
+        })
+        .collect();
+    if lines.is_empty() {
+        return text(locale, "digest.summary.empty");
+    }
+    let overdue_count = issues.iter().filter(|issue| issue.is_overdue(now)).count();
+    if overdue_count > 0 {
+        lines.push(plural_text(
+            locale,
+            "digest.summary.overdue",
+            overdue_count as i64,
+        ));
+    }
+    lines.join("\n")
+}
+
+// a digest in the thread layout
+// the summary is posted at the top level and each group is a reply in its thread, in order
+#[derive(Debug, Default)]
+pub struct SlackThreadDigest {
+    pub summary: SlackMessageBlocks,
+    pub groups: Vec<SlackMessageBlocks>,
+}
+
+// the groups are the sections of the template,
+// or the priorities with an overflow menu on every issue when `snooze_days` is given
+pub fn create_thread_payload_for_slack(
+    issues: Result<SortedIssues, GetIssueError>,
+    mention: Option<&str>,
+    locale: Locale,
+    template: &DigestTemplate,
+    snooze_days: Option<i64>,
+    now: DateTime<Utc>,
+) -> SlackThreadDigest {
+    let mut digest = SlackThreadDigest::default();
+    let issues = match issues {
+        Ok(issues) => issues,
+        Err(e) => {
+            digest.summary.add_text_block(e.message);
+            return digest;
+        }
+    };
+
+    if let Some(mention) = mention {
+        digest.summary.add_text_block(format!("{mention}\n"));
+    }
+    digest
+        .summary
+        .add_header_block(text(locale, "digest.title"));
+    digest.summary.add_text_block(generate_summary_text(
+        &issues,
+        locale,
+        template.style(),
+        now,
+    ));
+
+    match snooze_days {
+        Some(snooze_days) => {
+            for group in group_views(&issues, locale, template.style(), now) {
+                let mut message_block = SlackMessageBlocks::default();
+                add_interactive_issue_blocks(&mut message_block, &group, snooze_days, locale);
+                digest.groups.push(message_block);
+            }
+        }
+        None => match template.render_sections(&issues, locale, now) {
+            Ok(sections) => {
+                for section in sections {
+                    let mut group = SlackMessageBlocks::default();
+                    add_section_blocks(&mut group, &section, locale);
+                    digest.groups.push(group);
+                }
+            }
+            Err(e) => digest.summary.add_text_block(e.message),
+        },
+    }
+
+    if !digest.groups.is_empty() {
+        digest
+            .summary
+            .add_context_block(text(locale, "digest.summary.thread"));
+    }
+    digest
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::{Issue, Priority};
+
+    fn generate_text_for_issue(
+        issue: &Issue,
+        priority: Priority,
+        locale: Locale,
+        style: &DigestStyle,
+        now: DateTime<Utc>,
+    ) -> String {
+        generate_item_text(&IssueView::new(issue, priority, locale, style, now))
+    }
 
     #[test]
     fn test_add_header_block() {
@@ -91,11 +282,8 @@ mod tests {
         // add header block
         slack_message_blocks.add_header_block("test".to_string());
         assert_eq!(slack_message_blocks.blocks.len(), 1);
-        assert_eq!(slack_message_blocks.blocks[0].block_type, "header");
-        assert_eq!(
-            slack_message_blocks.blocks[0].text.as_ref().unwrap().text,
-            "test"
-        );
+        assert_eq!(slack_message_blocks.blocks[0].block_type(), "header");
+        assert_eq!(slack_message_blocks.blocks[0].text().unwrap().text, "test");
     }
 
     #[test]
@@ -106,40 +294,138 @@ mod tests {
         // add text block
         slack_message_blocks.add_text_block("test".to_string());
         assert_eq!(slack_message_blocks.blocks.len(), 1);
-        assert_eq!(slack_message_blocks.blocks[0].block_type, "section");
-        assert_eq!(
-            slack_message_blocks.blocks[0].text.as_ref().unwrap().text,
-            "test"
-        );
+        assert_eq!(slack_message_blocks.blocks[0].block_type(), "section");
+        assert_eq!(slack_message_blocks.blocks[0].text().unwrap().text, "test");
     }
 
     #[test]
-    fn test_generate_text_with_header() {
+    fn test_generate_texts_with_header() {
         use crate::models::{Label, Repository};
 
-        let issues = vec![Issue {
+        let issue = Issue {
             html_url: "issue_html_url".to_string(),
             title: "title".to_string(),
             labels: Some(vec![Label {
                 name: "label1".to_string(),
                 id: 0,
+                color: None,
+                description: None,
             }]),
             repository: Repository {
                 html_url: "repo_html_url".to_string(),
                 name: "name".to_string(),
+                full_name: "owner/name".to_string(),
                 id: 0,
             },
             body: None,
             id: 0,
             label_string: None,
+            assignees: None,
+            milestone: None,
+            created_at: None,
+            updated_at: None,
+            number: 1,
             state: "open".to_string(),
-        }];
-        let text = generate_text_with_header("header", &issues);
+        };
+        let lines = vec![generate_text_for_issue(
+            &issue,
+            Priority::High,
+            Locale::Ja,
+            &DigestStyle::default(),
+            Utc::now(),
+        )];
+        let texts = generate_texts_with_header("header", &lines, Locale::Ja);
         assert_eq!(
-            text,
-            "header".to_string()
-                + "\n"
-                + "- <issue_html_url|title>(<repo_html_url|name>): label1 \n"
+            texts,
+            vec![
+                "header".to_string()
+                    + "\n"
+                    + "- <issue_html_url|title>(<repo_html_url|name>) `label1`\n"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generate_texts_with_header_splits_long_list() {
+        use crate::models::Repository;
+
+        let issues: Vec<Issue> = (0..100)
+            .map(|number| Issue {
+                html_url: format!("https://github.com/owner/name/issues/{number}"),
+                title: "a long title of an issue".to_string(),
+                labels: None,
+                repository: Repository {
+                    html_url: "https://github.com/owner/name".to_string(),
+                    name: "name".to_string(),
+                    full_name: "owner/name".to_string(),
+                    id: 0,
+                },
+                body: None,
+                id: 0,
+                label_string: None,
+                assignees: None,
+                milestone: None,
+                created_at: None,
+                updated_at: None,
+                number,
+                state: "open".to_string(),
+            })
+            .collect();
+        let lines: Vec<String> = issues
+            .iter()
+            .map(|issue| {
+                generate_text_for_issue(
+                    issue,
+                    Priority::High,
+                    Locale::Ja,
+                    &DigestStyle::default(),
+                    Utc::now(),
+                )
+            })
+            .collect();
+        let texts = generate_texts_with_header("header", &lines, Locale::Ja);
+        assert!(texts.len() > 1);
+        assert!(texts
+            .iter()
+            .all(|text| text.chars().count() <= MAX_SECTION_TEXT_LENGTH));
+        assert!(texts[1].starts_with("header (続き)\n- "));
+        // no issue is lost or cut
+        let count: usize = texts.iter().map(|text| text.matches("\n- ").count()).sum();
+        assert_eq!(count, 100);
+    }
+
+    #[test]
+    fn test_generate_text_for_issue_escapes_user_text() {
+        use crate::models::{Label, Repository};
+
+        let issue = Issue {
+            html_url: "issue_html_url".to_string(),
+            title: "<!channel> a > b | *c*".to_string(),
+            labels: Some(vec![Label {
+                name: "a&b".to_string(),
+                id: 0,
+                color: None,
+                description: None,
+            }]),
+            repository: Repository {
+                html_url: "repo_html_url".to_string(),
+                name: "name".to_string(),
+                full_name: "owner/name".to_string(),
+                id: 0,
+            },
+            body: None,
+            id: 0,
+            label_string: None,
+            assignees: None,
+            milestone: None,
+            created_at: None,
+            updated_at: None,
+            number: 1,
+            state: "open".to_string(),
+        };
+        assert_eq!(
+            generate_text_for_issue(&issue, Priority::High, Locale::Ja, &DigestStyle::default(), Utc::now()),
+            "- <issue_html_url|&lt;!channel&gt; a &gt; b ｜ *\u{200B}c*\u{200B}>(<repo_html_url|name>) `a&amp;b`\n"
         );
     }
 
@@ -153,21 +439,81 @@ mod tests {
             labels: Some(vec![Label {
                 name: "label1".to_string(),
                 id: 0,
+                color: None,
+                description: None,
             }]),
             repository: Repository {
                 html_url: "repo_html_url".to_string(),
                 name: "name".to_string(),
+                full_name: "owner/name".to_string(),
                 id: 0,
             },
             body: None,
             id: 0,
             label_string: None,
+            assignees: None,
+            milestone: None,
+            created_at: None,
+            updated_at: None,
+            number: 1,
             state: "open".to_string(),
         };
-        let text = generate_text_for_issue(&issue);
+        let text = generate_text_for_issue(
+            &issue,
+            Priority::High,
+            Locale::Ja,
+            &DigestStyle::default(),
+            Utc::now(),
+        );
         assert_eq!(
             text,
-            "- <issue_html_url|title>(<repo_html_url|name>): label1 \n"
+            "- <issue_html_url|title>(<repo_html_url|name>) `label1`\n"
+        );
+    }
+
+    #[test]
+    fn test_create_payload_for_slack_hides_priority_labels() {
+        use crate::models::{Label, Repository, PRIORITY_HIGH_LABEL};
+
+        let label = |name: &str| Label {
+            name: name.to_string(),
+            id: 0,
+            color: None,
+            description: None,
+        };
+        let mut issues = SortedIssues::default();
+        issues.priority_high_issues.push(Issue {
+            html_url: "issue_html_url".to_string(),
+            title: "title".to_string(),
+            labels: Some(vec![label("bug"), label(PRIORITY_HIGH_LABEL)]),
+            repository: Repository {
+                html_url: "repo_html_url".to_string(),
+                name: "name".to_string(),
+                full_name: "owner/name".to_string(),
+                id: 0,
+            },
+            body: None,
+            id: 0,
+            label_string: None,
+            assignees: None,
+            milestone: None,
+            created_at: None,
+            updated_at: None,
+            number: 1,
+            state: "open".to_string(),
+        });
+        let style = DigestStyle {
+            priority_emoji: std::collections::HashMap::from([(
+                Priority::High,
+                ":fire:".to_string(),
+            )]),
+            hide_priority_labels: true,
+        };
+        let template = DigestTemplate::default().with_style(style);
+        let payload = create_payload_for_slack(Ok(issues), None, Locale::En, &template, Utc::now());
+        assert_eq!(
+            payload.blocks[1].text().unwrap().text,
+            ":fire: *Priority: High*\n- <issue_html_url|title>(<repo_html_url|name>) `bug`\n"
         );
     }
 
@@ -182,32 +528,360 @@ mod tests {
             labels: Some(vec![Label {
                 name: "label1".to_string(),
                 id: 0,
+                color: None,
+                description: None,
             }]),
             repository: Repository {
                 html_url: "repo_html_url".to_string(),
                 name: "name".to_string(),
+                full_name: "owner/name".to_string(),
                 id: 0,
             },
             body: None,
             id: 0,
             label_string: None,
+            assignees: None,
+            milestone: None,
+            created_at: None,
+            updated_at: None,
+            number: 1,
             state: "open".to_string(),
         });
-        let payload = create_payload_for_slack(Ok(issues));
+        let payload = create_payload_for_slack(
+            Ok(issues),
+            Some("<!channel>"),
+            Locale::Ja,
+            &DigestTemplate::default(),
+            Utc::now(),
+        );
         assert_eq!(payload.blocks.len(), 3);
-        assert_eq!(payload.blocks[0].block_type, "section");
+        assert_eq!(payload.blocks[0].block_type(), "section");
+        assert_eq!(payload.blocks[0].text().unwrap().text, "<!channel>\n");
+        assert_eq!(payload.blocks[1].block_type(), "header");
+        assert_eq!(payload.blocks[1].text().unwrap().text, "タスク一覧");
+        assert_eq!(payload.blocks[2].block_type(), "section");
+        assert_eq!(
+            payload.blocks[2].text().unwrap().text,
+            "🔴 *優先度: 高*".to_string()
+                + "\n"
+                + "- <issue_html_url|title>(<repo_html_url|name>) `label1`\n"
+        );
+    }
+
+    #[test]
+    fn test_create_payload_for_slack_with_template() {
+        use crate::models::Repository;
+
+        let issue = |number| Issue {
+            html_url: format!("https://github.com/owner/name/issues/{number}"),
+            title: "a long title of an issue".to_string(),
+            labels: None,
+            repository: Repository {
+                html_url: "https://github.com/owner/name".to_string(),
+                name: "name".to_string(),
+                full_name: "owner/name".to_string(),
+                id: 0,
+            },
+            body: None,
+            id: 0,
+            label_string: None,
+            assignees: None,
+            milestone: None,
+            created_at: None,
+            updated_at: None,
+            number,
+            state: "open".to_string(),
+        };
+        let issues = SortedIssues {
+            priority_high_issues: (0..100).map(issue).collect(),
+            priority_low_issues: vec![issue(100)],
+            ..SortedIssues::default()
+        };
+        // one-liners without headers
+        let template = DigestTemplate::parse(
+            "{% for group in groups %}{% for issue in group.issues %}\
+             - {{ issue.link }} ({{ group.priority }})\n{% endfor %}{% endfor %}",
+        )
+        .unwrap();
+        let payload = create_payload_for_slack(Ok(issues), None, Locale::Ja, &template, Utc::now());
+        assert_eq!(payload.blocks[0].text().unwrap().text, "タスク一覧");
+        let texts: Vec<&str> = payload.blocks[1..]
+            .iter()
+            .map(|block| block.text().unwrap().text.as_str())
+            .collect();
+        assert!(texts.len() > 1);
+        assert!(texts.iter().all(|text| text.starts_with("- <")));
+        assert!(texts.last().unwrap().ends_with(
+            "- <https://github.com/owner/name/issues/100|a long title of an issue> (low)\n"
+        ));
+        let count: usize = texts.iter().map(|text| text.matches("- <").count()).sum();
+        assert_eq!(count, 101);
+    }
+
+    #[test]
+    fn test_create_interactive_payload_for_slack() {
+        use crate::models::Repository;
+
+        let mut issues = SortedIssues::default();
+        issues.priority_medium_issues.push(Issue {
+            html_url: "issue_html_url".to_string(),
+            title: "title".to_string(),
+            labels: None,
+            repository: Repository {
+                html_url: "repo_html_url".to_string(),
+                name: "name".to_string(),
+                full_name: "owner/name".to_string(),
+                id: 0,
+            },
+            body: None,
+            id: 0,
+            label_string: None,
+            assignees: None,
+            milestone: None,
+            created_at: None,
+            updated_at: None,
+            number: 5,
+            state: "open".to_string(),
+        });
+        let payload = create_interactive_payload_for_slack(
+            Ok(issues),
+            Some("<!channel>"),
+            3,
+            Locale::Ja,
+            &DigestStyle::default(),
+            Utc::now(),
+        );
+        assert_eq!(payload.blocks.len(), 4);
+        assert_eq!(payload.blocks[2].text().unwrap().text, "🟠 *優先度: 中*");
+        let issue_block = &payload.blocks[3];
+        assert_eq!(issue_block.block_id(), Some("owner/name#5"));
+        let accessory = match issue_block.accessory() {
+            Some(SlackBlockElement::Overflow(overflow)) => overflow,
+            other => panic!("unexpected accessory: {other:?}"),
+        };
+        assert_eq!(accessory.action_id, ISSUE_ACTION_ID);
+        assert_eq!(accessory.options.len(), 5);
+        assert_eq!(accessory.options[1].value, "snooze:3");
+    }
+
+    #[test]
+    fn test_generate_text_for_issue_with_due_date() {
+        use crate::models::{Milestone, Repository};
+        use chrono::TimeZone;
+
+        let now = Utc.with_ymd_and_hms(2023, 1, 10, 0, 0, 0).unwrap();
+        let issue = Issue {
+            html_url: "issue_html_url".to_string(),
+            title: "title".to_string(),
+            labels: None,
+            repository: Repository {
+                html_url: "repo_html_url".to_string(),
+                name: "name".to_string(),
+                full_name: "owner/name".to_string(),
+                id: 0,
+            },
+            body: None,
+            id: 0,
+            label_string: None,
+            assignees: None,
+            milestone: Some(Milestone {
+                number: 1,
+                title: "v1".to_string(),
+                html_url: "milestone_html_url".to_string(),
+                due_on: Some(Utc.with_ymd_and_hms(2023, 1, 13, 7, 0, 0).unwrap()),
+            }),
+            created_at: None,
+            updated_at: None,
+            number: 1,
+            state: "open".to_string(),
+        };
         assert_eq!(
-            payload.blocks[0].text.as_ref().unwrap().text,
-            "<!channel>\n"
+            generate_text_for_issue(
+                &issue,
+                Priority::None,
+                Locale::Ja,
+                &DigestStyle::default(),
+                now
+            ),
+            "- <issue_html_url|title>(<repo_html_url|name>) (期限: 3日後)\n"
         );
-        assert_eq!(payload.blocks[1].block_type, "header");
-        assert_eq!(payload.blocks[1].text.as_ref().unwrap().text, "タスク一覧");
-        assert_eq!(payload.blocks[2].block_type, "section");
         assert_eq!(
-            payload.blocks[2].text.as_ref().unwrap().text,
-            "*優先度: 高*".to_string()
-                + "\n"
-                + "- <issue_html_url|title>(<repo_html_url|name>): label1 \n"
+            generate_text_for_issue(
+                &issue,
+                Priority::None,
+                Locale::En,
+                &DigestStyle::default(),
+                now
+            ),
+            "- <issue_html_url|title>(<repo_html_url|name>) (due in 3 days)\n"
+        );
+    }
+
+    #[test]
+    fn test_create_interactive_payload_for_slack_in_english() {
+        use crate::models::Repository;
+
+        let mut issues = SortedIssues::default();
+        issues.priority_none_issues.push(Issue {
+            html_url: "issue_html_url".to_string(),
+            title: "title".to_string(),
+            labels: None,
+            repository: Repository {
+                html_url: "repo_html_url".to_string(),
+                name: "name".to_string(),
+                full_name: "owner/name".to_string(),
+                id: 0,
+            },
+            body: None,
+            id: 0,
+            label_string: None,
+            assignees: None,
+            milestone: None,
+            created_at: None,
+            updated_at: None,
+            number: 5,
+            state: "open".to_string(),
+        });
+        let payload = create_interactive_payload_for_slack(
+            Ok(issues),
+            None,
+            1,
+            Locale::En,
+            &DigestStyle::default(),
+            Utc::now(),
+        );
+        assert_eq!(payload.blocks[0].text().unwrap().text, "Tasks");
+        assert_eq!(
+            payload.blocks[1].text().unwrap().text,
+            "⚪ *Priority: None*"
+        );
+        let accessory = match payload.blocks[2].accessory() {
+            Some(SlackBlockElement::Overflow(overflow)) => overflow,
+            other => panic!("unexpected accessory: {other:?}"),
+        };
+        assert_eq!(accessory.options[0].text.text, "Close");
+        assert_eq!(accessory.options[1].text.text, "Snooze for 1 day");
+    }
+
+    fn thread_digest_issues() -> SortedIssues {
+        use crate::models::{Milestone, Repository};
+        use chrono::TimeZone;
+
+        let issue = |number| Issue {
+            html_url: format!("issue_html_url_{number}"),
+            title: "title".to_string(),
+            labels: None,
+            repository: Repository {
+                html_url: "repo_html_url".to_string(),
+                name: "name".to_string(),
+                full_name: "owner/name".to_string(),
+                id: 0,
+            },
+            body: None,
+            id: 0,
+            label_string: None,
+            assignees: None,
+            milestone: None,
+            created_at: None,
+            updated_at: None,
+            number,
+            state: "open".to_string(),
+        };
+        let mut overdue_issue = issue(3);
+        overdue_issue.milestone = Some(Milestone {
+            number: 1,
+            title: "v1".to_string(),
+            html_url: "milestone_html_url".to_string(),
+            due_on: Some(Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap()),
+        });
+        SortedIssues {
+            priority_high_issues: vec![issue(1), issue(2)],
+            priority_none_issues: vec![overdue_issue],
+            ..SortedIssues::default()
+        }
+    }
+
+    #[test]
+    fn test_generate_summary_text() {
+        use chrono::TimeZone;
+
+        let now = Utc.with_ymd_and_hms(2023, 1, 10, 0, 0, 0).unwrap();
+        let style = DigestStyle::default();
+        assert_eq!(
+            generate_summary_text(&thread_digest_issues(), Locale::Ja, &style, now),
+            "🔴 *優先度: 高* 2件\n⚪ *優先度: なし* 1件\n期限切れ: 1件"
+        );
+        assert_eq!(
+            generate_summary_text(&thread_digest_issues(), Locale::En, &style, now),
+            "🔴 *Priority: High* 2 issues\n⚪ *Priority: None* 1 issue\n1 overdue issue"
+        );
+        assert_eq!(
+            generate_summary_text(&SortedIssues::default(), Locale::En, &style, now),
+            "No tasks"
+        );
+    }
+
+    #[test]
+    fn test_create_thread_payload_for_slack() {
+        let digest = create_thread_payload_for_slack(
+            Ok(thread_digest_issues()),
+            Some("<!channel>"),
+            Locale::Ja,
+            &DigestTemplate::default(),
+            None,
+            Utc::now(),
+        );
+        let summary = &digest.summary.blocks;
+        assert_eq!(summary.len(), 4);
+        assert_eq!(summary[0].text().unwrap().text, "<!channel>\n");
+        assert_eq!(summary[1].text().unwrap().text, "タスク一覧");
+        assert!(summary[2]
+            .text()
+            .unwrap()
+            .text
+            .starts_with("🔴 *優先度: 高* 2件\n"));
+        assert_eq!(summary[3].block_type(), "context");
+
+        // a reply for each group, from high to none
+        assert_eq!(digest.groups.len(), 2);
+        let high = &digest.groups[0].blocks[0].text().unwrap().text;
+        assert!(high.starts_with("🔴 *優先度: 高*\n- <issue_html_url_1|title>"));
+        assert_eq!(high.matches("\n- ").count(), 2);
+        let none = &digest.groups[1].blocks[0].text().unwrap().text;
+        assert!(none.starts_with("⚪ *優先度: なし*\n- <issue_html_url_3|title>"));
+    }
+
+    #[test]
+    fn test_create_interactive_thread_payload_for_slack() {
+        let digest = create_thread_payload_for_slack(
+            Ok(thread_digest_issues()),
+            None,
+            Locale::En,
+            &DigestTemplate::default(),
+            Some(3),
+            Utc::now(),
+        );
+        assert_eq!(digest.groups.len(), 2);
+        // the header and a section with a menu for each issue
+        assert_eq!(digest.groups[0].blocks.len(), 3);
+        assert!(digest.groups[0].blocks[1].accessory().is_some());
+        assert_eq!(digest.groups[1].blocks.len(), 2);
+    }
+
+    #[test]
+    fn test_create_thread_payload_for_slack_error() {
+        let digest = create_thread_payload_for_slack(
+            Err(GetIssueError {
+                message: "error".to_string(),
+            }),
+            None,
+            Locale::Ja,
+            &DigestTemplate::default(),
+            None,
+            Utc::now(),
         );
+        assert_eq!(digest.summary.blocks.len(), 1);
+        assert_eq!(digest.summary.blocks[0].text().unwrap().text, "error");
+        assert!(digest.groups.is_empty());
     }
 }
@@ -0,0 +1,226 @@
+use super::layout::split_message_blocks;
+use super::message::SlackMessageBlock;
+use super::notifier::SlackDigestFormat;
+use super::template::DigestTemplate;
+use super::user_map::resolve_github_login;
+use crate::digest::Digest;
+use crate::env::{get_slack_snooze_days, is_slack_interactive_digest_enabled};
+use crate::errors::GetIssueError;
+use crate::github::{get_my_issues, get_public_assigned_issues, sort_issues};
+use crate::i18n::{text, Locale};
+use crate::models::Issue;
+use crate::snooze::{SnoozeScope, SnoozedIssues, SNOOZED_ISSUES_FILE_NAME};
+use crate::store::{load_json, state_file_path};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+// for the users who are not linked yet, the slash command replies only to them
+pub fn onboarding_text(locale: Locale) -> String {
+    text(locale, "personal.onboarding")
+}
+
+// whose issues a personal digest shows
+#[derive(Debug, PartialEq, Eq)]
+pub enum DigestUser {
+    // a token of the user, the issues assigned to its owner are fetched
+    Token(String),
+    // a GitHub login, the issues are searched with the shared token
+    // the user may have linked a login which isn't theirs, so only the public issues are shown
+    Login(String),
+}
+
+// a token of the user is preferred, as it can see the private repositories of the user
+// otherwise the login comes from the static entries, then from the links,
+// as for resolve_slack_users
+pub fn resolve_digest_user(
+    slack_user_id: &str,
+    user_tokens: &HashMap<String, String>,
+    static_users: &HashMap<String, String>,
+    user_map_path: &Path,
+) -> Option<DigestUser> {
+    if let Some(token) = user_tokens.get(slack_user_id) {
+        return Some(DigestUser::Token(token.clone()));
+    }
+    resolve_github_login(slack_user_id, static_users, user_map_path).map(DigestUser::Login)
+}
+
+pub async fn get_digest_user_issues(
+    github_api_addr: &str,
+    shared_token: &str,
+    digest_user: &DigestUser,
+) -> Result<Vec<Issue>, GetIssueError> {
+    match digest_user {
+        DigestUser::Token(token) => {
+            get_my_issues(github_api_addr.to_string(), token.to_string()).await
+        }
+        DigestUser::Login(login) => {
+            get_public_assigned_issues(github_api_addr, shared_token, login).await
+        }
+    }
+}
+
+// the personal digest of a user, the same for the slash command and a mention
+// the issues the user snoozed are left out, and no one is mentioned
+pub async fn create_personal_digest(
+    github_api_addr: &str,
+    shared_token: &str,
+    digest_user: &DigestUser,
+    slack_user_id: &str,
+    state_dir: &str,
+    locale: Locale,
+    template: DigestTemplate,
+) -> (Digest, SlackDigestFormat) {
+    let now = chrono::Utc::now();
+    let issues = get_digest_user_issues(github_api_addr, shared_token, digest_user).await;
+    let snoozed_issues: SnoozedIssues =
+        load_json(&state_file_path(state_dir, SNOOZED_ISSUES_FILE_NAME));
+    let scope = SnoozeScope::User(slack_user_id.to_string());
+    let issues = snoozed_issues.filter_issues(&scope, issues, now.timestamp());
+    let format = SlackDigestFormat {
+        locale,
+        template,
+        mention: None,
+        snooze_days: is_slack_interactive_digest_enabled().then(get_slack_snooze_days),
+    };
+    (Digest::new(sort_issues(issues), now), format)
+}
+
+// the response to the slash command, which only the user who runs it sees
+// posted to the response_url of the command, see slash::post_command_response
+#[derive(Debug, Serialize)]
+pub struct PersonalDigestResponse {
+    pub response_type: String,
+    pub blocks: Vec<SlackMessageBlock>,
+}
+
+// a personal digest is never sent to the shared notifiers, it is only the answer to its user
+// a response is a single message, so a long digest ends with a "+N more" footer
+pub fn create_personal_digest_response(
+    digest: &Digest,
+    format: &SlackDigestFormat,
+) -> PersonalDigestResponse {
+    let payload = format.create_payload(digest);
+    let blocks = split_message_blocks(payload, 1, format.locale)
+        .into_iter()
+        .next()
+        .map(|message| message.blocks)
+        .unwrap_or_default();
+    PersonalDigestResponse {
+        response_type: "ephemeral".to_string(),
+        blocks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slack::user_map::SlackUserMap;
+    use crate::store::save_json;
+
+    fn user_map_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir()
+            .join("github_notification_personal_test")
+            .join(name);
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_resolve_digest_user() {
+        let path = user_map_path("resolve.json");
+        let mut user_map = SlackUserMap::default();
+        user_map.link("octocat", "U1");
+        user_map.link("hubot", "U2");
+        save_json(&path, &user_map).unwrap();
+        let user_tokens = HashMap::from([("U2".to_string(), "token2".to_string())]);
+        let static_users = HashMap::from([
+            ("monalisa".to_string(), "U1".to_string()),
+            ("mona".to_string(), "U4".to_string()),
+            ("lisa".to_string(), "U4".to_string()),
+        ]);
+
+        // the static entries come before the links
+        assert_eq!(
+            resolve_digest_user("U1", &user_tokens, &static_users, &path),
+            Some(DigestUser::Login("monalisa".to_string()))
+        );
+        assert_eq!(
+            resolve_digest_user("U1", &user_tokens, &HashMap::new(), &path),
+            Some(DigestUser::Login("octocat".to_string()))
+        );
+        assert_eq!(
+            resolve_digest_user("U2", &user_tokens, &static_users, &path),
+            Some(DigestUser::Token("token2".to_string()))
+        );
+        assert_eq!(
+            resolve_digest_user("U4", &user_tokens, &static_users, &path),
+            Some(DigestUser::Login("lisa".to_string()))
+        );
+        assert_eq!(
+            resolve_digest_user("U3", &user_tokens, &static_users, &path),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_digest_user_issues() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let my_issues_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/issues")
+                .header("Authorization", "Bearer user_token");
+            then.status(200).json_body(serde_json::json!([]));
+        });
+        let search_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search/issues")
+                .query_param("q", "is:open is:issue is:public assignee:octocat")
+                .header("Authorization", "Bearer shared_token");
+            then.status(200)
+                .json_body(serde_json::json!({"total_count": 0, "items": []}));
+        });
+        let mock_addr = format!("http://{}", server.address());
+
+        let issues = get_digest_user_issues(
+            &mock_addr,
+            "shared_token",
+            &DigestUser::Token("user_token".to_string()),
+        )
+        .await;
+        assert!(issues.unwrap().is_empty());
+        let issues = get_digest_user_issues(
+            &mock_addr,
+            "shared_token",
+            &DigestUser::Login("octocat".to_string()),
+        )
+        .await;
+        assert!(issues.unwrap().is_empty());
+        my_issues_mock.assert();
+        search_mock.assert();
+    }
+
+    #[test]
+    fn test_create_personal_digest_response() {
+        use crate::fixtures::{issue, now};
+        use crate::models::SortedIssues;
+
+        let digest = Digest::new(
+            Ok(SortedIssues {
+                priority_high_issues: vec![issue(1, "title", None)],
+                ..SortedIssues::default()
+            }),
+            now(),
+        );
+        let response = create_personal_digest_response(&digest, &SlackDigestFormat::default());
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["response_type"], "ephemeral");
+        assert_eq!(json["blocks"][0]["type"], "header");
+        assert!(json["blocks"][1]["text"]["text"]
+            .as_str()
+            .unwrap()
+            .contains("https://github.com/owner/name/issues/1"));
+    }
+}
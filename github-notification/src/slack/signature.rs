@@ -0,0 +1,91 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+pub const SLACK_SIGNATURE_HEADER: &str = "X-Slack-Signature";
+pub const SLACK_REQUEST_TIMESTAMP_HEADER: &str = "X-Slack-Request-Timestamp";
+
+// requests older than this are rejected to prevent replay attacks
+const MAX_REQUEST_AGE_SECS: i64 = 60 * 5;
+
+fn compute_slack_signature(signing_secret: &str, timestamp: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(signing_secret.as_bytes())
+        .expect("HMAC can take key of any size");
+    mac.update(format!("v0:{timestamp}:{body}").as_bytes());
+    format!("v0={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+// verify a request from Slack
+// https://api.slack.com/authentication/verifying-requests-from-slack
+pub fn verify_slack_signature(
+    signing_secret: &str,
+    timestamp: &str,
+    body: &str,
+    signature: &str,
+    now: i64,
+) -> Result<(), String> {
+    let request_time = timestamp
+        .parse::<i64>()
+        .map_err(|_| "Invalid timestamp".to_string())?;
+    if (now - request_time).abs() > MAX_REQUEST_AGE_SECS {
+        return Err("Expired timestamp".to_string());
+    }
+
+    let expected = compute_slack_signature(signing_secret, timestamp, body);
+    // compare in constant time
+    let is_valid = expected.len() == signature.len()
+        && expected
+            .bytes()
+            .zip(signature.bytes())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0;
+    if !is_valid {
+        return Err("Invalid signature".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // example from the Slack documentation
+    const SIGNING_SECRET: &str = "8f742231b10e8888abcd99yyyzzz85a5";
+    const TIMESTAMP: &str = "1531420618";
+    const BODY: &str = "token=xyzz0WbapA4vBCDEFasx0q6G&team_id=T1DC2JH3J&team_domain=testteamnow&channel_id=G8PSS9T3V&channel_name=foobar&user_id=U2CERLKJA&user_name=roadrunner&command=%2Fwebhook-collect&text=&response_url=https%3A%2F%2Fhooks.slack.com%2Fcommands%2FT1DC2JH3J%2F397700885554%2F96rGlfmibIGlgcZRskXaIFfN&trigger_id=398738663015.47445629121.803a0bc887a14d10d2c447fce8b6703c";
+    const SIGNATURE: &str = "v0=a2114d57b48eac39b9ad189dd8316235a7b4a8d21a10bd27519666489c69b503";
+
+    #[test]
+    fn test_compute_slack_signature() {
+        assert_eq!(
+            compute_slack_signature(SIGNING_SECRET, TIMESTAMP, BODY),
+            SIGNATURE
+        );
+    }
+
+    #[test]
+    fn test_verify_slack_signature_valid() {
+        let now = TIMESTAMP.parse::<i64>().unwrap() + 10;
+        let res = verify_slack_signature(SIGNING_SECRET, TIMESTAMP, BODY, SIGNATURE, now);
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_verify_slack_signature_invalid_signature() {
+        let now = TIMESTAMP.parse::<i64>().unwrap();
+        let res = verify_slack_signature(SIGNING_SECRET, TIMESTAMP, "tampered", SIGNATURE, now);
+        assert_eq!(res.unwrap_err(), "Invalid signature");
+    }
+
+    #[test]
+    fn test_verify_slack_signature_expired_timestamp() {
+        let now = TIMESTAMP.parse::<i64>().unwrap() + MAX_REQUEST_AGE_SECS + 1;
+        let res = verify_slack_signature(SIGNING_SECRET, TIMESTAMP, BODY, SIGNATURE, now);
+        assert_eq!(res.unwrap_err(), "Expired timestamp");
+    }
+
+    #[test]
+    fn test_verify_slack_signature_invalid_timestamp() {
+        let res = verify_slack_signature(SIGNING_SECRET, "abc", BODY, SIGNATURE, 0);
+        assert_eq!(res.unwrap_err(), "Invalid timestamp");
+    }
+}
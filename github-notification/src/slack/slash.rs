@@ -13,16 +13,20 @@ pub struct SlashCommandPayload {
     pub command: String,
     pub text: String,
     pub response_url: String,
+    pub trigger_id: String,
 }
 
 #[derive(Debug)]
 pub struct SlackCommandRequest {
     pub command: String,
+    // the subcommand, the first word of the text
     pub text: String,
+    // the rest of the text, e.g. the GitHub login of "link octocat"
+    pub args: Vec<String>,
 }
 
 const AVAILABLE_COMMANDS: [&str; 1] = ["/mygithub"];
-const AVAILABLE_TEXT: [&str; 3] = ["help", "health-check", "create-notification"];
+const AVAILABLE_TEXT: [&str; 5] = ["help", "health-check", "create-notification", "new", "link"];
 
 pub fn validate_slash_command_payload(
     payload: &SlashCommandPayload,
@@ -36,16 +40,40 @@ pub fn validate_slash_command_payload(
         return Err("Invalid command".to_string());
     }
 
-    if !AVAILABLE_TEXT.contains(&payload.text.as_str()) {
+    let mut words = payload.text.split_whitespace();
+    let text = words.next().unwrap_or_default();
+    if !AVAILABLE_TEXT.contains(&text) {
         return Err("Invalid text".to_string());
     }
 
     Ok(SlackCommandRequest {
         command: payload.command.clone(),
-        text: payload.text.clone(),
+        text: text.to_string(),
+        args: words.map(|word| word.to_string()).collect(),
     })
 }
 
+// a delayed response to the command, e.g. a digest which takes longer to fetch
+// Slack waits only 3 seconds for the response to the command, but accepts this one for 30 minutes
+// https://api.slack.com/interactivity/handling#message_responses
+pub async fn post_command_response<T: Serialize + ?Sized>(response_url: &str, body: &T) {
+    let client = reqwest::Client::new();
+    match client.post(response_url).json(body).send().await {
+        Ok(res) if res.status() == 200 => println!("Command Response OK"),
+        Ok(res) => println!("Command Response Error: {}", res.status()),
+        Err(err) => println!("Command Response Error: {err}"),
+    }
+}
+
+// a text which only the user who runs the command sees
+pub async fn post_command_text(response_url: &str, text: &str) {
+    let body = serde_json::json!({
+        "response_type": "ephemeral",
+        "text": text,
+    });
+    post_command_response(response_url, &body).await;
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{env::SLACK_SLASH_COMMAND_TOKEN_KEY, slack::slash::SlashCommandPayload};
@@ -67,6 +95,7 @@ mod tests {
             command: valid_command.to_string(),
             text: valid_text.to_string(),
             response_url: "test_response_url".to_string(),
+            trigger_id: "test_trigger_id".to_string(),
         };
         let result = super::validate_slash_command_payload(&mock_payload);
         assert!(result.is_ok());
@@ -93,6 +122,7 @@ mod tests {
             command: valid_command.to_string(),
             text: valid_text.to_string(),
             response_url: "test_response_url".to_string(),
+            trigger_id: "test_trigger_id".to_string(),
         };
         let result = super::validate_slash_command_payload(&mock_payload);
         assert!(result.is_err());
@@ -116,6 +146,7 @@ mod tests {
             command: invalid_command.to_string(),
             text: valid_text.to_string(),
             response_url: "test_response_url".to_string(),
+            trigger_id: "test_trigger_id".to_string(),
         };
         let result = super::validate_slash_command_payload(&mock_payload);
         assert!(result.is_err());
@@ -139,9 +170,48 @@ mod tests {
             command: valid_command.to_string(),
             text: invalid_text.to_string(),
             response_url: "test_response_url".to_string(),
+            trigger_id: "test_trigger_id".to_string(),
         };
         let result = super::validate_slash_command_payload(&mock_payload);
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "Invalid text");
     }
+
+    #[test]
+    fn test_validate_slash_command_payload_with_args() {
+        let valid_token = "test_token";
+        std::env::set_var(SLACK_SLASH_COMMAND_TOKEN_KEY, valid_token);
+        let mock_payload = SlashCommandPayload {
+            token: valid_token.to_string(),
+            team_id: "test_team_id".to_string(),
+            team_domain: "test_team_domain".to_string(),
+            channel_id: "test_channel_id".to_string(),
+            channel_name: "test_channel_name".to_string(),
+            user_id: "test_user_id".to_string(),
+            user_name: "test_user_name".to_string(),
+            command: "/mygithub".to_string(),
+            text: " link  octocat ".to_string(),
+            response_url: "test_response_url".to_string(),
+            trigger_id: "test_trigger_id".to_string(),
+        };
+        let result = super::validate_slash_command_payload(&mock_payload).unwrap();
+        assert_eq!(result.text, "link");
+        assert_eq!(result.args, vec!["octocat"]);
+    }
+
+    #[tokio::test]
+    async fn test_post_command_text() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let response_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/commands/1")
+                .json_body(serde_json::json!({"response_type": "ephemeral", "text": "text"}));
+            then.status(200);
+        });
+
+        super::post_command_text(&format!("http://{}/commands/1", server.address()), "text").await;
+        response_mock.assert();
+    }
 }
@@ -0,0 +1,167 @@
+use super::mrkdwn::code;
+use crate::i18n::{text, Locale};
+use crate::models::{Issue, Label, Priority};
+use std::collections::HashMap;
+
+// how the priorities and labels of a digest look
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestStyle {
+    // put before the header of each priority, an empty one is left out
+    // an emoji or a shortcode like ":fire:"
+    pub priority_emoji: HashMap<Priority, String>,
+    // leave out the priority label of an issue in the section of that priority
+    pub hide_priority_labels: bool,
+}
+
+impl Default for DigestStyle {
+    fn default() -> Self {
+        Self {
+            priority_emoji: HashMap::from([
+                (Priority::High, "🔴".to_string()),
+                (Priority::Medium, "🟠".to_string()),
+                (Priority::Low, "🟡".to_string()),
+                (Priority::None, "⚪".to_string()),
+            ]),
+            hide_priority_labels: false,
+        }
+    }
+}
+
+// the title of a priority without markup, e.g. "優先度: 高", each renderer formats it
+pub fn group_title(priority: Priority, locale: Locale) -> String {
+    text(locale, &format!("digest.group.{}", priority.key()))
+}
+
+impl DigestStyle {
+    pub fn emoji(&self, priority: Priority) -> &str {
+        self.priority_emoji
+            .get(&priority)
+            .map(|emoji| emoji.as_str())
+            .unwrap_or("")
+    }
+
+    // e.g. "🔴 *優先度: 高*"
+    pub fn priority_header(&self, priority: Priority, locale: Locale) -> String {
+        let header = format!("*{}*", group_title(priority, locale));
+        match self.emoji(priority) {
+            "" => header,
+            emoji => format!("{emoji} {header}"),
+        }
+    }
+
+    // without mrkdwn, e.g. "🔴 優先度: 高" for the title of a Discord embed
+    pub fn priority_title(&self, priority: Priority, locale: Locale) -> String {
+        let title = group_title(priority, locale);
+        match self.emoji(priority) {
+            "" => title,
+            emoji => format!("{emoji} {title}"),
+        }
+    }
+
+    // the labels of an issue shown in the section of `priority`
+    pub fn visible_labels<'a>(&self, issue: &'a Issue, priority: Priority) -> Vec<&'a Label> {
+        issue
+            .labels
+            .iter()
+            .flatten()
+            .filter(|label| {
+                !(self.hide_priority_labels && priority.label_name() == Some(label.name.as_str()))
+            })
+            .collect()
+    }
+
+    // e.g. "`bug` `Priority: High`"
+    pub fn label_chips(&self, issue: &Issue, priority: Priority) -> String {
+        let chips: Vec<String> = self
+            .visible_labels(issue, priority)
+            .into_iter()
+            .map(|label| code(&label.name))
+            .collect();
+        chips.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use crate::models::{PRIORITY_HIGH_LABEL, PRIORITY_LOW_LABEL};
+
+    fn label(name: &str) -> Label {
+        Label {
+            id: 0,
+            name: name.to_string(),
+            color: None,
+            description: None,
+        }
+    }
+
+    // the shared fixture labeled `labels` alone
+    fn issue(labels: Vec<Label>) -> Issue {
+        let mut issue = fixtures::issue(1, "title", None);
+        issue.labels = Some(labels);
+        issue
+    }
+
+    #[test]
+    fn test_priority_header() {
+        let mut style = DigestStyle::default();
+        assert_eq!(
+            style.priority_header(Priority::High, Locale::Ja),
+            "🔴 *優先度: 高*"
+        );
+        assert_eq!(
+            style.priority_header(Priority::None, Locale::En),
+            "⚪ *Priority: None*"
+        );
+
+        style
+            .priority_emoji
+            .insert(Priority::High, ":fire:".to_string());
+        style.priority_emoji.remove(&Priority::Low);
+        assert_eq!(
+            style.priority_header(Priority::High, Locale::Ja),
+            ":fire: *優先度: 高*"
+        );
+        assert_eq!(
+            style.priority_header(Priority::Low, Locale::Ja),
+            "*優先度: 低*"
+        );
+    }
+
+    #[test]
+    fn test_priority_title() {
+        let mut style = DigestStyle::default();
+        assert_eq!(
+            style.priority_title(Priority::Medium, Locale::En),
+            "🟠 Priority: Medium"
+        );
+        style.priority_emoji.clear();
+        assert_eq!(
+            style.priority_title(Priority::Low, Locale::Ja),
+            "優先度: 低"
+        );
+    }
+
+    #[test]
+    fn test_label_chips() {
+        let labeled_issue = issue(vec![
+            label("bug"),
+            label(PRIORITY_HIGH_LABEL),
+            label(PRIORITY_LOW_LABEL),
+        ]);
+        let mut style = DigestStyle::default();
+        assert_eq!(
+            style.label_chips(&labeled_issue, Priority::High),
+            "`bug` `Priority: High` `Priority: Low`"
+        );
+
+        // only the label of the section is implied
+        style.hide_priority_labels = true;
+        assert_eq!(
+            style.label_chips(&labeled_issue, Priority::High),
+            "`bug` `Priority: Low`"
+        );
+        assert_eq!(style.label_chips(&issue(vec![]), Priority::High), "");
+    }
+}
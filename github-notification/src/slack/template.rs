@@ -0,0 +1,452 @@
+use super::layout::DIGEST_ITEM_PREFIX;
+use super::mrkdwn::{code, escape_text, link};
+use super::style::{group_title, DigestStyle};
+use crate::errors::DigestTemplateError;
+use crate::i18n::{relative_date, text, text_with, Locale};
+use crate::models::{Issue, Label, Milestone, Priority, Repository, SortedIssues, User};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::Path;
+use tera::{Context, Tera};
+
+const TEMPLATE_NAME: &str = "digest";
+
+// a line of only this starts a new section
+pub const SECTION_SEPARATOR: &str = "---";
+
+// the layout of the digest before templates
+// https://keats.github.io/tera/docs/#templates
+pub const DEFAULT_DIGEST_TEMPLATE: &str = "\
+{% for group in groups %}{% if group.emoji %}{{ group.emoji }} {% endif %}{{ group.header }}
+{% for issue in group.issues %}- {{ issue.link }}({{ issue.repository.link }})\
+{% for label in issue.labels %} {{ label.chip }}{% endfor %}\
+{% if issue.due_label %} {{ issue.due_label }}{% endif %}
+{% endfor %}{% if not loop.last %}---
+{% endif %}{% endfor %}";
+
+// the data a template sees
+// user-controlled text is escaped already, and links are mrkdwn links
+#[derive(Debug, Serialize)]
+struct TemplateDigest {
+    locale: &'static str,
+    title: String,
+    // only the priorities which have issues, from high to none
+    groups: Vec<TemplateGroup>,
+}
+
+#[derive(Debug, Serialize)]
+struct TemplateGroup {
+    // "high", "medium", "low" or "none"
+    priority: &'static str,
+    // empty when the priority has no emoji
+    emoji: String,
+    header: String,
+    issues: Vec<TemplateIssue>,
+}
+
+#[derive(Debug, Serialize)]
+struct TemplateIssue {
+    number: i64,
+    title: String,
+    url: String,
+    link: String,
+    repository: TemplateRepository,
+    // without the labels hidden by the style
+    labels: Vec<TemplateLabel>,
+    assignees: Vec<String>,
+    milestone: Option<String>,
+    // relative to now, e.g. "3日後" and "in 3 days", empty without a due date
+    due: String,
+    // e.g. "(期限: 3日後)"
+    due_label: String,
+    overdue: bool,
+    // when the issue was opened, e.g. "3日前" and "3 days ago"
+    age: String,
+}
+
+#[derive(Debug, Serialize)]
+struct TemplateLabel {
+    name: String,
+    // the name as inline code
+    chip: String,
+    // e.g. "d73a4a", empty when unknown
+    color: String,
+    description: String,
+}
+
+#[derive(Debug, Serialize)]
+struct TemplateRepository {
+    name: String,
+    full_name: String,
+    url: String,
+    link: String,
+}
+
+impl TemplateIssue {
+    fn new(
+        issue: &Issue,
+        priority: Priority,
+        locale: Locale,
+        style: &DigestStyle,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let due_on = issue
+            .milestone
+            .as_ref()
+            .and_then(|milestone| milestone.due_on);
+        let due = due_on
+            .map(|due_on| relative_date(locale, due_on, now))
+            .unwrap_or_default();
+        let due_label = match due_on {
+            Some(_) => text_with(locale, "digest.due", &[("date", &due)]),
+            None => String::new(),
+        };
+        let repository = &issue.repository;
+        Self {
+            number: issue.number,
+            title: escape_text(&issue.title),
+            url: issue.html_url.clone(),
+            link: link(&issue.html_url, &issue.title),
+            repository: TemplateRepository {
+                name: escape_text(&repository.name),
+                full_name: escape_text(&repository.full_name),
+                url: repository.html_url.clone(),
+                link: link(&repository.html_url, &repository.name),
+            },
+            labels: style
+                .visible_labels(issue, priority)
+                .into_iter()
+                .map(|label| TemplateLabel {
+                    name: escape_text(&label.name),
+                    chip: code(&label.name),
+                    color: label.color.clone().unwrap_or_default(),
+                    description: label
+                        .description
+                        .as_deref()
+                        .map(escape_text)
+                        .unwrap_or_default(),
+                })
+                .collect(),
+            assignees: issue
+                .assignee_logins()
+                .into_iter()
+                .map(escape_text)
+                .collect(),
+            milestone: issue
+                .milestone
+                .as_ref()
+                .map(|milestone| escape_text(&milestone.title)),
+            due,
+            due_label,
+            overdue: issue.is_overdue(now),
+            age: issue
+                .created_at
+                .map(|created_at| relative_date(locale, created_at, now))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl TemplateDigest {
+    fn new(issues: &SortedIssues, locale: Locale, style: &DigestStyle, now: DateTime<Utc>) -> Self {
+        Self {
+            locale: locale.code(),
+            title: text(locale, "digest.title"),
+            groups: issues
+                .groups()
+                .into_iter()
+                .filter(|(_, issues)| !issues.is_empty())
+                .map(|(priority, issues)| TemplateGroup {
+                    priority: priority.key(),
+                    emoji: style.emoji(priority).to_string(),
+                    header: format!("*{}*", group_title(priority, locale)),
+                    issues: issues
+                        .iter()
+                        .map(|issue| TemplateIssue::new(issue, priority, locale, style, now))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+// a section of a rendered digest
+// an item is a line starting with DIGEST_ITEM_PREFIX and the lines up to the next one,
+// so that a long section is split only between items
+#[derive(Debug, PartialEq, Eq)]
+pub struct TemplateSection {
+    pub header: String,
+    pub items: Vec<String>,
+}
+
+fn parse_sections(rendered: &str) -> Vec<TemplateSection> {
+    let mut sections = Vec::new();
+    let mut section: Option<TemplateSection> = None;
+    for line in rendered.split_inclusive('\n') {
+        if line.trim_end() == SECTION_SEPARATOR {
+            sections.extend(section.take());
+            continue;
+        }
+        match &mut section {
+            // blank lines before a header are left out
+            None if line.trim().is_empty() => {}
+            // a section may have no header
+            None if line.starts_with(DIGEST_ITEM_PREFIX) => {
+                section = Some(TemplateSection {
+                    header: String::new(),
+                    items: vec![line.to_string()],
+                })
+            }
+            None => {
+                section = Some(TemplateSection {
+                    header: line.trim_end_matches('\n').to_string(),
+                    items: Vec::new(),
+                })
+            }
+            Some(section) => match section.items.last_mut() {
+                Some(item) if !line.starts_with(DIGEST_ITEM_PREFIX) => item.push_str(line),
+                _ => section.items.push(line.to_string()),
+            },
+        }
+    }
+    sections.extend(section);
+    sections
+}
+
+fn template_error(err: tera::Error) -> DigestTemplateError {
+    // the cause, e.g. the line of a syntax error, is in the sources
+    let mut message = err.to_string();
+    let mut source = std::error::Error::source(&err);
+    while let Some(err) = source {
+        message.push_str(&format!(": {err}"));
+        source = err.source();
+    }
+    DigestTemplateError { message }
+}
+
+// issues with every field, so that the validation reaches every branch
+fn sample_issues() -> SortedIssues {
+    let sample_issue = || Issue {
+        id: 1,
+        number: 1,
+        title: "title".to_string(),
+        html_url: "https://github.com/owner/name/issues/1".to_string(),
+        state: "open".to_string(),
+        body: None,
+        labels: Some(vec![Label {
+            id: 1,
+            name: "label".to_string(),
+            color: None,
+            description: None,
+        }]),
+        repository: Repository {
+            id: 1,
+            name: "name".to_string(),
+            full_name: "owner/name".to_string(),
+            html_url: "https://github.com/owner/name".to_string(),
+        },
+        label_string: None,
+        assignees: Some(vec![User {
+            login: "octocat".to_string(),
+        }]),
+        milestone: Some(Milestone {
+            number: 1,
+            title: "v1".to_string(),
+            html_url: "https://github.com/owner/name/milestone/1".to_string(),
+            due_on: Some(Utc::now()),
+        }),
+        created_at: Some(Utc::now()),
+        updated_at: None,
+    };
+    SortedIssues {
+        priority_high_issues: vec![sample_issue()],
+        priority_medium_issues: vec![sample_issue()],
+        priority_low_issues: vec![sample_issue()],
+        priority_none_issues: vec![sample_issue()],
+    }
+}
+
+// a template of the sections of the digest, rendered with Tera
+// the header and the mention stay outside of the template
+#[derive(Debug, Clone)]
+pub struct DigestTemplate {
+    tera: Tera,
+    style: DigestStyle,
+}
+
+impl Default for DigestTemplate {
+    fn default() -> Self {
+        Self::parse(DEFAULT_DIGEST_TEMPLATE).unwrap()
+    }
+}
+
+impl DigestTemplate {
+    // the template is rendered once with sample issues,
+    // so that unknown variables are reported before any digest is sent
+    pub fn parse(source: &str) -> Result<Self, DigestTemplateError> {
+        let mut tera = Tera::default();
+        tera.add_raw_template(TEMPLATE_NAME, source)
+            .map_err(template_error)?;
+        let template = Self {
+            tera,
+            style: DigestStyle::default(),
+        };
+        template.render_sections(&sample_issues(), Locale::default(), Utc::now())?;
+        Ok(template)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, DigestTemplateError> {
+        let source = std::fs::read_to_string(path).map_err(|err| DigestTemplateError {
+            message: format!("Read Digest Template Error: {}: {err}", path.display()),
+        })?;
+        Self::parse(&source).map_err(|err| DigestTemplateError {
+            message: format!("Digest Template Error: {}: {err}", path.display()),
+        })
+    }
+
+    pub fn with_style(mut self, style: DigestStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn style(&self) -> &DigestStyle {
+        &self.style
+    }
+
+    pub fn render_sections(
+        &self,
+        issues: &SortedIssues,
+        locale: Locale,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<TemplateSection>, DigestTemplateError> {
+        let digest = TemplateDigest::new(issues, locale, &self.style, now);
+        let context = Context::from_serialize(digest).map_err(template_error)?;
+        let rendered = self
+            .tera
+            .render(TEMPLATE_NAME, &context)
+            .map_err(template_error)?;
+        Ok(parse_sections(&rendered))
+    }
+}
+
+// the built-in template when no path is given
+pub fn load_digest_template(path: Option<&str>) -> Result<DigestTemplate, DigestTemplateError> {
+    match path {
+        Some(path) => DigestTemplate::load(Path::new(path)),
+        None => Ok(DigestTemplate::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{issue, now};
+    use chrono::TimeZone;
+
+    // a high priority issue due in two days, and a medium one of octocat opened three days ago
+    fn issues() -> SortedIssues {
+        let due_on = Utc.with_ymd_and_hms(2023, 1, 12, 0, 0, 0).unwrap();
+        let mut medium_issue = issue(2, "title", None);
+        medium_issue.assignees = Some(vec![User {
+            login: "octocat".to_string(),
+        }]);
+        medium_issue.created_at = Some(Utc.with_ymd_and_hms(2023, 1, 7, 0, 0, 0).unwrap());
+        SortedIssues {
+            priority_high_issues: vec![issue(1, "<!here> *fix*", Some(due_on))],
+            priority_medium_issues: vec![medium_issue],
+            ..SortedIssues::default()
+        }
+    }
+
+    #[test]
+    fn test_default_template() {
+        let sections = DigestTemplate::default()
+            .render_sections(&issues(), Locale::Ja, now())
+            .unwrap();
+        assert_eq!(
+            sections,
+            vec![
+                TemplateSection {
+                    header: "🔴 *優先度: 高*".to_string(),
+                    items: vec![
+                        "- <https://github.com/owner/name/issues/1|&lt;!here&gt; *\u{200B}fix*\u{200B}>(<https://github.com/owner/name|name>) `bug` (期限: 2日後)\n".to_string()
+                    ],
+                },
+                TemplateSection {
+                    header: "🟠 *優先度: 中*".to_string(),
+                    items: vec![
+                        "- <https://github.com/owner/name/issues/2|title>(<https://github.com/owner/name|name>) `bug`\n".to_string()
+                    ],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_template() {
+        let template = DigestTemplate::parse(
+            "{% for group in groups %}{{ group.header }} ({{ group.issues | length }})\n\
+             {% for issue in group.issues %}- #{{ issue.number }} {{ issue.title }}\n  \
+             {{ issue.assignees | join(sep=\", \") }} {{ issue.age }}\n{% endfor %}---\n{% endfor %}",
+        )
+        .unwrap();
+        let sections = template
+            .render_sections(&issues(), Locale::En, now())
+            .unwrap();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[1].header, "*Priority: Medium* (1)");
+        // the detail line belongs to the item above it
+        assert_eq!(
+            sections[1].items,
+            vec!["- #2 title\n  octocat 3 days ago\n".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_parse_reports_errors() {
+        let err = DigestTemplate::parse("{% for group in groups %}").unwrap_err();
+        assert!(err.message.contains("digest"), "{}", err.message);
+
+        // unknown variables are found by the trial rendering
+        let err = DigestTemplate::parse("{% for group in groups %}{{ group.unknown }}{% endfor %}")
+            .unwrap_err();
+        assert!(err.message.contains("group.unknown"), "{}", err.message);
+    }
+
+    #[test]
+    fn test_parse_sections() {
+        let sections = parse_sections("\nh1\n- a\n  detail\n- b\n---\n\n---\nh2\ntext\n---\n- c\n");
+        assert_eq!(
+            sections,
+            vec![
+                TemplateSection {
+                    header: "h1".to_string(),
+                    items: vec!["- a\n  detail\n".to_string(), "- b\n".to_string()],
+                },
+                TemplateSection {
+                    header: "h2".to_string(),
+                    items: vec!["text\n".to_string()],
+                },
+                TemplateSection {
+                    header: String::new(),
+                    items: vec!["- c\n".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_digest_template() {
+        assert!(load_digest_template(None).is_ok());
+
+        let dir = std::env::temp_dir().join("github_notification_template_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("broken.tera");
+        std::fs::write(&path, "{{ unclosed").unwrap();
+        let err = load_digest_template(path.to_str()).unwrap_err();
+        assert!(err.message.starts_with("Digest Template Error: "));
+
+        let err = load_digest_template(Some("/nonexistent/digest.tera")).unwrap_err();
+        assert!(err.message.starts_with("Read Digest Template Error: "));
+    }
+}
@@ -0,0 +1,438 @@
+use super::api::lookup_user_by_email;
+use super::mrkdwn::escape_text;
+use crate::github::get_user_profile;
+use crate::i18n::{text, text_with, Locale};
+use crate::store::{load_json, save_json};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+pub const SLACK_USER_MAP_FILE_NAME: &str = "slack_user_map.json";
+
+// GitHub login -> Slack user id
+// linked by the users with "/mygithub link", or found by the email of the GitHub user
+// logins are kept in lowercase, as GitHub doesn't tell the case apart
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SlackUserMap {
+    pub users: HashMap<String, String>,
+}
+
+impl SlackUserMap {
+    // a Slack user is linked to one GitHub login, the previous link is replaced
+    // a login linked to another Slack user is refused, so that nobody can take it over
+    pub fn link(&mut self, github_login: &str, slack_user_id: &str) -> bool {
+        if matches!(self.slack_user_id(github_login), Some(user_id) if user_id != slack_user_id) {
+            return false;
+        }
+        self.users.retain(|_, user_id| user_id != slack_user_id);
+        self.users
+            .insert(github_login.to_lowercase(), slack_user_id.to_string());
+        true
+    }
+
+    // a link found by a lookup, added only when neither the login nor the Slack user has one
+    // so that a link made by the user is never replaced, and a Slack user keeps one login
+    pub fn link_if_unlinked(&mut self, github_login: &str, slack_user_id: &str) -> bool {
+        if self.slack_user_id(github_login).is_some() || self.github_login(slack_user_id).is_some()
+        {
+            return false;
+        }
+        self.users
+            .insert(github_login.to_lowercase(), slack_user_id.to_string());
+        true
+    }
+
+    pub fn slack_user_id(&self, github_login: &str) -> Option<&str> {
+        self.users
+            .get(&github_login.to_lowercase())
+            .map(|user_id| user_id.as_str())
+    }
+
+    // the first login in order, for a map written before a Slack user had one login at most
+    pub fn github_login(&self, slack_user_id: &str) -> Option<&str> {
+        self.users
+            .iter()
+            .filter(|(_, user_id)| *user_id == slack_user_id)
+            .map(|(login, _)| login.as_str())
+            .min()
+    }
+}
+
+// https://github.com/shinnn/github-username-regex
+pub fn is_valid_github_login(login: &str) -> bool {
+    !login.is_empty()
+        && login.len() <= 39
+        && !login.starts_with('-')
+        && !login.ends_with('-')
+        && !login.contains("--")
+        && login.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+// "/mygithub link <login>" links the user, "/mygithub link" shows the current link
+pub fn handle_link_command(
+    user_map_path: &Path,
+    slack_user_id: &str,
+    args: &[String],
+    locale: Locale,
+) -> String {
+    let mut user_map: SlackUserMap = load_json(user_map_path);
+    let login = match args.first() {
+        Some(login) => login.trim_start_matches('@'),
+        None => {
+            return match user_map.github_login(slack_user_id) {
+                Some(login) => text_with(locale, "link.current", &[("login", login)]),
+                None => text(locale, "link.usage"),
+            };
+        }
+    };
+    if !is_valid_github_login(login) {
+        return text_with(locale, "link.invalid", &[("login", &escape_text(login))]);
+    }
+
+    if !user_map.link(login, slack_user_id) {
+        return text_with(locale, "link.taken", &[("login", login)]);
+    }
+    if let Err(err) = save_json(user_map_path, &user_map) {
+        return err.message;
+    }
+    text_with(locale, "link.done", &[("login", login)])
+}
+
+// the GitHub login of a Slack user, from the static entries first, then from the links
+// the first login in order is taken when the static entries map several to the user
+pub fn resolve_github_login(
+    slack_user_id: &str,
+    static_users: &HashMap<String, String>,
+    user_map_path: &Path,
+) -> Option<String> {
+    let static_login = static_users
+        .iter()
+        .filter(|(_, user_id)| *user_id == slack_user_id)
+        .map(|(login, _)| login)
+        .min();
+    if let Some(login) = static_login {
+        return Some(login.clone());
+    }
+    let user_map: SlackUserMap = load_json(user_map_path);
+    user_map
+        .github_login(slack_user_id)
+        .map(|login| login.to_string())
+}
+
+// the Slack user of a GitHub login, from the static entries first, then from the links
+// unlike resolve_slack_users, Slack is never asked
+pub fn resolve_slack_user_id(
+    github_login: &str,
+    static_users: &HashMap<String, String>,
+    user_map_path: &Path,
+) -> Option<String> {
+    if let Some(user_id) = static_users
+        .get(github_login)
+        .or_else(|| static_users.get(&github_login.to_lowercase()))
+    {
+        return Some(user_id.clone());
+    }
+    let user_map: SlackUserMap = load_json(user_map_path);
+    user_map
+        .slack_user_id(github_login)
+        .map(|user_id| user_id.to_string())
+}
+
+// the Slack user whose email is the public email of the GitHub user
+async fn lookup_slack_user_by_github_email(
+    slack_api_addr: &str,
+    slack_token: &str,
+    github_api_addr: &str,
+    github_token: &str,
+    github_login: &str,
+) -> Option<String> {
+    let profile = get_user_profile(github_api_addr, github_token, github_login)
+        .await
+        .ok()?;
+    let email = profile.email?;
+    let user = lookup_user_by_email(slack_api_addr, slack_token, &email)
+        .await
+        .ok()?;
+    Some(user.id)
+}
+
+// Slack users of the GitHub logins, logins without one are left out
+// the static entries come first, then the links, then the lookup by email,
+// which needs the bot token and whose results are saved for the next time
+pub async fn resolve_slack_users(
+    slack_api_addr: &str,
+    slack_token: Option<&str>,
+    github_api_addr: &str,
+    github_token: &str,
+    github_logins: &[&str],
+    static_users: &HashMap<String, String>,
+    user_map_path: &Path,
+) -> HashMap<String, String> {
+    let mut user_map: SlackUserMap = load_json(user_map_path);
+    let mut resolved = HashMap::new();
+    let mut is_changed = false;
+
+    for login in github_logins {
+        if resolved.contains_key(*login) {
+            continue;
+        }
+        let mut user_id = static_users
+            .get(*login)
+            .or_else(|| static_users.get(&login.to_lowercase()))
+            .cloned()
+            .or_else(|| user_map.slack_user_id(login).map(|id| id.to_string()));
+        if let (None, Some(slack_token)) = (&user_id, slack_token) {
+            user_id = lookup_slack_user_by_github_email(
+                slack_api_addr,
+                slack_token,
+                github_api_addr,
+                github_token,
+                login,
+            )
+            .await;
+            if let Some(user_id) = &user_id {
+                is_changed |= user_map.link_if_unlinked(login, user_id);
+            }
+        }
+        if let Some(user_id) = user_id {
+            resolved.insert(login.to_string(), user_id);
+        }
+    }
+
+    if is_changed {
+        if let Err(err) = save_json(user_map_path, &user_map) {
+            println!("{err}");
+        }
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_map_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir()
+            .join("github_notification_user_map_test")
+            .join(name);
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_slack_user_map_link() {
+        let mut user_map = SlackUserMap::default();
+        user_map.link("OctoCat", "U1");
+        assert_eq!(user_map.slack_user_id("octocat"), Some("U1"));
+        assert_eq!(user_map.github_login("U1"), Some("octocat"));
+
+        // linking another login replaces the link of the user
+        user_map.link("hubot", "U1");
+        assert_eq!(user_map.slack_user_id("octocat"), None);
+        assert_eq!(user_map.github_login("U1"), Some("hubot"));
+        assert_eq!(user_map.github_login("U2"), None);
+    }
+
+    #[test]
+    fn test_slack_user_map_link_conflict() {
+        let mut user_map = SlackUserMap::default();
+        assert!(user_map.link("octocat", "U1"));
+        assert!(user_map.link("hubot", "U2"));
+
+        // a login linked to another Slack user is kept as it is
+        assert!(!user_map.link("OctoCat", "U2"));
+        assert_eq!(user_map.slack_user_id("octocat"), Some("U1"));
+        assert_eq!(user_map.github_login("U2"), Some("hubot"));
+
+        // linking the same login again is not a conflict
+        assert!(user_map.link("octocat", "U1"));
+        assert_eq!(user_map.github_login("U1"), Some("octocat"));
+    }
+
+    #[test]
+    fn test_slack_user_map_link_if_unlinked() {
+        let mut user_map = SlackUserMap::default();
+        user_map.link("octocat", "U1");
+        // neither the login nor the Slack user gets a second link
+        assert!(!user_map.link_if_unlinked("octocat", "U2"));
+        assert!(!user_map.link_if_unlinked("hubot", "U1"));
+        assert!(user_map.link_if_unlinked("Hubot", "U2"));
+        assert_eq!(user_map.github_login("U1"), Some("octocat"));
+        assert_eq!(user_map.github_login("U2"), Some("hubot"));
+
+        // a map written with two logins of a user still gives the same one
+        user_map.users.insert("bob".to_string(), "U1".to_string());
+        assert_eq!(user_map.github_login("U1"), Some("bob"));
+    }
+
+    #[test]
+    fn test_is_valid_github_login() {
+        assert!(is_valid_github_login("octocat"));
+        assert!(is_valid_github_login("octo-cat1"));
+        assert!(!is_valid_github_login(""));
+        assert!(!is_valid_github_login("-octocat"));
+        assert!(!is_valid_github_login("octo--cat"));
+        assert!(!is_valid_github_login("octo cat"));
+        assert!(!is_valid_github_login(&"a".repeat(40)));
+    }
+
+    #[test]
+    fn test_handle_link_command() {
+        let path = user_map_path("link.json");
+
+        let text = handle_link_command(&path, "U1", &[], Locale::Ja);
+        assert_eq!(
+            text,
+            "`/mygithub link <GitHub ユーザー名>` で連携してください"
+        );
+
+        let text = handle_link_command(&path, "U1", &["@octocat".to_string()], Locale::Ja);
+        assert_eq!(
+            text,
+            "GitHub ユーザー octocat と連携しました。公開リポジトリの Issue のみ表示されます"
+        );
+        let user_map: SlackUserMap = load_json(&path);
+        assert_eq!(user_map.slack_user_id("octocat"), Some("U1"));
+
+        let text = handle_link_command(&path, "U1", &[], Locale::Ja);
+        assert_eq!(text, "GitHub ユーザー octocat と連携しています");
+
+        let text = handle_link_command(&path, "U1", &["<!channel>".to_string()], Locale::Ja);
+        assert_eq!(
+            text,
+            "GitHub ユーザー名が正しくありません: &lt;!channel&gt;"
+        );
+
+        let text = handle_link_command(&path, "U1", &[], Locale::En);
+        assert_eq!(text, "Linked to GitHub user octocat");
+
+        let text = handle_link_command(&path, "U2", &["octocat".to_string()], Locale::En);
+        assert_eq!(
+            text,
+            "GitHub user octocat is already linked to another Slack user"
+        );
+        let user_map: SlackUserMap = load_json(&path);
+        assert_eq!(user_map.slack_user_id("octocat"), Some("U1"));
+    }
+
+    #[test]
+    fn test_resolve_github_login() {
+        let path = user_map_path("resolve_github_login.json");
+        let mut user_map = SlackUserMap::default();
+        user_map.link("octocat", "U1");
+        user_map.link("hubot", "U2");
+        save_json(&path, &user_map).unwrap();
+        let static_users = HashMap::from([
+            ("monalisa".to_string(), "U1".to_string()),
+            ("mona".to_string(), "U3".to_string()),
+            ("lisa".to_string(), "U3".to_string()),
+        ]);
+
+        // the static entries come before the links
+        assert_eq!(
+            resolve_github_login("U1", &static_users, &path),
+            Some("monalisa".to_string())
+        );
+        assert_eq!(
+            resolve_github_login("U2", &static_users, &path),
+            Some("hubot".to_string())
+        );
+        assert_eq!(
+            resolve_github_login("U3", &static_users, &path),
+            Some("lisa".to_string())
+        );
+        assert_eq!(resolve_github_login("U4", &static_users, &path), None);
+    }
+
+    #[test]
+    fn test_resolve_slack_user_id() {
+        let path = user_map_path("resolve_slack_user_id.json");
+        let mut user_map = SlackUserMap::default();
+        user_map.link("octocat", "U1");
+        user_map.link("hubot", "U2");
+        save_json(&path, &user_map).unwrap();
+        let static_users = HashMap::from([("octocat".to_string(), "U3".to_string())]);
+
+        // the static entries come before the links
+        assert_eq!(
+            resolve_slack_user_id("Octocat", &static_users, &path),
+            Some("U3".to_string())
+        );
+        assert_eq!(
+            resolve_slack_user_id("HUBOT", &static_users, &path),
+            Some("U2".to_string())
+        );
+        assert_eq!(
+            resolve_slack_user_id("monalisa", &static_users, &path),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_slack_users() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let profile_mock = server.mock(|when, then| {
+            when.method(GET).path("/users/carol");
+            then.status(200)
+                .json_body(serde_json::json!({"login": "carol", "email": "carol@example.com"}));
+        });
+        let hidden_profile_mock = server.mock(|when, then| {
+            when.method(GET).path("/users/dave");
+            then.status(200)
+                .json_body(serde_json::json!({"login": "dave", "email": null}));
+        });
+        let lookup_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users.lookupByEmail")
+                .query_param("email", "carol@example.com");
+            then.status(200)
+                .json_body(serde_json::json!({"ok": true, "user": {"id": "U3"}}));
+        });
+        let mock_addr = format!("http://{}", server.address());
+        let path = user_map_path("resolve.json");
+        let mut user_map = SlackUserMap::default();
+        user_map.link("bob", "U2");
+        save_json(&path, &user_map).unwrap();
+        let static_users = HashMap::from([("alice".to_string(), "U1".to_string())]);
+
+        let resolved = resolve_slack_users(
+            &mock_addr,
+            Some("token"),
+            &mock_addr,
+            "token",
+            &["alice", "bob", "carol", "dave", "alice"],
+            &static_users,
+            &path,
+        )
+        .await;
+        profile_mock.assert();
+        hidden_profile_mock.assert();
+        lookup_mock.assert();
+        assert_eq!(resolved.len(), 3);
+        assert_eq!(resolved["alice"], "U1");
+        assert_eq!(resolved["bob"], "U2");
+        assert_eq!(resolved["carol"], "U3");
+
+        // the lookup is saved
+        let user_map: SlackUserMap = load_json(&path);
+        assert_eq!(user_map.slack_user_id("carol"), Some("U3"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_slack_users_without_slack_token() {
+        let path = user_map_path("resolve_without_token.json");
+        let resolved = resolve_slack_users(
+            "http://localhost",
+            None,
+            "http://localhost",
+            "token",
+            &["carol"],
+            &HashMap::new(),
+            &path,
+        )
+        .await;
+        assert!(resolved.is_empty());
+    }
+}
@@ -0,0 +1,149 @@
+use crate::errors::GetIssueError;
+use crate::models::{Issue, IssueRef};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const SNOOZED_ISSUES_FILE_NAME: &str = "snoozed_issues.json";
+
+// the days an issue can be snoozed for
+pub const SNOOZE_DAYS: [i64; 3] = [1, 3, 7];
+
+// the end of a snooze of `days` from `now`, None for days which are not offered
+pub fn snooze_until(now: i64, days: i64) -> Option<i64> {
+    if !SNOOZE_DAYS.contains(&days) {
+        return None;
+    }
+    days.checked_mul(24 * 60 * 60)
+        .and_then(|secs| now.checked_add(secs))
+}
+
+// whose digests a snooze hides the issue from
+// a snooze in the channel digest is for the channel, one in a personal digest is for its user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnoozeScope {
+    Channel(String),
+    User(String),
+}
+
+impl SnoozeScope {
+    // e.g. "channel:C1" or "user:U1"
+    fn key(&self) -> String {
+        match self {
+            SnoozeScope::Channel(channel_id) => format!("channel:{channel_id}"),
+            SnoozeScope::User(user_id) => format!("user:{user_id}"),
+        }
+    }
+}
+
+// issues hidden from the digests until the time, keyed by the scope, then by "owner/repo#number"
+// the snoozes saved before they had a scope are dropped, they were a week long at most
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SnoozedIssues {
+    #[serde(default)]
+    pub scopes: HashMap<String, HashMap<String, i64>>,
+}
+
+impl SnoozedIssues {
+    pub fn snooze(&mut self, scope: &SnoozeScope, issue_ref: &IssueRef, until: i64) {
+        self.scopes
+            .entry(scope.key())
+            .or_default()
+            .insert(issue_ref.to_string(), until);
+    }
+
+    pub fn is_snoozed(&self, scope: &SnoozeScope, issue_ref: &IssueRef, now: i64) -> bool {
+        match self
+            .scopes
+            .get(&scope.key())
+            .and_then(|until| until.get(&issue_ref.to_string()))
+        {
+            Some(until) => now < *until,
+            None => false,
+        }
+    }
+
+    pub fn remove_expired(&mut self, now: i64) {
+        for until in self.scopes.values_mut() {
+            until.retain(|_, until| now < *until);
+        }
+        self.scopes.retain(|_, until| !until.is_empty());
+    }
+
+    pub fn filter_issues(
+        &self,
+        scope: &SnoozeScope,
+        issues: Result<Vec<Issue>, GetIssueError>,
+        now: i64,
+    ) -> Result<Vec<Issue>, GetIssueError> {
+        let issues = issues?;
+        Ok(issues
+            .into_iter()
+            .filter(|issue| match IssueRef::from_issue(issue) {
+                Some(issue_ref) => !self.is_snoozed(scope, &issue_ref, now),
+                None => true,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::issue;
+
+    #[test]
+    fn test_snooze() {
+        let mut snoozed_issues = SnoozedIssues::default();
+        let scope = SnoozeScope::Channel("C1".to_string());
+        let issue_ref = IssueRef::parse("owner/repo#1").unwrap();
+        snoozed_issues.snooze(&scope, &issue_ref, 100);
+        assert!(snoozed_issues.is_snoozed(&scope, &issue_ref, 50));
+        assert!(!snoozed_issues.is_snoozed(&scope, &issue_ref, 100));
+        snoozed_issues.remove_expired(100);
+        assert!(snoozed_issues.scopes.is_empty());
+    }
+
+    #[test]
+    fn test_snooze_scopes() {
+        let mut snoozed_issues = SnoozedIssues::default();
+        let issue_ref = IssueRef::parse("owner/repo#1").unwrap();
+        snoozed_issues.snooze(&SnoozeScope::User("U1".to_string()), &issue_ref, 100);
+
+        // a snooze of a user hides the issue from no one else
+        assert!(snoozed_issues.is_snoozed(&SnoozeScope::User("U1".to_string()), &issue_ref, 50));
+        assert!(!snoozed_issues.is_snoozed(&SnoozeScope::User("U2".to_string()), &issue_ref, 50));
+        assert!(!snoozed_issues.is_snoozed(
+            &SnoozeScope::Channel("U1".to_string()),
+            &issue_ref,
+            50
+        ));
+
+        // a file saved before the scopes is read as no snoozes
+        let snoozed_issues: SnoozedIssues =
+            serde_json::from_str(r#"{"until": {"owner/repo#1": 100}}"#).unwrap();
+        assert!(snoozed_issues.scopes.is_empty());
+    }
+
+    #[test]
+    fn test_snooze_until() {
+        assert_eq!(snooze_until(0, 3), Some(3 * 24 * 60 * 60));
+        assert_eq!(snooze_until(0, 2), None);
+        assert_eq!(snooze_until(0, i64::MAX), None);
+        assert_eq!(snooze_until(i64::MAX, 1), None);
+    }
+
+    #[test]
+    fn test_filter_issues() {
+        let mut snoozed_issues = SnoozedIssues::default();
+        let scope = SnoozeScope::Channel("C1".to_string());
+        snoozed_issues.snooze(&scope, &IssueRef::parse("owner/name#1").unwrap(), 100);
+        let issues = snoozed_issues.filter_issues(
+            &scope,
+            Ok(vec![issue(1, "title", None), issue(2, "title", None)]),
+            50,
+        );
+        let issues = issues.unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].number, 2);
+    }
+}
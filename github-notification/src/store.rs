@@ -0,0 +1,72 @@
+use crate::errors::StoreError;
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::{Path, PathBuf};
+
+// build the path of a state file under the state directory
+pub fn state_file_path(state_dir: &str, file_name: &str) -> PathBuf {
+    Path::new(state_dir).join(file_name)
+}
+
+// load a JSON state file
+// a missing or broken file is treated as an empty state
+pub fn load_json<T: DeserializeOwned + Default>(path: &Path) -> T {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return T::default(),
+    };
+    match serde_json::from_str(&content) {
+        Ok(value) => value,
+        Err(e) => {
+            println!("Parse State Error: {}: {e}", path.display());
+            T::default()
+        }
+    }
+}
+
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<(), StoreError> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent).map_err(|e| StoreError {
+                message: format!("Create State Dir Error: {e}"),
+            })?;
+        }
+    }
+    let content = serde_json::to_string_pretty(value).map_err(|e| StoreError {
+        message: format!("Serialize State Error: {e}"),
+    })?;
+    std::fs::write(path, content).map_err(|e| StoreError {
+        message: format!("Write State Error: {e}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_state_file_path() {
+        let path = state_file_path("state", "test.json");
+        assert_eq!(path, Path::new("state").join("test.json"));
+    }
+
+    #[test]
+    fn test_load_json_missing_file() {
+        let path = std::env::temp_dir().join("github_notification_store_missing.json");
+        let _ = std::fs::remove_file(&path);
+        let value: HashMap<String, String> = load_json(&path);
+        assert!(value.is_empty());
+    }
+
+    #[test]
+    fn test_save_and_load_json() {
+        let path = std::env::temp_dir()
+            .join("github_notification_store_test")
+            .join("test.json");
+        let mut value = HashMap::new();
+        value.insert("key".to_string(), "value".to_string());
+        assert!(save_json(&path, &value).is_ok());
+        let loaded: HashMap<String, String> = load_json(&path);
+        assert_eq!(loaded, value);
+    }
+}
@@ -0,0 +1,363 @@
+use super::markdown::{escape_text, link};
+use crate::digest::Digest;
+use crate::i18n::{plural_text, relative_date, text, text_with, Locale};
+use crate::models::{Issue, Priority};
+use crate::slack::style::DigestStyle;
+use serde::Serialize;
+
+pub const ADAPTIVE_CARD_SCHEMA: &str = "http://adaptivecards.io/schemas/adaptive-card.json";
+pub const ADAPTIVE_CARD_CONTENT_TYPE: &str = "application/vnd.microsoft.card.adaptive";
+// the latest version which both Teams and Workflows render
+pub const ADAPTIVE_CARD_VERSION: &str = "1.4";
+
+// a message of Teams is limited to about 28KB, so long sections are cut
+pub const MAX_ISSUES_PER_SECTION: usize = 50;
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TextBlock {
+    pub text: String,
+    pub wrap: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_subtle: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spacing: Option<&'static str>,
+}
+
+impl TextBlock {
+    pub fn new(text: String) -> Self {
+        Self {
+            text,
+            wrap: true,
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(tag = "type")]
+pub enum CardAction {
+    #[serde(rename = "Action.ToggleVisibility", rename_all = "camelCase")]
+    ToggleVisibility { target_elements: Vec<String> },
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Container {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub items: Vec<CardElement>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_visible: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub select_action: Option<CardAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub separator: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(tag = "type")]
+pub enum CardElement {
+    TextBlock(TextBlock),
+    Container(Container),
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct TeamsCardOptions {
+    pub width: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct AdaptiveCard {
+    #[serde(rename = "$schema")]
+    pub schema: &'static str,
+    #[serde(rename = "type")]
+    pub card_type: &'static str,
+    pub version: &'static str,
+    pub body: Vec<CardElement>,
+    pub msteams: TeamsCardOptions,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamsAttachment {
+    pub content_type: &'static str,
+    pub content_url: Option<String>,
+    pub content: AdaptiveCard,
+}
+
+// the body which incoming webhooks and Workflows endpoints both take
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct TeamsMessage {
+    #[serde(rename = "type")]
+    pub message_type: &'static str,
+    pub attachments: Vec<TeamsAttachment>,
+}
+
+impl TeamsMessage {
+    pub fn new(card: AdaptiveCard) -> Self {
+        Self {
+            message_type: "message",
+            attachments: vec![TeamsAttachment {
+                content_type: ADAPTIVE_CARD_CONTENT_TYPE,
+                content_url: None,
+                content: card,
+            }],
+        }
+    }
+}
+
+// a color of the card theme, as Teams has no custom colors
+fn priority_color(priority: Priority) -> &'static str {
+    match priority {
+        Priority::High => "Attention",
+        Priority::Medium => "Warning",
+        Priority::Low => "Accent",
+        Priority::None => "Default",
+    }
+}
+
+// the id of the container which a section header shows and hides
+fn section_id(priority: Priority) -> String {
+    format!("priority-{}", priority.key())
+}
+
+// the linked title, then the repository, labels and due date in small text
+fn create_issue_container(
+    issue: &Issue,
+    priority: Priority,
+    locale: Locale,
+    style: &DigestStyle,
+    digest: &Digest,
+) -> Container {
+    let repository = &issue.repository;
+    let mut details = vec![link(&repository.html_url, &repository.full_name)];
+    details.extend(
+        style
+            .visible_labels(issue, priority)
+            .into_iter()
+            .map(|label| escape_text(&label.name)),
+    );
+    if let Some(due_on) = issue
+        .milestone
+        .as_ref()
+        .and_then(|milestone| milestone.due_on)
+    {
+        let date = relative_date(locale, due_on, digest.generated_at);
+        details.push(text_with(locale, "digest.due", &[("date", &date)]));
+    }
+    Container {
+        items: vec![
+            CardElement::TextBlock(TextBlock::new(link(&issue.html_url, &issue.title))),
+            CardElement::TextBlock(TextBlock {
+                size: Some("Small"),
+                is_subtle: Some(true),
+                spacing: Some("None"),
+                ..TextBlock::new(details.join(" · "))
+            }),
+        ],
+        ..Container::default()
+    }
+}
+
+// a header which shows and hides the issues of the priority when clicked
+// only the first section is open at first
+fn add_priority_section(
+    body: &mut Vec<CardElement>,
+    priority: Priority,
+    issues: &[Issue],
+    is_open: bool,
+    locale: Locale,
+    style: &DigestStyle,
+    digest: &Digest,
+) {
+    let id = section_id(priority);
+    let header = format!(
+        "{} ({})",
+        escape_text(&style.priority_title(priority, locale)),
+        issues.len()
+    );
+    body.push(CardElement::Container(Container {
+        items: vec![CardElement::TextBlock(TextBlock {
+            weight: Some("Bolder"),
+            color: Some(priority_color(priority)),
+            ..TextBlock::new(header)
+        })],
+        select_action: Some(CardAction::ToggleVisibility {
+            target_elements: vec![id.clone()],
+        }),
+        separator: Some(true),
+        ..Container::default()
+    }));
+
+    let mut items: Vec<CardElement> = issues
+        .iter()
+        .take(MAX_ISSUES_PER_SECTION)
+        .map(|issue| {
+            CardElement::Container(create_issue_container(
+                issue, priority, locale, style, digest,
+            ))
+        })
+        .collect();
+    if issues.len() > MAX_ISSUES_PER_SECTION {
+        let omitted_count = issues.len() - MAX_ISSUES_PER_SECTION;
+        items.push(CardElement::TextBlock(TextBlock {
+            is_subtle: Some(true),
+            ..TextBlock::new(plural_text(locale, "digest.more", omitted_count as i64))
+        }));
+    }
+    body.push(CardElement::Container(Container {
+        id: Some(id),
+        items,
+        is_visible: Some(is_open),
+        ..Container::default()
+    }));
+}
+
+// the digest as an Adaptive Card, grouped by priority like the Slack digest
+pub fn create_adaptive_card(digest: &Digest, locale: Locale, style: &DigestStyle) -> AdaptiveCard {
+    let mut body = vec![CardElement::TextBlock(TextBlock {
+        size: Some("Large"),
+        weight: Some("Bolder"),
+        ..TextBlock::new(text(locale, "digest.title"))
+    })];
+    match &digest.issues {
+        Err(e) => body.push(CardElement::TextBlock(TextBlock {
+            color: Some("Attention"),
+            ..TextBlock::new(escape_text(&e.message))
+        })),
+        Ok(_) if digest.issue_count() == 0 => body.push(CardElement::TextBlock(TextBlock::new(
+            text(locale, "digest.summary.empty"),
+        ))),
+        Ok(_) => {
+            for (i, (priority, issues)) in digest.groups().into_iter().enumerate() {
+                add_priority_section(&mut body, priority, issues, i == 0, locale, style, digest);
+            }
+        }
+    }
+    AdaptiveCard {
+        schema: ADAPTIVE_CARD_SCHEMA,
+        card_type: "AdaptiveCard",
+        version: ADAPTIVE_CARD_VERSION,
+        body,
+        msteams: TeamsCardOptions { width: "Full" },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::GetIssueError;
+    use crate::fixtures::{issue, now};
+    use crate::models::SortedIssues;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_create_adaptive_card() {
+        let due_issue = issue(
+            2,
+            "[bug] fix *bold*",
+            Some(Utc.with_ymd_and_hms(2023, 1, 11, 0, 0, 0).unwrap()),
+        );
+        let digest = Digest::new(
+            Ok(SortedIssues {
+                priority_high_issues: vec![issue(1, "[bug] fix *bold*", None)],
+                priority_none_issues: vec![due_issue],
+                ..SortedIssues::default()
+            }),
+            now(),
+        );
+        let card = create_adaptive_card(&digest, Locale::En, &DigestStyle::default());
+        let json = serde_json::to_value(&card).unwrap();
+        assert_eq!(json["type"], "AdaptiveCard");
+        assert_eq!(json["body"][0]["text"], "Tasks");
+
+        // a header toggles the container of its issues
+        let header = &json["body"][1];
+        assert_eq!(header["items"][0]["text"], "🔴 Priority: High (1)");
+        assert_eq!(header["items"][0]["color"], "Attention");
+        assert_eq!(
+            header["selectAction"],
+            serde_json::json!({"type": "Action.ToggleVisibility", "targetElements": ["priority-high"]})
+        );
+        let section = &json["body"][2];
+        assert_eq!(section["id"], "priority-high");
+        assert_eq!(section["isVisible"], true);
+        assert_eq!(
+            section["items"][0]["items"][0]["text"],
+            // no backslash escapes, which Teams would show as they are
+            "[[bug］ fix ∗bold∗](https://github.com/owner/name/issues/1)"
+        );
+        assert_eq!(
+            section["items"][0]["items"][1]["text"],
+            "[owner/name](https://github.com/owner/name) · bug"
+        );
+
+        // the other sections are closed at first
+        let section = &json["body"][4];
+        assert_eq!(section["id"], "priority-none");
+        assert_eq!(section["isVisible"], false);
+        assert_eq!(
+            section["items"][0]["items"][1]["text"],
+            "[owner/name](https://github.com/owner/name) · bug · (due tomorrow)"
+        );
+    }
+
+    #[test]
+    fn test_create_adaptive_card_cuts_long_section() {
+        let digest = Digest::new(
+            Ok(SortedIssues {
+                priority_low_issues: (0..60)
+                    .map(|number| issue(number, "[bug] fix *bold*", None))
+                    .collect(),
+                ..SortedIssues::default()
+            }),
+            now(),
+        );
+        let card = create_adaptive_card(&digest, Locale::Ja, &DigestStyle::default());
+        let section = match &card.body[2] {
+            CardElement::Container(container) => container,
+            other => panic!("unexpected element: {other:?}"),
+        };
+        assert_eq!(section.items.len(), MAX_ISSUES_PER_SECTION + 1);
+        assert_eq!(
+            section.items.last(),
+            Some(&CardElement::TextBlock(TextBlock {
+                is_subtle: Some(true),
+                ..TextBlock::new("ほか 10 件".to_string())
+            }))
+        );
+    }
+
+    #[test]
+    fn test_create_adaptive_card_error() {
+        let digest = Digest::new(
+            Err(GetIssueError {
+                message: "Get Issue Error: 401".to_string(),
+            }),
+            now(),
+        );
+        let card = create_adaptive_card(&digest, Locale::En, &DigestStyle::default());
+        assert_eq!(card.body.len(), 2);
+        let message = serde_json::to_value(TeamsMessage::new(card)).unwrap();
+        assert_eq!(message["type"], "message");
+        assert_eq!(
+            message["attachments"][0]["contentType"],
+            ADAPTIVE_CARD_CONTENT_TYPE
+        );
+        assert_eq!(
+            message["attachments"][0]["contentUrl"],
+            serde_json::Value::Null
+        );
+        assert_eq!(
+            message["attachments"][0]["content"]["body"][1]["text"],
+            "Get Issue Error: 401"
+        );
+    }
+}
@@ -0,0 +1,77 @@
+// the markdown subset of Adaptive Card TextBlocks: **bold**, _italic_, lists and [links](url)
+// https://learn.microsoft.com/en-us/adaptive-cards/authoring-cards/text-features
+// backslash escapes aren't part of it and may be shown as they are,
+// so the characters which start formatting are replaced with look-alikes instead
+
+const ZERO_WIDTH_SPACE: char = '\u{200B}';
+
+// user-controlled text, e.g. issue titles and label names, shown as it is
+pub fn escape_text(text: &str) -> String {
+    let mut escaped: String = text
+        .chars()
+        .map(|c| match c {
+            // asterisk operator
+            '*' => '\u{2217}',
+            // modifier letter low macron
+            '_' => '\u{02CD}',
+            // a list item or a paragraph would start
+            '\n' | '\r' => ' ',
+            c => c,
+        })
+        .collect();
+    // "](" makes a link, which a zero width space in between breaks
+    escaped = escaped.replace("](", &format!("]{ZERO_WIDTH_SPACE}("));
+    // "- ", "+ " and "1. " start a list only at the start of a line
+    let starts_list = escaped.starts_with(['-', '+'])
+        || escaped.split_once('.').is_some_and(|(number, _)| {
+            !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
+        });
+    if starts_list {
+        escaped.insert(0, ZERO_WIDTH_SPACE);
+    }
+    escaped
+}
+
+// characters which end the URL part of a link
+fn escape_url(url: &str) -> String {
+    url.replace('(', "%28")
+        .replace(')', "%29")
+        .replace(' ', "%20")
+}
+
+// [text](url)
+pub fn link(url: &str, text: &str) -> String {
+    format!(
+        "[{}]({})",
+        escape_text(text).replace(']', "\u{FF3D}"),
+        escape_url(url)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_text() {
+        assert_eq!(escape_text("fix *bold* and _it_"), "fix ∗bold∗ and ˍitˍ");
+        // no backslash is left to be shown
+        assert!(!escape_text("a\\b *c*").contains("\\*"));
+        assert_eq!(
+            escape_text("[a](https://example.com)"),
+            "[a]\u{200B}(https://example.com)"
+        );
+        assert_eq!(escape_text("- item"), "\u{200B}- item");
+        assert_eq!(escape_text("1. item"), "\u{200B}1. item");
+        assert_eq!(escape_text("v1.2 release"), "v1.2 release");
+        assert_eq!(escape_text("line\nbreak"), "line break");
+    }
+
+    #[test]
+    fn test_link() {
+        assert_eq!(
+            link("https://example.com/a (b)", "fix [docs]"),
+            "[fix [docs］](https://example.com/a%20%28b%29)"
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub mod card;
+pub mod markdown;
+pub mod notifier;
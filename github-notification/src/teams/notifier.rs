@@ -0,0 +1,143 @@
+use super::card::{create_adaptive_card, TeamsMessage};
+use crate::digest::Digest;
+use crate::errors::{NotifyError, TeamsApiError};
+use crate::i18n::Locale;
+use crate::notifier::{Notifier, TEAMS_NOTIFIER};
+use crate::slack::style::DigestStyle;
+use async_trait::async_trait;
+
+// post a message to an incoming webhook or a Workflows endpoint
+// an incoming webhook answers 200 with "1", a Workflows endpoint answers 202,
+// and an incoming webhook may answer 200 with the error in the body
+pub async fn post_teams_message(
+    webhook_url: &str,
+    message: &TeamsMessage,
+) -> Result<(), TeamsApiError> {
+    let client = reqwest::Client::new();
+    let res = client
+        .post(webhook_url)
+        .json(message)
+        .send()
+        .await
+        .map_err(|err| TeamsApiError {
+            message: format!("Teams Webhook Error: {err}"),
+        })?;
+    let status = res.status();
+    if !status.is_success() {
+        return Err(TeamsApiError {
+            message: format!("Teams Webhook Error: {}", status.as_u16()),
+        });
+    }
+    let body = res.text().await.unwrap_or_default();
+    if body.to_lowercase().contains("error") {
+        return Err(TeamsApiError {
+            message: format!("Teams Webhook Error: {}", body.trim()),
+        });
+    }
+    Ok(())
+}
+
+pub struct TeamsWebhookNotifier {
+    pub webhook_url: String,
+    pub locale: Locale,
+    pub style: DigestStyle,
+}
+
+#[async_trait]
+impl Notifier for TeamsWebhookNotifier {
+    fn name(&self) -> &str {
+        TEAMS_NOTIFIER
+    }
+
+    async fn notify(&self, digest: &Digest) -> Result<(), NotifyError> {
+        let card = create_adaptive_card(digest, self.locale, &self.style);
+        post_teams_message(&self.webhook_url, &TeamsMessage::new(card)).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SortedIssues;
+    use chrono::Utc;
+
+    fn notifier(webhook_url: String) -> TeamsWebhookNotifier {
+        TeamsWebhookNotifier {
+            webhook_url,
+            locale: Locale::En,
+            style: DigestStyle::default(),
+        }
+    }
+
+    fn digest() -> Digest {
+        Digest::new(Ok(SortedIssues::default()), Utc::now())
+    }
+
+    #[tokio::test]
+    async fn test_teams_notifier_incoming_webhook() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/webhookb2/1")
+                .json_body_partial(r#"{"type": "message"}"#)
+                .body_contains("application/vnd.microsoft.card.adaptive");
+            then.status(200).body("1");
+        });
+        let notifier = notifier(format!("http://{}/webhookb2/1", server.address()));
+        notifier.notify(&digest()).await.unwrap();
+        mock.assert();
+        assert_eq!(notifier.name(), "teams");
+    }
+
+    #[tokio::test]
+    async fn test_teams_notifier_workflows() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/workflows/1/triggers/manual/paths/invoke");
+            then.status(202);
+        });
+        let notifier = notifier(format!(
+            "http://{}/workflows/1/triggers/manual/paths/invoke",
+            server.address()
+        ));
+        notifier.notify(&digest()).await.unwrap();
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_teams_notifier_errors() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let error_body_mock = server.mock(|when, then| {
+            when.method(POST).path("/error-body");
+            then.status(200)
+                .body("Microsoft Teams endpoint returned HTTP error 413");
+        });
+        let error_status_mock = server.mock(|when, then| {
+            when.method(POST).path("/error-status");
+            then.status(400);
+        });
+        let err = notifier(format!("http://{}/error-body", server.address()))
+            .notify(&digest())
+            .await
+            .unwrap_err();
+        error_body_mock.assert();
+        assert_eq!(
+            err.message,
+            "Teams Webhook Error: Microsoft Teams endpoint returned HTTP error 413"
+        );
+        let err = notifier(format!("http://{}/error-status", server.address()))
+            .notify(&digest())
+            .await
+            .unwrap_err();
+        error_status_mock.assert();
+        assert_eq!(err.message, "Teams Webhook Error: 400");
+    }
+}
@@ -0,0 +1,193 @@
+use super::payload::create_webhook_payload;
+use crate::digest::Digest;
+use crate::errors::{NotifyError, WebhookError};
+use crate::notifier::{Notifier, WEBHOOK_NOTIFIER};
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::Duration;
+
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-Signature";
+
+// e.g. "sha256=<hex>", the HMAC-SHA256 of the raw body by the shared secret
+pub fn compute_webhook_signature(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+pub struct WebhookNotifier {
+    pub url: String,
+    pub secret: String,
+    // for each attempt
+    pub timeout: Duration,
+    pub max_attempts: u32,
+    // doubled after each failed attempt
+    pub retry_delay: Duration,
+}
+
+impl WebhookNotifier {
+    // an error, and whether it is worth another attempt
+    async fn post(
+        &self,
+        client: &reqwest::Client,
+        body: &[u8],
+        signature: &str,
+    ) -> Result<(), (WebhookError, bool)> {
+        let res = client
+            .post(&self.url)
+            .timeout(self.timeout)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(WEBHOOK_SIGNATURE_HEADER, signature)
+            .body(body.to_vec())
+            .send()
+            .await
+            .map_err(|err| {
+                let error = WebhookError {
+                    message: format!("Webhook Error: {err}"),
+                };
+                (error, true)
+            })?;
+        let status = res.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let error = WebhookError {
+            message: format!("Webhook Error: {}", status.as_u16()),
+        };
+        // a client error other than rate limiting would fail again
+        let is_retryable = status.is_server_error() || status.as_u16() == 429;
+        Err((error, is_retryable))
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &str {
+        WEBHOOK_NOTIFIER
+    }
+
+    // the same body and signature are sent on every attempt
+    async fn notify(&self, digest: &Digest) -> Result<(), NotifyError> {
+        let body =
+            serde_json::to_vec(&create_webhook_payload(digest)).map_err(|err| WebhookError {
+                message: format!("Webhook Error: {err}"),
+            })?;
+        let signature = compute_webhook_signature(&self.secret, &body);
+        let client = reqwest::Client::new();
+        let mut delay = self.retry_delay;
+        let mut attempt = 1;
+        loop {
+            match self.post(&client, &body, &signature).await {
+                Ok(()) => return Ok(()),
+                Err((err, true)) if attempt < self.max_attempts => {
+                    println!("{err}, retrying in {}ms", delay.as_millis());
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+                Err((err, _)) => return Err(err.into()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SortedIssues;
+    use chrono::Utc;
+
+    fn notifier(url: String) -> WebhookNotifier {
+        WebhookNotifier {
+            url,
+            secret: "secret".to_string(),
+            timeout: Duration::from_secs(1),
+            max_attempts: 3,
+            retry_delay: Duration::from_millis(10),
+        }
+    }
+
+    fn digest() -> Digest {
+        Digest::new(Ok(SortedIssues::default()), Utc::now())
+    }
+
+    #[test]
+    fn test_compute_webhook_signature() {
+        // echo -n '{"version":1}' | openssl dgst -sha256 -hmac secret
+        assert_eq!(
+            compute_webhook_signature("secret", br#"{"version":1}"#),
+            "sha256=5bf41a7738bdf2b8c02d61765ceaf8e6fb4f1f2973b7db89386a3f399fba16bf"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_webhook_notifier() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let digest = digest();
+        let body = serde_json::to_vec(&create_webhook_payload(&digest)).unwrap();
+        let signature = compute_webhook_signature("secret", &body);
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/hooks/tasks")
+                .header("content-type", "application/json")
+                .header(WEBHOOK_SIGNATURE_HEADER, &signature)
+                .json_body_partial(r#"{"version": 1, "issue_count": 0}"#);
+            then.status(204);
+        });
+        let notifier = notifier(format!("http://{}/hooks/tasks", server.address()));
+        notifier.notify(&digest).await.unwrap();
+        mock.assert();
+        assert_eq!(notifier.name(), "webhook");
+    }
+
+    #[tokio::test]
+    async fn test_webhook_notifier_retries_server_error() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST).path("/hooks/tasks");
+            then.status(503);
+        });
+        let notifier = notifier(format!("http://{}/hooks/tasks", server.address()));
+        let err = notifier.notify(&digest()).await.unwrap_err();
+        mock.assert_hits(3);
+        assert_eq!(err.message, "Webhook Error: 503");
+    }
+
+    #[tokio::test]
+    async fn test_webhook_notifier_does_not_retry_client_error() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST).path("/hooks/tasks");
+            then.status(400);
+        });
+        let notifier = notifier(format!("http://{}/hooks/tasks", server.address()));
+        let err = notifier.notify(&digest()).await.unwrap_err();
+        mock.assert_hits(1);
+        assert_eq!(err.message, "Webhook Error: 400");
+    }
+
+    #[tokio::test]
+    async fn test_webhook_notifier_retries_timeout() {
+        use httpmock::prelude::*;
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST).path("/hooks/tasks");
+            then.status(200).delay(Duration::from_millis(500));
+        });
+        let mut notifier = notifier(format!("http://{}/hooks/tasks", server.address()));
+        notifier.timeout = Duration::from_millis(100);
+        notifier.max_attempts = 2;
+        let err = notifier.notify(&digest()).await.unwrap_err();
+        mock.assert_hits(2);
+        assert!(err.message.starts_with("Webhook Error: "));
+    }
+}
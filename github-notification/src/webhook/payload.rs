@@ -0,0 +1,206 @@
+use crate::digest::Digest;
+use crate::models::{Issue, Priority};
+use chrono::{DateTime, Utc};
+use schemars::{schema::RootSchema, schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+// raised only on breaking changes, a new optional field keeps the version
+pub const WEBHOOK_PAYLOAD_VERSION: u32 = 1;
+
+// the schema published for the consumers, see schema_json
+pub const WEBHOOK_PAYLOAD_SCHEMA_FILE_NAME: &str = "webhook-payload.v1.json";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookPriority {
+    High,
+    Medium,
+    Low,
+    None,
+}
+
+impl From<Priority> for WebhookPriority {
+    fn from(priority: Priority) -> Self {
+        match priority {
+            Priority::High => Self::High,
+            Priority::Medium => Self::Medium,
+            Priority::Low => Self::Low,
+            Priority::None => Self::None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct WebhookRepository {
+    // owner/name
+    pub full_name: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct WebhookIssue {
+    pub number: i64,
+    pub title: String,
+    pub url: String,
+    pub repository: WebhookRepository,
+    pub labels: Vec<String>,
+    // GitHub logins
+    pub assignees: Vec<String>,
+    // the due date of the milestone
+    pub due_on: Option<DateTime<Utc>>,
+    pub overdue: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct WebhookGroup {
+    pub priority: WebhookPriority,
+    pub issues: Vec<WebhookIssue>,
+}
+
+// the document POSTed by the webhook notifier
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct WebhookPayload {
+    pub version: u32,
+    pub generated_at: DateTime<Utc>,
+    pub issue_count: usize,
+    pub overdue_count: usize,
+    // why the issues could not be fetched, the groups are empty then
+    pub error: Option<String>,
+    // the priorities which have issues, from high to none
+    pub groups: Vec<WebhookGroup>,
+}
+
+fn create_webhook_issue(issue: &Issue, now: DateTime<Utc>) -> WebhookIssue {
+    WebhookIssue {
+        number: issue.number,
+        title: issue.title.clone(),
+        url: issue.html_url.clone(),
+        repository: WebhookRepository {
+            full_name: issue.repository.full_name.clone(),
+            url: issue.repository.html_url.clone(),
+        },
+        labels: issue
+            .labels
+            .iter()
+            .flatten()
+            .map(|label| label.name.clone())
+            .collect(),
+        assignees: issue
+            .assignee_logins()
+            .into_iter()
+            .map(|login| login.to_string())
+            .collect(),
+        due_on: issue
+            .milestone
+            .as_ref()
+            .and_then(|milestone| milestone.due_on),
+        overdue: issue.is_overdue(now),
+    }
+}
+
+pub fn create_webhook_payload(digest: &Digest) -> WebhookPayload {
+    WebhookPayload {
+        version: WEBHOOK_PAYLOAD_VERSION,
+        generated_at: digest.generated_at,
+        issue_count: digest.issue_count(),
+        overdue_count: digest.overdue_count(),
+        error: digest.issues.as_ref().err().map(|e| e.message.clone()),
+        groups: digest
+            .groups()
+            .into_iter()
+            .map(|(priority, issues)| WebhookGroup {
+                priority: priority.into(),
+                issues: issues
+                    .iter()
+                    .map(|issue| create_webhook_issue(issue, digest.generated_at))
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+pub fn webhook_payload_schema() -> RootSchema {
+    schema_for!(WebhookPayload)
+}
+
+// the schema as published in schema/webhook-payload.v1.json
+pub fn schema_json() -> String {
+    serde_json::to_string_pretty(&webhook_payload_schema()).unwrap() + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::GetIssueError;
+    use crate::fixtures;
+    use crate::models::{SortedIssues, User};
+    use chrono::TimeZone;
+
+    // an overdue issue assigned to octocat
+    fn issue(number: i64) -> Issue {
+        let due_on = Utc.with_ymd_and_hms(2023, 1, 9, 0, 0, 0).unwrap();
+        let mut issue = fixtures::issue(number, "title", Some(due_on));
+        issue.assignees = Some(vec![User {
+            login: "octocat".to_string(),
+        }]);
+        issue
+    }
+
+    #[test]
+    fn test_create_webhook_payload() {
+        let digest = Digest::new(
+            Ok(SortedIssues {
+                priority_medium_issues: vec![issue(1)],
+                ..SortedIssues::default()
+            }),
+            Utc.with_ymd_and_hms(2023, 1, 10, 0, 0, 0).unwrap(),
+        );
+        let payload = serde_json::to_value(create_webhook_payload(&digest)).unwrap();
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "version": 1,
+                "generated_at": "2023-01-10T00:00:00Z",
+                "issue_count": 1,
+                "overdue_count": 1,
+                "error": null,
+                "groups": [{
+                    "priority": "medium",
+                    "issues": [{
+                        "number": 1,
+                        "title": "title",
+                        "url": "https://github.com/owner/name/issues/1",
+                        "repository": {
+                            "full_name": "owner/name",
+                            "url": "https://github.com/owner/name"
+                        },
+                        "labels": ["bug"],
+                        "assignees": ["octocat"],
+                        "due_on": "2023-01-09T00:00:00Z",
+                        "overdue": true
+                    }]
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_create_webhook_payload_error() {
+        let digest = Digest::new(
+            Err(GetIssueError {
+                message: "Get Issue Error: 401".to_string(),
+            }),
+            Utc::now(),
+        );
+        let payload = create_webhook_payload(&digest);
+        assert_eq!(payload.error, Some("Get Issue Error: 401".to_string()));
+        assert!(payload.groups.is_empty());
+    }
+
+    #[test]
+    fn test_published_schema_is_up_to_date() {
+        // regenerate the file from schema_json when the payload changes
+        let published = include_str!("../../schema/webhook-payload.v1.json");
+        assert_eq!(published, schema_json());
+    }
+}